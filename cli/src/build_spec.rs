@@ -1,20 +1,20 @@
 use clap::ArgMatches;
 use config_env::ConfigEnv;
-use core::{BytesObject, Context, Object, RelativePath, Resolved, ResolvedByPrefix, Resolver,
-           RpChannel, RpPackage, RpPackageFormat, RpRequiredPackage, RpVersionedPackage, Version};
+use core::{codes, BytesObject, Context, Object, Range, RelativePath, Resolved,
+           ResolvedByPrefix, Resolver, RpFile, RpName, RpPackage, RpPackageFormat,
+           RpRequiredPackage, RpVersionedPackage, Version};
 use core::errors::*;
 use manifest::{self as m, read_manifest, read_manifest_preamble, Lang, Language, Manifest,
                ManifestFile, ManifestPreamble, NoLang, Publish};
-use repository::{index_from_path, index_from_url, objects_from_path, objects_from_url, Index,
-                 IndexConfig, NoIndex, NoObjects, Objects, ObjectsConfig, Paths, Repository,
-                 Resolvers};
+use repository::{index_from_path, index_from_url, init_file_index, objects_from_path,
+                 objects_from_url, Deployment, Index, IndexConfig, NoIndex, NoObjects, Objects,
+                 ObjectsConfig, Paths, Repository, Resolvers, SigningKey, Trust};
 use repository_http;
 use semck;
 use std::collections::HashMap;
-use std::fmt;
 use std::fs::File;
-use std::io::{self, Read};
-use std::path::Path;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::sync::Arc;
 use std::time::Duration;
@@ -103,6 +103,32 @@ pub fn repository(manifest: &Manifest) -> Result<Repository> {
         .and_then(|p| p.parent())
         .ok_or_else(|| "no parent path to manifest")?;
 
+    let out = if let Some(vendor) = repository.vendor.as_ref() {
+        debug!("vendor: {}", vendor.display());
+
+        open_file_repository(vendor).chain_err(|| {
+            format!(
+                "failed to open vendored dependencies, try running `reproto vendor`: {}",
+                vendor.display()
+            )
+        })?
+    } else {
+        open_repository(base, repository)?
+    };
+
+    let mut trust = Trust::new();
+
+    for t in &manifest.trust {
+        trust.protect(t.package.clone(), t.keys.clone());
+    }
+
+    Ok(out.with_trust(trust))
+}
+
+/// Open the repository described by the given configuration.
+///
+/// Relative paths are resolved against `base`.
+pub fn open_repository(base: &Path, repository: &m::Repository) -> Result<Repository> {
     let mut repo_dir = None;
     let mut cache_dir = None;
     let mut index = repository.index.clone();
@@ -134,6 +160,37 @@ pub fn repository(manifest: &Manifest) -> Result<Repository> {
     Ok(Repository::new(index, objects))
 }
 
+/// Open a repository stored in a local directory, which is both a file index and the objects
+/// storage.
+pub fn open_file_repository(path: &Path) -> Result<Repository> {
+    let index = index_from_path(path)?;
+    let objects = index.objects_from_index(RelativePath::new(index.objects_url()?))?;
+    Ok(Repository::new(Box::new(index), objects))
+}
+
+/// Open a repository stored in a local directory, initializing it if necessary.
+pub fn file_repository(path: &Path) -> Result<Repository> {
+    init_file_index(path)?;
+    open_file_repository(path)
+}
+
+/// Load the key to sign published packages with, if one is configured.
+///
+/// The `--signing-key` argument takes precedence over the user configuration.
+pub fn signing_key(matches: &ArgMatches) -> Result<Option<SigningKey>> {
+    if let Some(path) = matches.value_of("signing-key") {
+        return Ok(Some(SigningKey::from_path(path)?));
+    }
+
+    if let Some(config_env) = ConfigEnv::new()? {
+        if let Some(path) = config_env.signing_key {
+            return Ok(Some(SigningKey::from_path(path)?));
+        }
+    }
+
+    Ok(None)
+}
+
 pub fn path_resolver(manifest: &Manifest) -> Result<Option<Box<Resolver>>> {
     if manifest.paths.is_empty() {
         return Ok(None);
@@ -163,12 +220,122 @@ pub fn resolvers(manifest: &Manifest) -> Result<Box<Resolver>> {
     Ok(Box::new(Resolvers::new(resolvers)))
 }
 
+/// Setup resolvers for a member of a workspace.
+///
+/// Members resolve each other as local packages, through their combined paths and published
+/// packages.
+pub fn workspace_resolvers(workspace: &Manifest, members: &[Manifest]) -> Result<Resolvers> {
+    let mut resolvers: Vec<Box<Resolver>> = Vec::new();
+
+    resolvers.push(Box::new(repository(workspace)?));
+
+    let mut paths = workspace.paths.clone();
+    let mut published = HashMap::new();
+
+    for manifest in Some(workspace).into_iter().chain(members.iter()) {
+        for path in &manifest.paths {
+            if !paths.contains(path) {
+                paths.push(path.clone());
+            }
+        }
+
+        for publish in &manifest.publish {
+            published.insert(publish.package.clone(), publish.version.clone());
+        }
+    }
+
+    if !paths.is_empty() {
+        resolvers.push(Box::new(Paths::new(paths, published)));
+    }
+
+    Ok(Resolvers::new(resolvers))
+}
+
+/// Read all the members of a workspace.
+///
+/// Every member must specify its own language.
+pub fn workspace_members(workspace: &Manifest) -> Result<Vec<Manifest>> {
+    let mut members = Vec::new();
+
+    for member in &workspace.workspace.members {
+        let path = if member.is_dir() {
+            member.join(MANIFEST_NAME)
+        } else {
+            member.to_owned()
+        };
+
+        if !path.is_file() {
+            return Err(format!("workspace member: no such manifest: {}", path.display()).into());
+        }
+
+        debug!("reading workspace member: {}", path.display());
+        let reader = File::open(&path)?;
+
+        let preamble = read_manifest_preamble(&path, reader)
+            .map_err(|e| format!("{}: {}", path.display(), e.display()))?;
+
+        let language = preamble.language.ok_or_else(|| {
+            format!(
+                "{}: workspace members must specify a language",
+                path.display()
+            )
+        })?;
+
+        let lang = convert_lang(language);
+
+        let manifest = read_manifest(lang.as_ref(), preamble)
+            .map_err(|e| format!("{}: {}", path.display(), e.display()))?;
+
+        if !manifest.workspace.members.is_empty() {
+            return Err(format!("{}: nested workspaces are not supported", path.display()).into());
+        }
+
+        // the repository of the workspace is used for every member, so don't silently ignore
+        // configuration which would only apply to this member.
+        let repository = &manifest.repository;
+
+        if repository.no_repository || repository.index.is_some() || repository.objects.is_some()
+            || repository.vendor.is_some()
+        {
+            return Err(format!(
+                "{}: workspace members can't configure a repository, use the workspace manifest",
+                path.display()
+            ).into());
+        }
+
+        if !manifest.trust.is_empty() {
+            return Err(format!(
+                "{}: workspace members can't configure trust, use the workspace manifest",
+                path.display()
+            ).into());
+        }
+
+        members.push(manifest);
+    }
+
+    Ok(members)
+}
+
+/// Paths which are inputs when building the given manifest.
+pub fn watched_paths(manifest: &Manifest) -> Vec<PathBuf> {
+    let mut out = Vec::new();
+    out.extend(manifest.path.iter().cloned());
+    out.extend(manifest.paths.iter().cloned());
+    out.extend(manifest.files.iter().map(|f| f.path.clone()));
+    out
+}
+
+/// Path to the manifest, as specified through arguments.
+pub fn manifest_path<'a>(matches: &'a ArgMatches) -> &'a Path {
+    matches
+        .value_of("manifest-path")
+        .map(Path::new)
+        .unwrap_or_else(|| Path::new(MANIFEST_NAME))
+}
+
 /// Read the first part of the manifest, to determine the language used.
 pub fn manifest_preamble<'a>(matches: &ArgMatches<'a>) -> Result<ManifestPreamble> {
-    let manifest_path = matches
-        .value_of("manifest-path")
-        .map::<Result<&Path>, _>(|p| Ok(Path::new(p)))
-        .unwrap_or_else(|| Ok(Path::new(MANIFEST_NAME)))?;
+    let manifest_path = manifest_path(matches);
 
     if !manifest_path.is_file() {
         return Ok(ManifestPreamble::new(None, Some(manifest_path)));
@@ -204,6 +371,16 @@ pub fn manifest<'a>(
 /// Setup environment.
 pub fn environment(lang: &Lang, ctx: Rc<Context>, manifest: &Manifest) -> Result<Environment> {
     let resolvers = resolvers(manifest)?;
+    environment_with(lang, ctx, manifest, resolvers)
+}
+
+/// Setup environment, using the given resolvers.
+pub fn environment_with(
+    lang: &Lang,
+    ctx: Rc<Context>,
+    manifest: &Manifest,
+    resolvers: Box<Resolver>,
+) -> Result<Environment> {
     let package_prefix = manifest.package_prefix.clone();
 
     let mut env = lang.into_env(ctx, package_prefix, resolvers);
@@ -414,48 +591,225 @@ where
     Ok(results)
 }
 
+/// How to bump the version of packages, from `--bump`.
+#[derive(Debug, Clone, Copy)]
+pub enum BumpMode {
+    /// Use the smallest bump which permits every change since the latest deployment.
+    Auto,
+    /// Always use the given bump.
+    Fixed(semck::Bump),
+}
+
+/// Get how to bump the version of packages from `--bump`, if specified.
+pub fn bump_mode(matches: &ArgMatches) -> Result<Option<BumpMode>> {
+    let bump = match matches.value_of("bump") {
+        None => return Ok(None),
+        Some("auto") => BumpMode::Auto,
+        Some("patch") => BumpMode::Fixed(semck::Bump::Patch),
+        Some("minor") => BumpMode::Fixed(semck::Bump::Minor),
+        Some("major") => BumpMode::Fixed(semck::Bump::Major),
+        Some(bump) => return Err(format!("--bump: unsupported value `{}`", bump).into()),
+    };
+
+    Ok(Some(bump))
+}
+
+/// Find the latest deployment of a package which is not a pre-release.
+///
+/// If a version is specified, only deployments up to and including it are considered.
+fn latest_deployment(
+    repository: &Repository,
+    package: &RpPackage,
+    version: Option<&Version>,
+) -> Result<Option<Deployment>> {
+    let deployment = repository
+        .all(package)?
+        .into_iter()
+        .filter(|d| version.map(|v| d.version <= *v).unwrap_or(true))
+        .filter(|d| !d.version.is_prerelease())
+        .last();
+
+    Ok(deployment)
+}
+
+/// Load the specification of a deployment.
+fn load_deployment(
+    repository: &mut Repository,
+    env: &mut Environment,
+    package: &RpPackage,
+    d: &Deployment,
+) -> Result<RpFile> {
+    let previous = repository
+        .get_object(d)?
+        .ok_or_else(|| format!("No object found for deployment: {:?}", d))?;
+
+    let name = RpPackageFormat(package, Some(&d.version)).to_string();
+    let previous = previous.with_name(name);
+
+    let package_from = RpVersionedPackage::new(package.clone(), Some(d.version.clone()));
+    env.load_object(previous.as_ref(), &package_from)
+}
+
+/// Load every published version of a package before the given version, from the oldest to the
+/// newest.
+fn load_history(
+    repository: &mut Repository,
+    env: &mut Environment,
+    package: &RpPackage,
+    version: &Version,
+) -> Result<Vec<RpFile>> {
+    let deployments = repository
+        .all(package)?
+        .into_iter()
+        .filter(|d| d.version < *version)
+        .filter(|d| !d.version.is_prerelease())
+        .collect::<Vec<_>>();
+
+    let mut history = Vec::new();
+
+    for d in &deployments {
+        history.push(load_deployment(repository, env, package, d)?);
+    }
+
+    Ok(history)
+}
+
+/// Find the next version to publish the matched packages as.
+///
+/// Every package is compared against its latest deployment, and the highest resulting version is
+/// used so that the version is legal for all of them.
+/// Returns `None` if none of the packages have been published before.
+pub fn next_version(
+    repository: &mut Repository,
+    env: &mut Environment,
+    matches: &[Match],
+    bump: BumpMode,
+    mode: semck::Mode,
+) -> Result<Option<Version>> {
+    let mut next: Option<Version> = None;
+
+    for &Match(_, ref object, ref package) in matches {
+        let d = match latest_deployment(repository, package, None)? {
+            Some(d) => d,
+            None => continue,
+        };
+
+        let bump = match bump {
+            BumpMode::Fixed(bump) => bump,
+            BumpMode::Auto => {
+                let file_from = load_deployment(repository, env, package, &d)?;
+                let package_to = RpVersionedPackage::new(package.clone(), None);
+                let file_to = env.load_object(object.as_ref(), &package_to)?;
+                semck::suggest(mode, &file_from, &file_to)?
+            }
+        };
+
+        let version = bump.apply(&d.version);
+        debug!("{}: {} bump from {} to {}", package, bump, d.version, version);
+
+        if next.as_ref().map(|n| *n < version).unwrap_or(true) {
+            next = Some(version);
+        }
+    }
+
+    Ok(next)
+}
+
+/// Write the given versions to the `[publish]` section of the manifest at the given path.
+pub fn write_publish_versions(path: &Path, versions: &[(RpPackage, Version)]) -> Result<()> {
+    let mut content = String::new();
+
+    if path.is_file() {
+        File::open(path)?.read_to_string(&mut content)?;
+    }
+
+    for &(ref package, ref version) in versions {
+        content = m::update_publish_version(&content, package, version);
+    }
+
+    debug!("writing manifest: {}", path.display());
+    File::create(path)?.write_all(content.as_bytes())?;
+    Ok(())
+}
+
+/// Get the kind of compatibility to check for from `--mode`.
+pub fn semck_mode(matches: &ArgMatches) -> Result<semck::Mode> {
+    let mode = match matches.value_of("mode") {
+        None | Some("both") => semck::Mode::Both,
+        Some("wire") => semck::Mode::Wire,
+        Some("source") => semck::Mode::Source,
+        Some(mode) => return Err(format!("--mode: unsupported value `{}`", mode).into()),
+    };
+
+    Ok(mode)
+}
+
 pub fn semck_check(
     ctx: &Context,
     errors: &mut Vec<Error>,
     repository: &mut Repository,
     env: &mut Environment,
     m: &Match,
+    mode: semck::Mode,
+    suggest_reserved: bool,
 ) -> Result<()> {
     let Match(ref version, ref object, ref package) = *m;
 
+    let package_to = RpVersionedPackage::new(package.clone(), Some(version.clone()));
+    let file_to = env.load_object(object.as_ref(), &package_to)?;
+
+    let mut violations = Vec::new();
+
     // perform semck verification
-    if let Some(d) = repository
-        .all(package)?
-        .into_iter()
-        .filter(|d| d.version <= *version && !d.version.is_prerelease())
-        .last()
-    {
+    if let Some(d) = latest_deployment(repository, package, Some(version))? {
         debug!("Checking semantics of {} -> {}", d.version, version);
 
-        let previous = repository
-            .get_object(&d)?
-            .ok_or_else(|| format!("No object found for deployment: {:?}", d))?;
+        let file_from = load_deployment(repository, env, package, &d)?;
 
-        let name = RpPackageFormat(package, Some(&d.version)).to_string();
-        let previous = previous.with_name(name);
+        violations.extend(semck::check(
+            mode,
+            (&d.version, &file_from),
+            (&version, &file_to),
+        )?);
 
-        let package_from = RpVersionedPackage::new(package.clone(), Some(d.version.clone()));
-        let file_from = env.load_object(previous.as_ref(), &package_from)?;
-
-        let package_to = RpVersionedPackage::new(package.clone(), Some(version.clone()));
-        let file_to = env.load_object(object.as_ref(), &package_to)?;
+        if version.major > d.version.major {
+            info!(
+                "{}: new major version, see `reproto diff --migration {} {} {}` for what broke",
+                package, package, d.version, version
+            );
+        }
+    }
 
-        let violations = semck::check((&d.version, &file_from), (&version, &file_to))?;
+    // names removed in any earlier version, or reserved, may never be reused.
+    let history = load_history(repository, env, package, version)?;
+    let history = history.iter().collect::<Vec<_>>();
+
+    violations.extend(semck::reserved::check(mode, &history, &file_to)?);
+
+    if suggest_reserved {
+        for r in semck::reserved::suggest(&history, &file_to) {
+            ctx.report()
+                .warn(
+                    codes::RESERVE_REMOVED,
+                    r.pos,
+                    format!(
+                        "{}: removed fields are not reserved, add `#[reserved({})]`",
+                        r.decl,
+                        r.names.join(", ")
+                    ),
+                )
+                .close();
+        }
+    }
 
-        if !violations.is_empty() {
-            errors.push(Error::new(format!(
-                "Encountered {} semck violation(s)",
-                violations.len()
-            )));
+    if !violations.is_empty() {
+        errors.push(Error::new(format!(
+            "Encountered {} semck violation(s)",
+            violations.len()
+        )));
 
-            for v in violations {
-                handle_violation(ctx, v)?;
-            }
+        for v in violations {
+            handle_violation(ctx, v)?;
         }
     }
 
@@ -464,141 +818,54 @@ pub fn semck_check(
     fn handle_violation(ctx: &Context, violation: semck::Violation) -> Result<()> {
         use semck::Violation::*;
 
-        match violation {
-            DeclRemoved(c, reg) => {
-                ctx.report()
-                    .err(reg, format!("{}: declaration removed", c.describe()))
-                    .close();
-            }
-            DeclAdded(c, reg) => {
-                ctx.report()
-                    .err(reg, format!("{}: declaration added", c.describe()))
-                    .close();
-            }
-            RemoveField(c, field) => {
-                ctx.report()
-                    .err(field, format!("{}: field removed", c.describe()))
-                    .close();
+        // removals are reported where the item used to be, everything else where it is now.
+        let (code, removed, info) = match violation {
+            DeclRemoved(..) => (codes::DECL_REMOVED, true, "removed from here"),
+            DeclAdded(..) => (codes::DECL_ADDED, false, "added to here"),
+            RemoveField(..) => (codes::FIELD_REMOVED, true, "removed from here"),
+            RemoveVariant(..) => (codes::VARIANT_REMOVED, true, "removed from here"),
+            RemoveSubType(..) => (codes::SUB_TYPE_REMOVED, true, "removed from here"),
+            AddField(..) => (codes::FIELD_ADDED, false, "added to here"),
+            AddVariant(..) => (codes::VARIANT_ADDED, false, "added to here"),
+            FieldTypeChange(..) => (codes::FIELD_TYPE_CHANGED, false, "from here"),
+            FieldNameChange(..) => (codes::FIELD_NAME_CHANGED, false, "from here"),
+            VariantOrdinalChange(..) => (codes::VARIANT_ORDINAL_CHANGED, false, "from here"),
+            FieldRequiredChange(..) => (codes::FIELD_REQUIRED_CHANGED, false, "from here"),
+            AddRequiredField(..) => (codes::REQUIRED_FIELD_ADDED, false, "added to here"),
+            FieldModifierChange(..) => (codes::FIELD_MODIFIER_CHANGED, false, "from here"),
+            AddEndpoint(..) => (codes::ENDPOINT_ADDED, false, "added to here"),
+            RemoveEndpoint(..) => (codes::ENDPOINT_REMOVED, true, "removed from here"),
+            EndpointRequestChange(..) => (codes::ENDPOINT_REQUEST_CHANGED, false, "from here"),
+            EndpointResponseChange(..) => (codes::ENDPOINT_RESPONSE_CHANGED, false, "from here"),
+            SubTypeNameChange(..) => (codes::SUB_TYPE_NAME_CHANGED, false, "from here"),
+            SubTypeStrategyChange(..) => (codes::SUB_TYPE_STRATEGY_CHANGED, false, "from here"),
+            TupleMemberMoved(..) => (codes::TUPLE_MEMBER_MOVED, false, "from here"),
+            EndpointNameChange(..) => (codes::ENDPOINT_NAME_CHANGED, false, "from here"),
+            EndpointHttpPathChange(..) => (codes::ENDPOINT_HTTP_PATH_CHANGED, false, "from here"),
+            EndpointHttpMethodChange(..) => {
+                (codes::ENDPOINT_HTTP_METHOD_CHANGED, false, "from here")
             }
-            RemoveVariant(c, field) => {
-                ctx.report()
-                    .err(field, format!("{}: variant removed", c.describe()))
-                    .close();
-            }
-            AddField(c, field) => {
-                ctx.report()
-                    .err(field, format!("{}: field added", c.describe()))
-                    .close();
-            }
-            AddVariant(c, field) => {
-                ctx.report()
-                    .err(field, format!("{}: variant added", c.describe()))
-                    .close();
-            }
-            FieldTypeChange(c, from_type, from, to_type, to) => {
-                ctx.report()
-                    .err(
-                        to,
-                        format!("{}: type changed to `{}`", c.describe(), to_type),
-                    )
-                    .err(from, format!("from `{}`", from_type))
-                    .close();
-            }
-            FieldNameChange(c, from_name, from, to_name, to) => {
-                ctx.report()
-                    .err(
-                        to,
-                        format!("{}: name changed to `{}`", c.describe(), to_name),
-                    )
-                    .err(from, format!("from `{}`", from_name))
-                    .close();
-            }
-            VariantOrdinalChange(c, from_ordinal, from, to_ordinal, to) => {
-                ctx.report()
-                    .err(
-                        to,
-                        format!("{}: ordinal changed to `{}`", c.describe(), to_ordinal),
-                    )
-                    .err(from, format!("from `{}`", from_ordinal))
-                    .close();
-            }
-            FieldRequiredChange(c, from, to) => {
-                ctx.report()
-                    .err(
-                        to,
-                        format!("{}: field changed to be required`", c.describe(),),
-                    )
-                    .err(from, "from here")
-                    .close();
-            }
-            AddRequiredField(c, field) => {
-                ctx.report()
-                    .err(field, format!("{}: required field added", c.describe(),))
-                    .close();
-            }
-            FieldModifierChange(c, from, to) => {
-                ctx.report()
-                    .err(to, format!("{}: field modifier changed", c.describe(),))
-                    .err(from, "from here")
-                    .close();
-            }
-            AddEndpoint(c, pos) => {
-                ctx.report()
-                    .err(pos, format!("{}: endpoint added", c.describe()))
-                    .close();
-            }
-            RemoveEndpoint(c, pos) => {
-                ctx.report()
-                    .err(pos, format!("{}: endpoint removed", c.describe()))
-                    .close();
-            }
-            EndpointRequestChange(c, from_channel, from, to_channel, to) => {
-                ctx.report()
-                    .err(
-                        to,
-                        format!(
-                            "{}: request type changed to `{}`",
-                            c.describe(),
-                            FmtChannel(to_channel.as_ref())
-                        ),
-                    )
-                    .err(
-                        from,
-                        format!("from `{}`", FmtChannel(from_channel.as_ref())),
-                    )
-                    .close();
-            }
-            EndpointResponseChange(c, from_channel, from, to_channel, to) => {
-                ctx.report()
-                    .err(
-                        to,
-                        format!(
-                            "{}: response type changed to `{}`",
-                            c.describe(),
-                            FmtChannel(to_channel.as_ref())
-                        ),
-                    )
-                    .err(
-                        from,
-                        format!("from `{}`", FmtChannel(from_channel.as_ref())),
-                    )
-                    .close();
-            }
-        }
+            EndpointHttpBodyChange(..) => (codes::ENDPOINT_HTTP_BODY_CHANGED, false, "from here"),
+            NameReused(..) => (codes::NAME_REUSED, false, "removed or reserved here"),
+        };
 
-        return Ok(());
+        let message = format!("{}: {}", violation.component().describe(), violation.message());
 
-        /// Helper struct to display information on channels.
-        struct FmtChannel<'a>(Option<&'a RpChannel>);
+        let (primary, secondary) = match violation.positions() {
+            (Some(from), Some(to)) if removed => (from, Some(to)),
+            (Some(from), Some(to)) => (to, Some(from)),
+            (Some(pos), None) | (None, Some(pos)) => (pos, None),
+            (None, None) => return Err(message.into()),
+        };
 
-        impl<'a> fmt::Display for FmtChannel<'a> {
-            fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-                match self.0 {
-                    None => write!(fmt, "*empty*"),
-                    Some(channel) => write!(fmt, "{}", channel),
-                }
-            }
+        let mut report = ctx.report().err(code, primary.clone_error_pos(), message);
+
+        if let Some(secondary) = secondary {
+            report = report.info(secondary.clone_error_pos(), info);
         }
+
+        report.close();
+        Ok(())
     }
 }
 
@@ -634,3 +901,62 @@ pub fn simple_config(ctx: &Rc<Context>, matches: &ArgMatches) -> Result<(Manifes
 
     Ok((manifest, env))
 }
+
+/// Parse a declaration name like `foo.bar::Baz::Inner` into its package and path.
+pub fn parse_type_name(input: &str) -> Result<(RpPackage, Vec<String>)> {
+    let mut parts = input.split("::");
+
+    let package = match parts.next() {
+        Some(package) if !package.is_empty() => RpPackage::parse(package),
+        _ => return Err(format!("expected `<package>::<name>`, got: {}", input).into()),
+    };
+
+    let parts: Vec<String> = parts.map(ToString::to_string).collect();
+
+    if parts.is_empty() || parts.iter().any(String::is_empty) {
+        return Err(format!("expected `<package>::<name>`, got: {}", input).into());
+    }
+
+    Ok((package, parts))
+}
+
+/// Setup a language-neutral environment which includes the package of the given declaration.
+///
+/// Returns the fully qualified name of the declaration, which can be looked up in the
+/// environment.
+pub fn type_config(
+    ctx: &Rc<Context>,
+    matches: &ArgMatches,
+    type_name: &str,
+) -> Result<(Manifest, Environment, RpName)> {
+    let (package, parts) = parse_type_name(type_name)?;
+
+    let preamble = manifest_preamble(matches)?;
+
+    let lang = preamble
+        .language
+        .map(|l| convert_lang(l))
+        .unwrap_or_else(|| Box::new(NoLang) as Box<Lang>);
+
+    let mut manifest = manifest(lang.as_ref(), matches, preamble)?;
+
+    if !manifest.packages.iter().any(|p| p.package == package) {
+        manifest
+            .packages
+            .push(RpRequiredPackage::new(package.clone(), Range::any()));
+    }
+
+    // payloads are independent of language, so no language-specific naming is applied.
+    let env = environment(&NoLang, ctx.clone(), &manifest)?;
+
+    let versioned = env.for_each_file()
+        .map(|(versioned, _)| versioned)
+        .filter(|versioned| versioned.package == package)
+        .last()
+        .cloned()
+        .ok_or_else(|| format!("package not loaded: {}", package))?;
+
+    let name = RpName::new(None, versioned, parts);
+    env.lookup(&name)?;
+    Ok((manifest, env, name))
+}
@@ -33,6 +33,8 @@ pub struct Config {
     pub repo_dir: Option<PathBuf>,
     /// Objects cache location.
     pub cache_dir: Option<PathBuf>,
+    /// Key used to sign published packages.
+    pub signing_key: Option<PathBuf>,
 }
 
 pub fn read_config<P: AsRef<Path>>(path: P) -> Result<Config> {
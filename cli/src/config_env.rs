@@ -11,6 +11,7 @@ pub struct ConfigEnv {
     pub cache_dir: PathBuf,
     pub index: Option<String>,
     pub objects: Option<String>,
+    pub signing_key: Option<PathBuf>,
 }
 
 impl ConfigEnv {
@@ -23,6 +24,7 @@ impl ConfigEnv {
             let mut cache_dir = reproto_dir.join("cache");
             let mut index = None;
             let mut objects = None;
+            let mut signing_key = None;
 
             if config.is_file() {
                 let config = read_config(&config)?;
@@ -40,6 +42,8 @@ impl ConfigEnv {
                 if let Some(out) = config.repo_dir {
                     repo_dir = out;
                 }
+
+                signing_key = config.signing_key;
             }
 
             return Ok(Some(ConfigEnv {
@@ -48,6 +52,7 @@ impl ConfigEnv {
                 cache_dir: cache_dir,
                 index: index,
                 objects: objects,
+                signing_key: signing_key,
             }));
         }
 
@@ -7,7 +7,9 @@ extern crate serde_derive;
 
 extern crate ansi_term;
 extern crate clap;
+extern crate futures;
 extern crate genco;
+extern crate hyper;
 extern crate reproto_ast as ast;
 extern crate reproto_backend as backend;
 extern crate reproto_backend_csharp as csharp;
@@ -23,12 +25,18 @@ extern crate reproto_backend_swift as swift;
 extern crate reproto_compile as compile;
 extern crate reproto_core as core;
 extern crate reproto_derive as derive;
+extern crate reproto_lint as lint;
 extern crate reproto_manifest as manifest;
+extern crate reproto_mock as mock;
 extern crate reproto_parser as parser;
 extern crate reproto_repository as repository;
 extern crate reproto_repository_http as repository_http;
+extern crate reproto_sample as sample;
 extern crate reproto_semck as semck;
 extern crate reproto_trans as trans;
+extern crate reproto_validate as validate;
+extern crate serde_json;
+extern crate tokio_core;
 extern crate toml;
 extern crate url;
 
@@ -37,3 +45,5 @@ pub mod config;
 pub mod output;
 mod build_spec;
 mod config_env;
+mod serve;
+mod watch;
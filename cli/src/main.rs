@@ -33,6 +33,13 @@ fn setup_opts<'a, 'b>() -> App<'a, 'b> {
                 .long("no-color")
                 .help("Disable colored output"),
         )
+        .arg(
+            Arg::with_name("message-format")
+                .long("message-format")
+                .takes_value(true)
+                .possible_values(&["human", "json"])
+                .help("Format of diagnostics, `json` emits one JSON object per line"),
+        )
 }
 
 /// Configure logging
@@ -55,7 +62,7 @@ fn setup_logger(matches: &clap::ArgMatches, output: &output::Output) -> Result<(
 
 fn guarded_entry(ctx: Rc<Context>, matches: &ArgMatches, output: &output::Output) -> Result<()> {
     setup_logger(matches, output)?;
-    ops::entry(ctx, matches)?;
+    ops::entry(ctx, matches, output)?;
     Ok(())
 }
 
@@ -68,6 +75,8 @@ fn entry(matches: &ArgMatches, output: &output::Output) -> Result<()> {
         ::std::process::exit(1);
     }
 
+    // warnings are reported in the context of successful runs as well.
+    output.handle_context(ctx.as_ref())?;
     Ok(())
 }
 
@@ -79,7 +88,9 @@ fn main() {
     let colored = matches.is_present("color")
         || !matches.is_present("no-color") && atty::is(atty::Stream::Stdout);
 
-    let mut output: Box<output::Output> = if colored {
+    let mut output: Box<output::Output> = if matches.value_of("message-format") == Some("json") {
+        Box::new(output::Json::new(io::stdout()))
+    } else if colored {
         Box::new(output::Colored::new(io::stdout()))
     } else {
         Box::new(output::NonColored::new(io::stdout()))
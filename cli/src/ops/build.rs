@@ -1,11 +1,16 @@
 //! build command
 
-use build_spec::{convert_lang, environment, manifest, manifest_preamble};
+use build_spec::{convert_lang, environment, environment_with, manifest, manifest_path,
+                 manifest_preamble, watched_paths, workspace_members, workspace_resolvers};
 use clap::{App, Arg, ArgMatches, SubCommand};
 use core::Context;
 use core::errors::Result;
-use manifest::Language;
+use manifest::{Language, Manifest, NoLang};
+use output::Output;
+use std::cell::RefCell;
+use std::path::PathBuf;
 use std::rc::Rc;
+use watch::watch;
 
 pub fn options<'a, 'b>() -> App<'a, 'b> {
     let out = SubCommand::with_name("build").about("Build specifications");
@@ -17,24 +22,91 @@ pub fn options<'a, 'b>() -> App<'a, 'b> {
             .help("Language to build for"),
     );
 
+    let out = out.arg(
+        Arg::with_name("watch")
+            .long("watch")
+            .help("Rebuild whenever the manifest or any specification changes"),
+    );
+
     out
 }
 
-pub fn entry(ctx: Rc<Context>, matches: &ArgMatches) -> Result<()> {
+/// Build every member of a workspace, each for its own language and output directory.
+///
+/// All members share the same resolvers, so that the repository is only opened once, but every
+/// member resolves its packages separately.
+/// Each member still needs its own environment, since an environment applies the keywords and
+/// naming of its language when loading files, and is consumed by the backend of that language.
+fn build_workspace(
+    ctx: Rc<Context>,
+    workspace: Manifest,
+    watched: &mut Vec<PathBuf>,
+) -> Result<()> {
+    let members = workspace_members(&workspace)?;
+
+    watched.extend(workspace.workspace.members.iter().cloned());
+
+    for member in &members {
+        watched.extend(watched_paths(member));
+    }
+
+    let resolvers = Rc::new(RefCell::new(workspace_resolvers(&workspace, &members)?));
+    let mut environments = Vec::new();
+
+    // set up all environments before compiling anything, so that errors are reported early.
+    for member in &members {
+        let resolvers = Box::new(resolvers.clone());
+        let env = environment_with(member.lang.as_ref(), ctx.clone(), member, resolvers)?;
+        environments.push(env);
+    }
+
+    for (member, env) in members.into_iter().zip(environments) {
+        if let Some(path) = member.path.as_ref() {
+            info!("building: {}", path.display());
+        }
+
+        let lang = member.lang.copy();
+        lang.compile(ctx.clone(), env, member)?;
+    }
+
+    Ok(())
+}
+
+fn build(ctx: Rc<Context>, matches: &ArgMatches, watched: &mut Vec<PathBuf>) -> Result<()> {
     let preamble = manifest_preamble(matches)?;
 
     let language = preamble
         .language
         .as_ref()
         .cloned()
-        .or_else(|| matches.value_of("lang").and_then(Language::parse))
-        .ok_or_else(|| "no language specified either through manifest or cli (--lang)")?;
+        .or_else(|| matches.value_of("lang").and_then(Language::parse));
 
-    let lang = convert_lang(language);
+    let lang = language
+        .map(convert_lang)
+        .unwrap_or_else(|| Box::new(NoLang));
 
     let manifest = manifest(lang.as_ref(), matches, preamble)?;
+    watched.extend(watched_paths(&manifest));
+
+    if !manifest.workspace.members.is_empty() {
+        return build_workspace(ctx, manifest, watched);
+    }
+
+    if language.is_none() {
+        return Err("no language specified either through manifest or cli (--lang)".into());
+    }
+
     let env = environment(lang.as_ref(), ctx.clone(), &manifest)?;
 
     lang.compile(ctx, env, manifest)?;
     Ok(())
 }
+
+pub fn entry(ctx: Rc<Context>, matches: &ArgMatches, output: &Output) -> Result<()> {
+    if matches.is_present("watch") {
+        let initial = vec![manifest_path(matches).to_owned()];
+        return watch(output, initial, |ctx, watched| build(ctx, matches, watched));
+    }
+
+    build(ctx, matches, &mut Vec::new())
+}
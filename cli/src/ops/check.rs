@@ -1,4 +1,5 @@
-use build_spec::{matches, path_resolver, publish_matches, repository, semck_check, simple_config};
+use build_spec::{matches, path_resolver, publish_matches, repository, semck_check, semck_mode,
+                 simple_config};
 use clap::{App, Arg, ArgMatches, SubCommand};
 use core::{Context, RpRequiredPackage, Version};
 use core::errors::*;
@@ -14,6 +15,20 @@ pub fn options<'a, 'b>() -> App<'a, 'b> {
             .help("Override published version with argument"),
     );
 
+    let out = out.arg(
+        Arg::with_name("mode")
+            .long("mode")
+            .takes_value(true)
+            .possible_values(&["wire", "source", "both"])
+            .help("Compatibility to check for: wire, source, or both (default: both)"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("suggest-reserved")
+            .long("suggest-reserved")
+            .help("Suggest reserving fields which were removed in earlier versions"),
+    );
+
     let out = out.arg(Arg::with_name("package").multiple(true));
 
     out
@@ -52,12 +67,22 @@ pub fn entry(ctx: Rc<Context>, m: &ArgMatches) -> Result<()> {
         &packages,
     )?);
 
+    let mode = semck_mode(m)?;
+    let suggest_reserved = m.is_present("suggest-reserved");
     let mut repository = repository(&manifest)?;
 
     let mut errors = Vec::new();
 
     for m in results {
-        semck_check(&ctx, &mut errors, &mut repository, &mut env, &m)?;
+        semck_check(
+            &ctx,
+            &mut errors,
+            &mut repository,
+            &mut env,
+            &m,
+            mode,
+            suggest_reserved,
+        )?;
     }
 
     if errors.len() > 0 {
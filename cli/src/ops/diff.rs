@@ -0,0 +1,214 @@
+//! Diff command, lists the structural differences between two versions of a package.
+
+use build_spec::{manifest, manifest_preamble, repository, resolvers, semck_mode};
+use clap::{App, Arg, ArgMatches, SubCommand};
+use core::{Context, Object, PathObject, RpFile, RpPackage, RpPackageFormat, RpVersionedPackage,
+           Version};
+use core::errors::*;
+use manifest::{Lang, Manifest, NoLang};
+use repository::Repository;
+use semck::diff::{diff, Change, ChangeKind};
+use semck::migration::migration;
+use serde_json;
+use std::path::Path;
+use std::rc::Rc;
+use trans::Environment;
+
+pub fn options<'a, 'b>() -> App<'a, 'b> {
+    let out = SubCommand::with_name("diff")
+        .about("Show the differences between two versions of a package");
+
+    let out = out.arg(
+        Arg::with_name("package")
+            .required(true)
+            .help("Package to compare"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("from")
+            .required(true)
+            .help("Version to compare from, or path to a specification"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("to")
+            .required(true)
+            .help("Version to compare to, or path to a specification"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("format")
+            .long("format")
+            .takes_value(true)
+            .possible_values(&["text", "markdown", "json"])
+            .help("Format to print the differences in (default: text)"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("migration")
+            .long("migration")
+            .help("Print a markdown migration report of every breaking change instead"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("mode")
+            .long("mode")
+            .takes_value(true)
+            .possible_values(&["wire", "source", "both"])
+            .help("Compatibility to report breaking changes for (default: both)"),
+    );
+
+    out
+}
+
+/// Where to load one side of the diff from.
+struct Source<'a> {
+    manifest: &'a Manifest,
+    /// Repository, only opened when a version needs to be looked up.
+    repository: Option<Repository>,
+}
+
+impl<'a> Source<'a> {
+    /// Load the given version of the package, or the specification at the given path.
+    fn load(&mut self, env: &mut Environment, package: &RpPackage, spec: &str) -> Result<RpFile> {
+        let path = Path::new(spec);
+
+        if path.is_file() {
+            let object = PathObject::new(None, path);
+            let versioned = RpVersionedPackage::new(package.clone(), None);
+            return env.load_object(&object, &versioned);
+        }
+
+        let version = Version::parse(spec)
+            .map_err(|e| format!("`{}` is neither a file, nor a valid version: {}", spec, e))?;
+
+        if self.repository.is_none() {
+            self.repository = Some(repository(self.manifest)?);
+        }
+
+        let repository = self.repository.as_mut().ok_or_else(|| "no repository")?;
+
+        let deployment = repository
+            .all(package)?
+            .into_iter()
+            .find(|d| d.version == version)
+            .ok_or_else(|| format!("no such version of {}: {}", package, version))?;
+
+        let object = repository
+            .get_object(&deployment)?
+            .ok_or_else(|| format!("no object found for deployment: {:?}", deployment))?;
+
+        let object = object.with_name(RpPackageFormat(package, Some(&version)).to_string());
+        let versioned = RpVersionedPackage::new(package.clone(), Some(version));
+        env.load_object(object.as_ref(), &versioned)
+    }
+}
+
+/// Differences, as they are printed in JSON.
+#[derive(Serialize)]
+struct JsonDiff<'a> {
+    package: String,
+    from: &'a str,
+    to: &'a str,
+    changes: &'a [Change],
+}
+
+pub fn entry(ctx: Rc<Context>, matches: &ArgMatches) -> Result<()> {
+    let package = matches.value_of("package").ok_or_else(|| "package: missing")?;
+    let from = matches.value_of("from").ok_or_else(|| "from: missing")?;
+    let to = matches.value_of("to").ok_or_else(|| "to: missing")?;
+    let package = RpPackage::parse(package);
+
+    let preamble = manifest_preamble(matches)?;
+    let manifest = manifest(&NoLang, matches, preamble)?;
+
+    // declarations are compared as written, without any language-specific naming applied.
+    let mut env = NoLang.into_env(ctx.clone(), None, resolvers(&manifest)?);
+
+    let mut source = Source {
+        manifest: &manifest,
+        repository: None,
+    };
+
+    let from_file = source.load(&mut env, &package, from)?;
+    let to_file = source.load(&mut env, &package, to)?;
+
+    if matches.is_present("migration") {
+        let mode = semck_mode(matches)?;
+        let migration = migration(mode, &from_file, &to_file)?;
+        let title = format!("Migrating `{}` from {} to {}", package, from, to);
+        print!("{}", migration.to_markdown(&title)?);
+        return Ok(());
+    }
+
+    let changes = diff(&from_file, &to_file);
+
+    match matches.value_of("format").unwrap_or("text") {
+        "markdown" => print_markdown(&package, from, to, &changes),
+        "json" => {
+            let doc = JsonDiff {
+                package: package.to_string(),
+                from: from,
+                to: to,
+                changes: &changes,
+            };
+
+            let doc = serde_json::to_string_pretty(&doc).map_err(|e| e.to_string())?;
+            println!("{}", doc);
+        }
+        _ => print_text(&changes),
+    }
+
+    Ok(())
+}
+
+fn print_text(changes: &[Change]) {
+    if changes.is_empty() {
+        println!("no changes");
+        return;
+    }
+
+    for change in changes {
+        let sigil = match change.kind {
+            ChangeKind::Added => '+',
+            ChangeKind::Removed => '-',
+            ChangeKind::Changed => '~',
+        };
+
+        println!("{} {}", sigil, change);
+    }
+}
+
+fn print_markdown(package: &RpPackage, from: &str, to: &str, changes: &[Change]) {
+    println!("# Changes to `{}` from {} to {}", package, from, to);
+
+    if changes.is_empty() {
+        println!();
+        println!("No changes.");
+        return;
+    }
+
+    for &(kind, title) in &[
+        (ChangeKind::Added, "Added"),
+        (ChangeKind::Removed, "Removed"),
+        (ChangeKind::Changed, "Changed"),
+    ] {
+        let mut changes = changes.iter().filter(|c| c.kind == kind).peekable();
+
+        if changes.peek().is_none() {
+            continue;
+        }
+
+        println!();
+        println!("## {}", title);
+        println!();
+
+        for change in changes {
+            println!("* {} `{}`", change.item, change.path);
+
+            for detail in &change.details {
+                println!("  * {}", detail);
+            }
+        }
+    }
+}
@@ -1,16 +1,146 @@
 //! Action to build documentation.
 
-use build_spec::simple_config;
-use clap::{App, ArgMatches, SubCommand};
-use core::Context;
+use build_spec::{convert_lang, environment, manifest, manifest_path, manifest_preamble,
+                 repository, watched_paths};
+use clap::{App, Arg, ArgMatches, SubCommand};
+use core::{Context, RpPackageFormat, RpVersionedPackage};
 use core::errors::*;
+use doc::Deprecations;
+use manifest::{Lang, Manifest, NoLang};
+use output::Output;
+use serve::serve;
+use std::collections::BTreeSet;
+use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::rc::Rc;
+use trans::Environment;
+use watch::watch;
+
+/// Default address to serve documentation on.
+const DEFAULT_SERVE: &str = "127.0.0.1:8000";
 
 pub fn options<'a, 'b>() -> App<'a, 'b> {
-    ::doc::shared_options(SubCommand::with_name("doc").about("Generate documentation"))
+    let out = ::doc::shared_options(SubCommand::with_name("doc").about("Generate documentation"));
+
+    let out = out.arg(
+        Arg::with_name("watch")
+            .long("watch")
+            .help("Regenerate whenever the manifest or any specification changes"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("history")
+            .long("history")
+            .help("Also document every published version of the packages, with changelogs"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("serve")
+            .long("serve")
+            .takes_value(true)
+            .min_values(0)
+            .value_name("addr")
+            .help(
+                "Serve a live preview, which is rebuilt whenever the manifest or any \
+                 specification changes (default: 127.0.0.1:8000)",
+            ),
+    );
+
+    out
+}
+
+fn doc(ctx: Rc<Context>, matches: &ArgMatches, watched: &mut Vec<PathBuf>) -> Result<()> {
+    let preamble = manifest_preamble(matches)?;
+
+    let lang = preamble
+        .language
+        .map(|l| convert_lang(l))
+        .unwrap_or_else(|| Box::new(NoLang) as Box<Lang>);
+
+    let manifest = manifest(lang.as_ref(), matches, preamble)?;
+    watched.extend(watched_paths(&manifest));
+
+    let mut env = environment(lang.as_ref(), ctx.clone(), &manifest)?;
+
+    let deprecated = if matches.is_present("history") {
+        import_history(&mut env, &manifest)?
+    } else {
+        Deprecations::new()
+    };
+
+    let out = manifest.output.clone();
+
+    ::doc::compile(ctx, env, matches, manifest, deprecated)?;
+
+    if let Some(out) = out {
+        if !matches.is_present("serve") {
+            println!("Wrote documentation in: {}", out.display());
+        }
+    }
+
+    Ok(())
 }
 
-pub fn entry(ctx: Rc<Context>, matches: &ArgMatches) -> Result<()> {
-    let (manifest, env) = simple_config(&ctx, matches)?;
-    ::doc::compile(env, matches, manifest).map_err(Into::into)
+/// Import every published version of the documented packages from the repository.
+///
+/// Returns the deprecation notices of the imported versions.
+fn import_history(env: &mut Environment, manifest: &Manifest) -> Result<Deprecations> {
+    let mut repository = repository(manifest)?;
+    let mut deprecated = Deprecations::new();
+
+    let packages = env.for_each_file()
+        .map(|(versioned, _)| versioned.package.clone())
+        .collect::<BTreeSet<_>>();
+
+    for package in packages {
+        for d in repository.all(&package)? {
+            if d.version.is_prerelease() {
+                continue;
+            }
+
+            let object = repository
+                .get_object(&d)?
+                .ok_or_else(|| format!("no object found for deployment: {:?}", d))?;
+
+            let object = object.with_name(RpPackageFormat(&package, Some(&d.version)).to_string());
+            let versioned = RpVersionedPackage::new(package.clone(), Some(d.version.clone()));
+
+            debug!("history: {}", versioned);
+            env.import_version(object.as_ref(), versioned.clone())?;
+
+            let message = d.deprecated.clone().or_else(|| {
+                if d.yanked {
+                    Some("this version has been yanked".to_string())
+                } else {
+                    None
+                }
+            });
+
+            if let Some(message) = message {
+                deprecated.insert(versioned, message);
+            }
+        }
+    }
+
+    Ok(deprecated)
+}
+
+pub fn entry(ctx: Rc<Context>, matches: &ArgMatches, output: &Output) -> Result<()> {
+    if matches.is_present("serve") {
+        let addr = matches.value_of("serve").unwrap_or(DEFAULT_SERVE);
+
+        let addr: SocketAddr = addr
+            .parse()
+            .map_err(|e| format!("--serve: bad address `{}`: {}", addr, e))?;
+
+        let initial = vec![manifest_path(matches).to_owned()];
+        return serve(output, &addr, initial, |ctx, watched| doc(ctx, matches, watched));
+    }
+
+    if matches.is_present("watch") {
+        let initial = vec![manifest_path(matches).to_owned()];
+        return watch(output, initial, |ctx, watched| doc(ctx, matches, watched));
+    }
+
+    doc(ctx, matches, &mut Vec::new())
 }
@@ -0,0 +1,37 @@
+//! Explain a diagnostic code.
+
+use clap::{App, Arg, ArgMatches, SubCommand};
+use core::{codes, Context};
+use core::errors::*;
+use std::rc::Rc;
+
+pub fn options<'a, 'b>() -> App<'a, 'b> {
+    let out = SubCommand::with_name("explain").about("Explain a diagnostic code, like `R0001`");
+
+    let out = out.arg(
+        Arg::with_name("code")
+            .help("Code to explain, lists all codes if omitted"),
+    );
+
+    out
+}
+
+pub fn entry(_ctx: Rc<Context>, matches: &ArgMatches) -> Result<()> {
+    let id = match matches.value_of("code") {
+        Some(id) => id,
+        None => {
+            for code in codes::ALL {
+                println!("{}: {}", code.id, code.summary);
+            }
+
+            return Ok(());
+        }
+    };
+
+    let code = codes::lookup(id).ok_or_else(|| format!("no such code: {}", id))?;
+
+    println!("{}: {}", code.id, code.summary);
+    println!();
+    println!("{}", code.explanation);
+    Ok(())
+}
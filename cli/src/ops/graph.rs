@@ -0,0 +1,149 @@
+//! Graph command, prints the dependency graph between packages or declarations.
+
+use build_spec::{environment, manifest, manifest_preamble};
+use clap::{App, Arg, ArgMatches, SubCommand};
+use core::{Context, Graph};
+use core::errors::*;
+use manifest::NoLang;
+use serde_json;
+use std::collections::{BTreeSet, HashMap};
+use std::rc::Rc;
+
+pub fn options<'a, 'b>() -> App<'a, 'b> {
+    let out = SubCommand::with_name("graph").about("Print the dependency graph between packages");
+
+    let out = out.arg(
+        Arg::with_name("decls")
+            .long("decls")
+            .help("Print the graph of references between declarations instead"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("format")
+            .long("format")
+            .takes_value(true)
+            .possible_values(&["dot", "json"])
+            .help("Format to print the graph in (default: dot)"),
+    );
+
+    out
+}
+
+#[derive(Serialize)]
+struct JsonEdge<'a> {
+    from: &'a str,
+    to: &'a str,
+    /// If the edge is part of a cycle.
+    cycle: bool,
+}
+
+#[derive(Serialize)]
+struct JsonGraph<'a> {
+    nodes: &'a BTreeSet<String>,
+    edges: Vec<JsonEdge<'a>>,
+    cycles: &'a [Vec<String>],
+}
+
+pub fn entry(ctx: Rc<Context>, matches: &ArgMatches) -> Result<()> {
+    let preamble = manifest_preamble(matches)?;
+    let manifest = manifest(&NoLang, matches, preamble)?;
+    let env = environment(&NoLang, ctx.clone(), &manifest)?;
+
+    let decls = matches.is_present("decls");
+    let mut graph = Graph::default();
+
+    for (package, file) in env.for_each_file() {
+        if !decls {
+            graph.node(package.to_string());
+
+            for used in &file.uses {
+                graph.edge(package.to_string(), used.to_string());
+            }
+
+            continue;
+        }
+
+        for decl in file.for_each_decl() {
+            graph.node(decl.name().qualified());
+        }
+
+        for reference in file.references() {
+            graph.edge(reference.owner.qualified(), reference.name.qualified());
+        }
+    }
+
+    let cycles = graph.cycles();
+
+    if !decls {
+        for cycle in &cycles {
+            warn!("import cycle between packages: {}", cycle.join(", "));
+        }
+    }
+
+    // map every node to the cycle it is part of, if any.
+    let in_cycle: HashMap<&str, usize> = cycles
+        .iter()
+        .enumerate()
+        .flat_map(|(i, cycle)| cycle.iter().map(move |n| (n.as_str(), i)))
+        .collect();
+
+    let is_cycle_edge = |from: &str, to: &str| match (in_cycle.get(from), in_cycle.get(to)) {
+        (Some(a), Some(b)) => a == b,
+        _ => false,
+    };
+
+    match matches.value_of("format").unwrap_or("dot") {
+        "json" => {
+            let mut edges = Vec::new();
+
+            for (from, to) in &graph.edges {
+                for to in to {
+                    edges.push(JsonEdge {
+                        from: from,
+                        to: to,
+                        cycle: is_cycle_edge(from.as_str(), to.as_str()),
+                    });
+                }
+            }
+
+            let doc = JsonGraph {
+                nodes: &graph.nodes,
+                edges: edges,
+                cycles: &cycles,
+            };
+
+            let doc = serde_json::to_string_pretty(&doc).map_err(|e| e.to_string())?;
+            println!("{}", doc);
+        }
+        _ => {
+            println!("digraph reproto {{");
+
+            for node in &graph.nodes {
+                if in_cycle.contains_key(node.as_str()) {
+                    println!("  {} [color=red];", quote(node));
+                } else {
+                    println!("  {};", quote(node));
+                }
+            }
+
+            for (from, to) in &graph.edges {
+                for to in to {
+                    if is_cycle_edge(from.as_str(), to.as_str()) {
+                        println!("  {} -> {} [color=red];", quote(from), quote(to));
+                    } else {
+                        println!("  {} -> {};", quote(from), quote(to));
+                    }
+                }
+            }
+
+            println!("}}");
+        }
+    }
+
+    return Ok(());
+
+    /// Quote an identifier for DOT.
+    fn quote(id: &str) -> String {
+        format!("\"{}\"", id.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
@@ -82,6 +82,7 @@ pub fn entry(ctx: Rc<Context>, matches: &ArgMatches) -> Result<()> {
             package.join("/")
         )?;
         writeln!(manifest, "\"{}\" = \"*\"", package.join("."))?;
+        manifest.flush()?;
     }
 
     let example = package
@@ -101,6 +102,7 @@ pub fn entry(ctx: Rc<Context>, matches: &ArgMatches) -> Result<()> {
         info!("Writing: {}", example.display());
         let mut example = handle.create(&example)?;
         example.write_all(EXAMPLE)?;
+        example.flush()?;
     }
 
     Ok(())
@@ -0,0 +1,132 @@
+//! Lint command, checks that specifications follow the configured conventions.
+
+use build_spec::{convert_lang, environment, manifest, manifest_preamble};
+use clap::{App, Arg, ArgMatches, SubCommand};
+use core::{Context, RpPackage};
+use core::errors::*;
+use lint::{Level, Linter, Summary};
+use manifest::{LintLevel, Manifest, NoLang};
+use std::collections::HashSet;
+use std::rc::Rc;
+
+pub fn options<'a, 'b>() -> App<'a, 'b> {
+    let out = SubCommand::with_name("lint").about("Check that specifications follow conventions");
+
+    let out = out.arg(
+        Arg::with_name("allow")
+            .long("allow")
+            .short("A")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)
+            .help("Allow the given lint rule"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("warn")
+            .long("warn")
+            .short("W")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)
+            .help("Warn about violations of the given lint rule"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("deny")
+            .long("deny")
+            .short("D")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)
+            .help("Treat violations of the given lint rule as errors"),
+    );
+
+    out
+}
+
+/// Setup the linter from the manifest.
+///
+/// Levels given as arguments take precedence over the ones in the manifest.
+fn linter(manifest: &Manifest, matches: &ArgMatches) -> Result<Linter> {
+    let mut linter = Linter::builtin();
+
+    for (name, level) in &manifest.lint.levels {
+        let level = match *level {
+            LintLevel::Allow => Level::Allow,
+            LintLevel::Warn => Level::Warn,
+            LintLevel::Deny => Level::Deny,
+        };
+
+        linter
+            .set_level(name.as_str(), level)
+            .chain_err(|| "bad [lint] section in manifest")?;
+    }
+
+    for &(arg, level) in &[
+        ("allow", Level::Allow),
+        ("warn", Level::Warn),
+        ("deny", Level::Deny),
+    ] {
+        for name in matches.values_of(arg).into_iter().flat_map(|it| it) {
+            linter.set_level(name, level)?;
+        }
+    }
+
+    Ok(linter)
+}
+
+pub fn entry(ctx: Rc<Context>, matches: &ArgMatches) -> Result<()> {
+    let preamble = manifest_preamble(matches)?;
+
+    let lang = preamble
+        .language
+        .as_ref()
+        .map(|l| convert_lang(*l))
+        .unwrap_or_else(|| Box::new(NoLang));
+
+    let manifest = manifest(lang.as_ref(), matches, preamble)?;
+    let linter = linter(&manifest, matches)?;
+
+    // specifications are linted as written, without any language-specific naming applied.
+    let env = environment(&NoLang, ctx.clone(), &manifest)?;
+
+    // only lint what the manifest builds, not its dependencies.
+    let mut targets = HashSet::new();
+
+    targets.extend(manifest.packages.iter().map(|p| p.package.clone()));
+    targets.extend(
+        manifest
+            .files
+            .iter()
+            .map(|f| f.package.clone().unwrap_or_else(RpPackage::empty)),
+    );
+
+    if targets.is_empty() {
+        targets.insert(RpPackage::empty());
+    }
+
+    let mut summary = Summary::default();
+
+    for (package, file) in env.for_each_file() {
+        if !targets.contains(&package.package) {
+            continue;
+        }
+
+        summary.merge(linter.lint_file(&ctx, file));
+    }
+
+    if summary.errors > 0 {
+        return Err(format!(
+            "lint failed with {} error(s) and {} warning(s)",
+            summary.errors, summary.warnings
+        ).into());
+    }
+
+    // warnings in the context are reported after the command has completed.
+    if summary.warnings > 0 {
+        warn!("lint: {} warning(s)", summary.warnings);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,82 @@
+//! Mock command, serves the HTTP endpoints of services with generated responses.
+
+use build_spec::{environment, manifest, manifest_preamble};
+use clap::{App, Arg, ArgMatches, SubCommand};
+use core::Context;
+use core::errors::*;
+use manifest::NoLang;
+use mock::Mock;
+use sample::Sampler;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::rc::Rc;
+
+pub fn options<'a, 'b>() -> App<'a, 'b> {
+    let out = SubCommand::with_name("mock").about("Serve a mock HTTP server for services");
+
+    let out = out.arg(
+        Arg::with_name("listen")
+            .long("listen")
+            .takes_value(true)
+            .help("Address to listen on (default: 127.0.0.1:8080)"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("fixtures")
+            .long("fixtures")
+            .takes_value(true)
+            .help("Directory with fixtures to respond with, like `<Service>/<endpoint>.json`"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("seed")
+            .long("seed")
+            .takes_value(true)
+            .help("Seed to generate responses from (default: random)"),
+    );
+
+    out
+}
+
+pub fn entry(ctx: Rc<Context>, matches: &ArgMatches) -> Result<()> {
+    let listen = matches.value_of("listen").unwrap_or("127.0.0.1:8080");
+
+    let listen: SocketAddr = listen
+        .parse()
+        .map_err(|e| format!("--listen: bad address `{}`: {}", listen, e))?;
+
+    let seed = match matches.value_of("seed") {
+        Some(seed) => seed
+            .parse::<u64>()
+            .map_err(|e| format!("--seed: bad value `{}`: {}", seed, e))?,
+        None => Sampler::seed_from_time()?,
+    };
+
+    let preamble = manifest_preamble(matches)?;
+    let manifest = manifest(&NoLang, matches, preamble)?;
+
+    if manifest.packages.is_empty() {
+        return Err("no packages to mock, specify one with `--package`".into());
+    }
+
+    let packages: Vec<_> = manifest.packages.iter().map(|p| p.package.clone()).collect();
+
+    // requests and responses are independent of language, so no language-specific naming is
+    // applied.
+    let env = environment(&NoLang, ctx.clone(), &manifest)?;
+
+    let mut mock = Mock::new(env, &packages)?.with_seed(seed);
+
+    if let Some(fixtures) = matches.value_of("fixtures") {
+        let fixtures = Path::new(fixtures);
+
+        if !fixtures.is_dir() {
+            return Err(format!("--fixtures: no such directory: {}", fixtures.display()).into());
+        }
+
+        mock = mock.with_fixtures(fixtures.to_owned());
+    }
+
+    info!("seed: {}", seed);
+    mock.serve(&listen)
+}
@@ -1,15 +1,26 @@
 mod build;
 mod doc;
+mod explain;
 mod publish;
 mod repo;
 mod update;
+mod vendor;
 mod check;
 mod derive;
 mod init;
+mod lint;
+mod validate;
+mod sample;
+mod mock;
+mod diff;
+mod graph;
+mod refs;
+mod version;
 
 use clap::{App, Arg, ArgMatches};
 use core::Context;
 use core::errors::*;
+use output::Output;
 use std::rc::Rc;
 
 pub fn base_args<'a, 'b>(out: App<'a, 'b>) -> App<'a, 'b> {
@@ -117,27 +128,47 @@ pub fn options<'a, 'b>(out: App<'a, 'b>) -> App<'a, 'b> {
     let out = out.subcommand(build_args(build::options()));
     let out = out.subcommand(build_args(doc::options()));
     let out = out.subcommand(base_args(check::options()));
+    let out = out.subcommand(base_args(diff::options()));
+    let out = out.subcommand(build_args(lint::options()));
+    let out = out.subcommand(build_args(validate::options()));
+    let out = out.subcommand(build_args(sample::options()));
+    let out = out.subcommand(build_args(mock::options()));
+    let out = out.subcommand(build_args(graph::options()));
+    let out = out.subcommand(build_args(refs::options()));
     let out = out.subcommand(base_args(publish::options()));
     let out = out.subcommand(base_args(update::options()));
     let out = out.subcommand(base_args(repo::options()));
+    let out = out.subcommand(build_args(vendor::options()));
+    let out = out.subcommand(version::options());
     let out = out.subcommand(derive::options());
     let out = out.subcommand(init::options());
+    let out = out.subcommand(explain::options());
     out
 }
 
-pub fn entry(ctx: Rc<Context>, matches: &ArgMatches) -> Result<()> {
+pub fn entry(ctx: Rc<Context>, matches: &ArgMatches, output: &Output) -> Result<()> {
     let (name, matches) = matches.subcommand();
     let matches = matches.ok_or_else(|| "no subcommand")?;
 
     match name {
-        "build" => return build::entry(ctx, matches),
+        "build" => return build::entry(ctx, matches, output),
         "check" => return check::entry(ctx, matches),
-        "doc" => return doc::entry(ctx, matches),
+        "diff" => return diff::entry(ctx, matches),
+        "lint" => return lint::entry(ctx, matches),
+        "validate" => return validate::entry(ctx, matches),
+        "sample" => return sample::entry(ctx, matches),
+        "mock" => return mock::entry(ctx, matches),
+        "graph" => return graph::entry(ctx, matches),
+        "refs" => return refs::entry(ctx, matches),
+        "doc" => return doc::entry(ctx, matches, output),
         "update" => return update::entry(ctx, matches),
         "publish" => return publish::entry(ctx, matches),
         "repo" => return repo::entry(ctx, matches),
+        "version" => return version::entry(ctx, matches),
         "derive" => return derive::entry(ctx, matches),
         "init" => return init::entry(ctx, matches),
+        "explain" => return explain::entry(ctx, matches),
+        "vendor" => return vendor::entry(ctx, matches),
         _ => {}
     }
 
@@ -1,8 +1,10 @@
-use build_spec::{matches, path_resolver, publish_matches, repository, semck_check, simple_config,
-                 Match};
+use build_spec::{bump_mode, manifest_path, matches, next_version, path_resolver, publish_matches,
+                 repository, semck_check, semck_mode, signing_key, simple_config,
+                 write_publish_versions, Match};
 use clap::{App, Arg, ArgMatches, SubCommand};
 use core::{Context, RpRequiredPackage, Version};
 use core::errors::*;
+use std::iter;
 use std::rc::Rc;
 
 pub fn options<'a, 'b>() -> App<'a, 'b> {
@@ -26,6 +28,13 @@ pub fn options<'a, 'b>() -> App<'a, 'b> {
             .help("Disable Semantic Checks"),
     );
 
+    let out = out.arg(
+        Arg::with_name("signing-key")
+            .long("signing-key")
+            .takes_value(true)
+            .help("Path to ed25519 key to sign published packages with"),
+    );
+
     let out = out.arg(
         Arg::with_name("version")
             .long("version")
@@ -33,6 +42,22 @@ pub fn options<'a, 'b>() -> App<'a, 'b> {
             .help("Override published version with argument"),
     );
 
+    let out = out.arg(
+        Arg::with_name("bump")
+            .long("bump")
+            .takes_value(true)
+            .possible_values(&["auto", "patch", "minor", "major"])
+            .help("Bump the versions in the [publish] section from their latest deployments"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("mode")
+            .long("mode")
+            .takes_value(true)
+            .possible_values(&["wire", "source", "both"])
+            .help("Compatibility to check for: wire, source, or both (default: both)"),
+    );
+
     let out = out.arg(Arg::with_name("package").multiple(true));
 
     out
@@ -51,13 +76,43 @@ pub fn entry(ctx: Rc<Context>, m: &ArgMatches) -> Result<()> {
         None
     };
 
+    let bump = bump_mode(m)?;
+
+    if bump.is_some() && version_override.is_some() {
+        return Err("--bump and --version can't be used at the same time".into());
+    }
+
+    let mode = semck_mode(m)?;
+    let mut repository = repository(&manifest)?;
+
     let mut results = Vec::new();
+    // bumped versions to write back to the [publish] section of the manifest.
+    let mut bumped = Vec::new();
 
-    results.extend(publish_matches(
-        manifest_resolver.as_mut(),
-        version_override.as_ref(),
-        &manifest.publish,
-    )?);
+    if let Some(bump) = bump {
+        for publish in &manifest.publish {
+            let found = publish_matches(manifest_resolver.as_mut(), None, iter::once(publish))?;
+
+            let version = next_version(&mut repository, &mut env, &found, bump, mode)?
+                .unwrap_or_else(|| publish.version.clone());
+
+            info!("{}: {} -> {}", publish.package, publish.version, version);
+
+            results.extend(
+                found
+                    .into_iter()
+                    .map(|Match(_, object, package)| Match(version.clone(), object, package)),
+            );
+
+            bumped.push((publish.package.clone(), version));
+        }
+    } else {
+        results.extend(publish_matches(
+            manifest_resolver.as_mut(),
+            version_override.as_ref(),
+            &manifest.publish,
+        )?);
+    }
 
     // packages to publish from the commandline
     let packages: Vec<RpRequiredPackage> = m.values_of("package")
@@ -76,13 +131,25 @@ pub fn entry(ctx: Rc<Context>, m: &ArgMatches) -> Result<()> {
     let pretend = m.is_present("pretend");
     let no_semck = m.is_present("no-semck");
 
-    let mut repository = repository(&manifest)?;
+    let signing_key = signing_key(m)?;
+
+    if let Some(ref signing_key) = signing_key {
+        info!("signing with key: {}", signing_key.public_key());
+    }
 
     // errors that would prevent publishing
     let mut semck_errors = Vec::new();
 
     for m in &results {
-        semck_check(&ctx, &mut semck_errors, &mut repository, &mut env, &m)?;
+        semck_check(
+            &ctx,
+            &mut semck_errors,
+            &mut repository,
+            &mut env,
+            &m,
+            mode,
+            false,
+        )?;
     }
 
     if semck_errors.len() > 0 {
@@ -104,9 +171,13 @@ pub fn entry(ctx: Rc<Context>, m: &ArgMatches) -> Result<()> {
             );
         } else {
             info!("publishing: {}@{} (from {})", package, version, object);
-            repository.publish(&object, &package, &version, force)?;
+            repository.publish(&object, &package, &version, signing_key.as_ref(), force)?;
         }
     }
 
+    if !pretend && !bumped.is_empty() {
+        write_publish_versions(manifest_path(m), &bumped)?;
+    }
+
     Ok(())
 }
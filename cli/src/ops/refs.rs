@@ -0,0 +1,162 @@
+//! Refs command, finds every reference to a declaration.
+
+use build_spec::{path_resolver, type_config};
+use clap::{App, Arg, ArgMatches, SubCommand};
+use core::{self, Context, RpName, RpReference};
+use core::errors::*;
+use serde_json;
+use std::collections::{HashSet, VecDeque};
+use std::rc::Rc;
+
+pub fn options<'a, 'b>() -> App<'a, 'b> {
+    let out = SubCommand::with_name("refs").about("Find every reference to a declaration");
+
+    let out = out.arg(
+        Arg::with_name("name")
+            .required(true)
+            .help("Declaration to find references to, like `foo.bar::Baz`"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("transitive")
+            .long("transitive")
+            .help("Also find references to the declarations making the references"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("format")
+            .long("format")
+            .takes_value(true)
+            .possible_values(&["text", "json"])
+            .help("Format to print references in (default: text)"),
+    );
+
+    out
+}
+
+#[derive(Serialize)]
+struct JsonRef {
+    /// Declaration being referenced.
+    target: String,
+    owner: String,
+    owner_kind: &'static str,
+    site: String,
+    nesting: Vec<String>,
+    file: String,
+    line: usize,
+    column: usize,
+}
+
+pub fn entry(ctx: Rc<Context>, matches: &ArgMatches) -> Result<()> {
+    let type_name = matches.value_of("name").ok_or_else(|| "name: missing")?;
+    let transitive = matches.is_present("transitive");
+
+    let (manifest, mut env, name) = type_config(&ctx, matches, type_name)?;
+
+    // packages which make references are not necessarily imported by the manifest.
+    if let Some(mut resolver) = path_resolver(&manifest)? {
+        env.import_all(resolver.as_mut())?;
+    }
+
+    let mut references = Vec::new();
+
+    for (_, file) in env.for_each_file() {
+        references.extend(file.references());
+    }
+
+    let mut queue = VecDeque::new();
+    let mut visited = HashSet::new();
+
+    visited.insert(name.qualified());
+    queue.push_back(&name);
+
+    // references found, grouped by the declaration they reference.
+    let mut found: Vec<(&RpName, Vec<&RpReference>)> = Vec::new();
+
+    while let Some(target) = queue.pop_front() {
+        let matching = references
+            .iter()
+            .filter(|r| r.name.is_same(target))
+            .collect::<Vec<_>>();
+
+        if transitive {
+            for r in &matching {
+                if visited.insert(r.owner.qualified()) {
+                    queue.push_back(r.owner);
+                }
+            }
+        }
+
+        found.push((target, matching));
+    }
+
+    match matches.value_of("format").unwrap_or("text") {
+        "json" => {
+            let mut out = Vec::new();
+
+            for &(target, ref matching) in &found {
+                for r in matching {
+                    let (line, column) = position(r)?;
+
+                    out.push(JsonRef {
+                        target: target.qualified(),
+                        owner: r.owner.qualified(),
+                        owner_kind: r.owner_kind,
+                        site: r.site.to_string(),
+                        nesting: r.nesting.iter().map(ToString::to_string).collect(),
+                        file: r.pos.object.to_string(),
+                        line: line,
+                        column: column,
+                    });
+                }
+            }
+
+            let out = serde_json::to_string_pretty(&out).map_err(|e| e.to_string())?;
+            println!("{}", out);
+        }
+        _ => for &(target, ref matching) in &found {
+            if matching.is_empty() {
+                println!("{}: no references", target.qualified());
+                continue;
+            }
+
+            println!("{}: {} reference(s)", target.qualified(), matching.len());
+
+            for r in matching {
+                let (line, column) = position(r)?;
+
+                let nesting = if r.nesting.is_empty() {
+                    String::new()
+                } else {
+                    let nesting = r.nesting
+                        .iter()
+                        .rev()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>();
+
+                    format!(" (as {})", nesting.join(" of "))
+                };
+
+                println!(
+                    "  {}:{}:{}: {} `{}`, {}{}",
+                    r.pos.object,
+                    line,
+                    column,
+                    r.owner_kind,
+                    r.owner.qualified(),
+                    r.site,
+                    nesting
+                );
+            }
+        },
+    }
+
+    return Ok(());
+
+    /// Find the line and column (both starting at 1) of the reference.
+    fn position(r: &RpReference) -> Result<(usize, usize)> {
+        let pos = r.pos;
+        let (_, line, (s, _)) = core::utils::find_line(pos.object.read()?, (pos.start, pos.end))?;
+        Ok((line + 1, s + 1))
+    }
+}
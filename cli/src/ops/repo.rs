@@ -1,9 +1,14 @@
 //! Repository management commands.
 
+use build_spec::{convert_lang, file_repository, manifest, manifest_preamble, open_repository,
+                 repository};
 use clap::{App, Arg, ArgMatches, SubCommand};
-use core::Context;
+use core::{Context, RpPackage, Version};
 use core::errors::*;
-use repository::init_file_index;
+use manifest::{self as m, NoLang};
+use repository::{init_file_index, Repository, SigningKey};
+use std::env;
+use std::path::Path;
 use std::rc::Rc;
 
 fn init(matches: &ArgMatches) -> Result<()> {
@@ -27,9 +32,198 @@ fn init_options<'a, 'b>() -> App<'a, 'b> {
     out
 }
 
+fn keygen(matches: &ArgMatches) -> Result<()> {
+    let path = matches.value_of("path").ok_or_else(|| "missing <path>")?;
+
+    info!("Generating key: {}", path);
+    let key = SigningKey::generate(path)?;
+    info!("Public key: {}", key.public_key());
+    Ok(())
+}
+
+fn keygen_options<'a, 'b>() -> App<'a, 'b> {
+    let out = SubCommand::with_name("keygen").about("Generate a new key for signing packages");
+
+    let out = out.arg(
+        Arg::with_name("path")
+            .required(true)
+            .help("Path to write the key to"),
+    );
+
+    out
+}
+
+fn gc(matches: &ArgMatches) -> Result<()> {
+    let mut repository = setup_repository(matches)?;
+    let gc = repository.gc()?;
+
+    info!(
+        "Removed {} object(s) and {} missing-marker(s), reclaimed {} byte(s)",
+        gc.objects, gc.missing, gc.reclaimed
+    );
+
+    Ok(())
+}
+
+fn gc_options<'a, 'b>() -> App<'a, 'b> {
+    let out = SubCommand::with_name("gc")
+        .about("Remove objects which are no longer referenced by the index, and stale caches");
+
+    super::base_args(out)
+}
+
+fn mirror(matches: &ArgMatches) -> Result<()> {
+    let url = matches.value_of("url").ok_or_else(|| "missing <url>")?;
+    let path = matches.value_of("path").ok_or_else(|| "missing <path>")?;
+
+    let config = m::Repository {
+        index: Some(url.to_owned()),
+        objects: matches.value_of("objects").map(ToOwned::to_owned),
+        ..m::Repository::default()
+    };
+
+    let mut source = open_repository(&env::current_dir()?, &config)?;
+    let mut target = file_repository(Path::new(path))?;
+
+    for package in source.packages()? {
+        for deployment in source.all(&package)? {
+            info!("mirroring: {}@{}", package, deployment.version);
+            source.copy_deployment(&mut target, &package, &deployment)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn mirror_options<'a, 'b>() -> App<'a, 'b> {
+    let out = SubCommand::with_name("mirror")
+        .about("Copy every package of a remote repository into a local repository");
+
+    let out = out.arg(
+        Arg::with_name("url")
+            .required(true)
+            .help("URL of the index to mirror"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("path")
+            .required(true)
+            .help("Path to the local repository to mirror into"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("objects")
+            .long("objects")
+            .short("O")
+            .takes_value(true)
+            .help("URL for objects storage of the mirrored repository"),
+    );
+
+    out
+}
+
+/// Setup the repository from the manifest and arguments of a subcommand.
+fn setup_repository(matches: &ArgMatches) -> Result<Repository> {
+    let preamble = manifest_preamble(matches)?;
+
+    let lang = preamble
+        .language
+        .as_ref()
+        .map(|l| convert_lang(*l))
+        .unwrap_or_else(|| Box::new(NoLang));
+
+    let manifest = manifest(lang.as_ref(), matches, preamble)?;
+    repository(&manifest)
+}
+
+/// Parse the package and version arguments of a subcommand.
+fn package_version(matches: &ArgMatches) -> Result<(RpPackage, Version)> {
+    let package = matches
+        .value_of("package")
+        .map(RpPackage::parse)
+        .ok_or_else(|| "missing <package>")?;
+
+    let version = matches
+        .value_of("version")
+        .ok_or_else(|| "missing <version>")?;
+
+    let version = Version::parse(version)
+        .map_err(|e| format!("not a valid version: {}: {}", version, e))?;
+
+    Ok((package, version))
+}
+
+fn yank(matches: &ArgMatches) -> Result<()> {
+    let mut repository = setup_repository(matches)?;
+    let (package, version) = package_version(matches)?;
+
+    info!("Yanking: {}@{}", package, version);
+    repository.yank(&package, &version, matches.value_of("message"))?;
+    Ok(())
+}
+
+fn yank_options<'a, 'b>() -> App<'a, 'b> {
+    let out = SubCommand::with_name("yank")
+        .about("Yank a published version, excluding it from resolution unless pinned");
+
+    let out = out.arg(
+        Arg::with_name("package")
+            .required(true)
+            .help("Package to yank"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("version")
+            .required(true)
+            .help("Version to yank"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("message")
+            .long("message")
+            .short("m")
+            .takes_value(true)
+            .help("Deprecation message to show when the yanked version is used"),
+    );
+
+    super::base_args(out)
+}
+
+fn unyank(matches: &ArgMatches) -> Result<()> {
+    let mut repository = setup_repository(matches)?;
+    let (package, version) = package_version(matches)?;
+
+    info!("Un-yanking: {}@{}", package, version);
+    repository.unyank(&package, &version)?;
+    Ok(())
+}
+
+fn unyank_options<'a, 'b>() -> App<'a, 'b> {
+    let out = SubCommand::with_name("unyank").about("Reverse the effect of a previous yank");
+
+    let out = out.arg(
+        Arg::with_name("package")
+            .required(true)
+            .help("Package to un-yank"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("version")
+            .required(true)
+            .help("Version to un-yank"),
+    );
+
+    super::base_args(out)
+}
+
 pub fn options<'a, 'b>() -> App<'a, 'b> {
     let out = SubCommand::with_name("repo").about("Manage repositories");
     let out = out.subcommand(init_options());
+    let out = out.subcommand(yank_options());
+    let out = out.subcommand(unyank_options());
+    let out = out.subcommand(keygen_options());
+    let out = out.subcommand(mirror_options());
+    let out = out.subcommand(gc_options());
     out
 }
 
@@ -39,6 +233,11 @@ pub fn entry(_ctx: Rc<Context>, matches: &ArgMatches) -> Result<()> {
 
     match name {
         "init" => init(matches),
+        "yank" => yank(matches),
+        "unyank" => unyank(matches),
+        "keygen" => keygen(matches),
+        "mirror" => mirror(matches),
+        "gc" => gc(matches),
         _ => unreachable!("bad subcommand"),
     }
 }
@@ -0,0 +1,75 @@
+//! Sample command, generates random payloads which match a declaration.
+
+use build_spec::type_config;
+use clap::{App, Arg, ArgMatches, SubCommand};
+use core::Context;
+use core::errors::*;
+use sample::Sampler;
+use serde_json;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+pub fn options<'a, 'b>() -> App<'a, 'b> {
+    let out = SubCommand::with_name("sample").about("Generate sample payloads for a declaration");
+
+    let out = out.arg(
+        Arg::with_name("type")
+            .long("type")
+            .takes_value(true)
+            .required(true)
+            .help("Declaration to generate payloads for, like `foo.bar::Baz`"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("count")
+            .long("count")
+            .short("n")
+            .takes_value(true)
+            .help("Number of payloads to generate (default: 1)"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("seed")
+            .long("seed")
+            .takes_value(true)
+            .help("Seed to generate payloads from (default: random)"),
+    );
+
+    out
+}
+
+pub fn entry(ctx: Rc<Context>, matches: &ArgMatches) -> Result<()> {
+    let type_name = matches.value_of("type").ok_or_else(|| "--type: missing")?;
+
+    let count = match matches.value_of("count") {
+        Some(count) => count
+            .parse::<u64>()
+            .map_err(|e| format!("--count: bad value `{}`: {}", count, e))?,
+        None => 1,
+    };
+
+    let seed = match matches.value_of("seed") {
+        Some(seed) => seed
+            .parse::<u64>()
+            .map_err(|e| format!("--seed: bad value `{}`: {}", seed, e))?,
+        None => Sampler::seed_from_time()?,
+    };
+
+    let (_, env, name) = type_config(&ctx, matches, type_name)?;
+
+    // so that a failing sample can be reproduced.
+    info!("seed: {}", seed);
+
+    let mut sampler = Sampler::new(&env, seed);
+
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+
+    for _ in 0..count {
+        let value = sampler.sample(&name)?;
+        let line = serde_json::to_string(&value).map_err(|e| format!("bad sample: {}", e))?;
+        writeln!(stdout, "{}", line)?;
+    }
+
+    Ok(())
+}
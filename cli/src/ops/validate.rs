@@ -0,0 +1,57 @@
+//! Validate command, checks that a JSON payload matches a declaration.
+
+use build_spec::type_config;
+use clap::{App, Arg, ArgMatches, SubCommand};
+use core::Context;
+use core::errors::*;
+use serde_json;
+use std::fs::File;
+use std::io::{self, Read};
+use std::rc::Rc;
+use validate::Validator;
+
+pub fn options<'a, 'b>() -> App<'a, 'b> {
+    let out = SubCommand::with_name("validate")
+        .about("Validate a JSON payload against a declaration");
+
+    let out = out.arg(
+        Arg::with_name("type")
+            .long("type")
+            .takes_value(true)
+            .required(true)
+            .help("Declaration to validate against, like `foo.bar::Baz`"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("input")
+            .long("input")
+            .short("i")
+            .takes_value(true)
+            .help("File to read the payload from (default: stdin)"),
+    );
+
+    out
+}
+
+pub fn entry(ctx: Rc<Context>, matches: &ArgMatches) -> Result<()> {
+    let type_name = matches.value_of("type").ok_or_else(|| "--type: missing")?;
+    let (_, env, name) = type_config(&ctx, matches, type_name)?;
+
+    let reader: Box<Read> = match matches.value_of("input") {
+        Some(path) => Box::new(File::open(path).map_err(|e| format!("{}: {}", path, e))?),
+        None => Box::new(io::stdin()),
+    };
+
+    let value: serde_json::Value =
+        serde_json::from_reader(reader).map_err(|e| format!("bad payload: {}", e))?;
+
+    let violations = Validator::new(&env).validate(&name, &value)?;
+
+    if !violations.is_empty() {
+        return Err(Error::new(format!("payload does not match `{}`", type_name))
+            .with_suppressed(violations.into_iter().map(|v| Error::new(v.to_string()))));
+    }
+
+    info!("payload matches `{}`", type_name);
+    Ok(())
+}
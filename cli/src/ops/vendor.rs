@@ -0,0 +1,77 @@
+//! Vendor command, copies all dependencies of a project into a local repository.
+
+use build_spec::{convert_lang, environment, file_repository, manifest, manifest_preamble,
+                 repository};
+use clap::{App, Arg, ArgMatches, SubCommand};
+use core::Context;
+use core::errors::*;
+use manifest::NoLang;
+use std::path::Path;
+use std::rc::Rc;
+
+pub fn options<'a, 'b>() -> App<'a, 'b> {
+    let out = SubCommand::with_name("vendor")
+        .about("Copy all dependencies into a local repository, for use without network access");
+
+    let out = out.arg(
+        Arg::with_name("dir")
+            .long("dir")
+            .takes_value(true)
+            .help("Directory to vendor dependencies into (default: vendor)"),
+    );
+
+    out
+}
+
+pub fn entry(ctx: Rc<Context>, matches: &ArgMatches) -> Result<()> {
+    let preamble = manifest_preamble(matches)?;
+
+    let lang = preamble
+        .language
+        .as_ref()
+        .map(|l| convert_lang(*l))
+        .unwrap_or_else(|| Box::new(NoLang));
+
+    let mut manifest = manifest(lang.as_ref(), matches, preamble)?;
+
+    // dependencies are always resolved from upstream while vendoring.
+    let configured = manifest.repository.vendor.take();
+
+    let vendor = match matches.value_of("dir") {
+        Some(dir) => Path::new(dir).to_owned(),
+        None => match configured {
+            Some(vendor) => vendor,
+            None => manifest
+                .path
+                .as_ref()
+                .and_then(|p| p.parent())
+                .ok_or_else(|| "no parent path to manifest")?
+                .join("vendor"),
+        },
+    };
+
+    let env = environment(lang.as_ref(), ctx.clone(), &manifest)?;
+
+    let mut source = repository(&manifest)?;
+    let mut target = file_repository(&vendor)?;
+
+    for (package, _) in env.for_each_file() {
+        let version = match package.version {
+            Some(ref version) => version,
+            None => continue,
+        };
+
+        for deployment in source.get_deployments(&package.package, version)? {
+            info!(
+                "vendoring: {}@{} -> {}",
+                package.package,
+                version,
+                vendor.display()
+            );
+
+            source.copy_deployment(&mut target, &package.package, &deployment)?;
+        }
+    }
+
+    Ok(())
+}
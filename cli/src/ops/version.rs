@@ -0,0 +1,83 @@
+//! Version commands.
+
+use build_spec::{manifest_path, next_version, path_resolver, publish_matches, repository,
+                 semck_mode, simple_config, write_publish_versions, BumpMode};
+use clap::{App, Arg, ArgMatches, SubCommand};
+use core::Context;
+use core::errors::*;
+use std::iter;
+use std::rc::Rc;
+
+fn suggest(ctx: Rc<Context>, matches: &ArgMatches) -> Result<()> {
+    let (manifest, mut env) = simple_config(&ctx, matches)?;
+
+    let mut manifest_resolver =
+        path_resolver(&manifest)?.ok_or_else(|| "could not setup manifest resolver")?;
+
+    if manifest.publish.is_empty() {
+        return Err("no packages in the [publish] section of the manifest".into());
+    }
+
+    let mode = semck_mode(matches)?;
+    let mut repository = repository(&manifest)?;
+
+    let mut suggested = Vec::new();
+
+    for publish in &manifest.publish {
+        let found = publish_matches(manifest_resolver.as_mut(), None, iter::once(publish))?;
+
+        let version = match next_version(&mut repository, &mut env, &found, BumpMode::Auto, mode)? {
+            Some(version) => version,
+            None => {
+                info!("{}: not published yet, keeping {}", publish.package, publish.version);
+                continue;
+            }
+        };
+
+        println!("{}: {} -> {}", publish.package, publish.version, version);
+        suggested.push((publish.package.clone(), version));
+    }
+
+    if matches.is_present("write") && !suggested.is_empty() {
+        write_publish_versions(manifest_path(matches), &suggested)?;
+    }
+
+    Ok(())
+}
+
+fn suggest_options<'a, 'b>() -> App<'a, 'b> {
+    let out = SubCommand::with_name("suggest")
+        .about("Suggest the next version of the packages in the [publish] section");
+
+    let out = out.arg(
+        Arg::with_name("write")
+            .long("write")
+            .help("Write the suggested versions to the [publish] section of the manifest"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("mode")
+            .long("mode")
+            .takes_value(true)
+            .possible_values(&["wire", "source", "both"])
+            .help("Compatibility to check for: wire, source, or both (default: both)"),
+    );
+
+    super::base_args(out)
+}
+
+pub fn options<'a, 'b>() -> App<'a, 'b> {
+    let out = SubCommand::with_name("version").about("Manage the versions of packages");
+    let out = out.subcommand(suggest_options());
+    out
+}
+
+pub fn entry(ctx: Rc<Context>, matches: &ArgMatches) -> Result<()> {
+    let (name, matches) = matches.subcommand();
+    let matches = matches.ok_or_else(|| "no subcommand")?;
+
+    match name {
+        "suggest" => suggest(ctx, matches),
+        _ => unreachable!("bad subcommand"),
+    }
+}
@@ -65,9 +65,16 @@ where
         })
     }
 
-    fn print(&self, m: &str) -> Result<()> {
+    fn print(&self, level: log::LogLevel, m: &str) -> Result<()> {
         let mut o = self.out.lock();
-        writeln!(o, "ERROR: {}", Red.paint(m.as_ref()))?;
+
+        let color = match level {
+            log::LogLevel::Error => Red,
+            log::LogLevel::Warn => Colour::Yellow,
+            _ => Blue,
+        };
+
+        writeln!(o, "{}: {}", level, color.paint(m.as_ref()))?;
         Ok(())
     }
 
@@ -0,0 +1,269 @@
+//! Machine-readable output, emitting one JSON object per line for every diagnostic.
+
+use super::{LockableWrite, Output};
+use core::{self, ContextItem, ErrorPos};
+use core::errors::*;
+use log;
+use serde_json;
+use std::cmp;
+use std::io::{self, Read, Write};
+
+pub struct Json<T> {
+    out: T,
+}
+
+/// Location in a file, both line and column are 1-based.
+#[derive(Debug, Serialize)]
+struct Location {
+    line: usize,
+    column: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct Span {
+    file: String,
+    /// Byte offsets of the span.
+    byte_start: usize,
+    byte_end: usize,
+    start: Location,
+    end: Location,
+}
+
+#[derive(Debug, Serialize)]
+struct Related {
+    message: String,
+    span: Span,
+}
+
+#[derive(Debug, Serialize)]
+struct Diagnostic {
+    severity: &'static str,
+    /// Stable code of the diagnostic, see `reproto explain`.
+    code: Option<String>,
+    message: String,
+    span: Option<Span>,
+    related: Vec<Related>,
+}
+
+impl<T> Json<T>
+where
+    T: LockableWrite,
+{
+    pub fn new(out: T) -> Json<T> {
+        Json { out: out }
+    }
+
+    /// Build the span for the given position.
+    fn span(&self, p: &ErrorPos) -> Result<Span> {
+        let mut content = Vec::new();
+        p.object.read()?.read_to_end(&mut content)?;
+
+        Ok(Span {
+            file: p.object.to_string(),
+            byte_start: p.start,
+            byte_end: p.end,
+            start: location(&content, p.start),
+            end: location(&content, p.end),
+        })
+    }
+
+    fn emit(&self, diagnostic: &Diagnostic) -> Result<()> {
+        emit(&self.out, diagnostic)
+    }
+
+    fn positional(&self, severity: &'static str, m: &str, p: &ErrorPos) -> Result<()> {
+        self.emit(&Diagnostic {
+            severity: severity,
+            code: None,
+            message: m.to_string(),
+            span: Some(self.span(p)?),
+            related: vec![],
+        })
+    }
+}
+
+/// Find the 1-based line and column of the given byte offset.
+fn location(content: &[u8], offset: usize) -> Location {
+    let offset = cmp::min(offset, content.len());
+    let before = &content[..offset];
+
+    let line = before.iter().filter(|b| **b == b'\n').count();
+
+    let column = match before.iter().rposition(|b| *b == b'\n') {
+        Some(newline) => offset - newline - 1,
+        None => offset,
+    };
+
+    Location {
+        line: line + 1,
+        column: column + 1,
+    }
+}
+
+/// Severity of diagnostics emitted at the given log level.
+fn severity(level: log::LogLevel) -> &'static str {
+    match level {
+        log::LogLevel::Error => "error",
+        log::LogLevel::Warn => "warning",
+        _ => "info",
+    }
+}
+
+fn emit<T: LockableWrite>(out: &T, diagnostic: &Diagnostic) -> Result<()> {
+    let mut o = out.lock();
+    serde_json::to_writer(&mut o, diagnostic).map_err(|e| format!("{}", e))?;
+    writeln!(o)?;
+    Ok(())
+}
+
+/// Logger which emits warnings and errors as diagnostics.
+///
+/// Other messages are written to stderr, to keep stdout reserved for diagnostics.
+pub struct JsonLogger<T> {
+    out: T,
+}
+
+impl<T> log::Log for JsonLogger<T>
+where
+    T: LockableWrite,
+{
+    fn enabled(&self, metadata: &log::LogMetadata) -> bool {
+        metadata.level() <= log::LogLevel::Debug
+    }
+
+    fn log(&self, record: &log::LogRecord) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        if record.level() <= log::LogLevel::Warn {
+            let diagnostic = Diagnostic {
+                severity: severity(record.level()),
+                code: None,
+                message: record.args().to_string(),
+                span: None,
+                related: vec![],
+            };
+
+            emit(&self.out, &diagnostic).unwrap();
+            return;
+        }
+
+        let stderr = io::stderr();
+        let mut out = stderr.lock();
+        writeln!(out, "{}: {}", record.level(), record.args()).unwrap();
+    }
+}
+
+impl<T> Output for Json<T>
+where
+    T: 'static + LockableWrite,
+{
+    fn lock<'a>(&'a self) -> Box<io::Write + 'a> {
+        self.out.lock()
+    }
+
+    /// Information items are attached as related positions to the error preceding them.
+    fn handle_context(&self, ctx: &core::Context) -> Result<()> {
+        let errors = ctx.errors()?;
+
+        let mut current: Option<Diagnostic> = None;
+
+        for e in errors.iter() {
+            match *e {
+                ContextItem::ErrorPos(ref pos, ref message, ref code) => {
+                    if let Some(diagnostic) = current.take() {
+                        self.emit(&diagnostic)?;
+                    }
+
+                    current = Some(Diagnostic {
+                        severity: "error",
+                        code: Some(code.id.to_string()),
+                        message: message.to_string(),
+                        span: Some(self.span(pos)?),
+                        related: vec![],
+                    });
+                }
+                ContextItem::WarningPos(ref pos, ref message, ref code) => {
+                    if let Some(diagnostic) = current.take() {
+                        self.emit(&diagnostic)?;
+                    }
+
+                    current = Some(Diagnostic {
+                        severity: "warning",
+                        code: Some(code.id.to_string()),
+                        message: message.to_string(),
+                        span: Some(self.span(pos)?),
+                        related: vec![],
+                    });
+                }
+                ContextItem::InfoPos(ref pos, ref message) => {
+                    let related = Related {
+                        message: message.to_string(),
+                        span: self.span(pos)?,
+                    };
+
+                    match current {
+                        Some(ref mut diagnostic) => diagnostic.related.push(related),
+                        None => self.print_info(message.as_str(), pos)?,
+                    }
+                }
+            }
+        }
+
+        if let Some(diagnostic) = current.take() {
+            self.emit(&diagnostic)?;
+        }
+
+        Ok(())
+    }
+
+    fn handle_error(&self, e: &Error) -> Result<()> {
+        for e in e.causes() {
+            self.emit(&Diagnostic {
+                severity: "error",
+                code: None,
+                message: e.message().to_string(),
+                span: match e.pos() {
+                    Some(pos) => Some(self.span(pos)?),
+                    None => None,
+                },
+                related: vec![],
+            })?;
+
+            for e in e.suppressed() {
+                self.handle_error(e)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn error(&self, e: &Error) -> Result<()> {
+        self.handle_error(e)
+    }
+
+    fn logger(&self) -> Box<log::Log + 'static> {
+        Box::new(JsonLogger {
+            out: self.out.open_new(),
+        })
+    }
+
+    fn print(&self, level: log::LogLevel, m: &str) -> Result<()> {
+        self.emit(&Diagnostic {
+            severity: severity(level),
+            code: None,
+            message: m.to_string(),
+            span: None,
+            related: vec![],
+        })
+    }
+
+    fn print_info(&self, m: &str, p: &ErrorPos) -> Result<()> {
+        self.positional("info", m, p)
+    }
+
+    fn print_error(&self, m: &str, p: &ErrorPos) -> Result<()> {
+        self.positional("error", m, p)
+    }
+}
@@ -1,7 +1,9 @@
 mod colored;
+mod json;
 mod non_colored;
 
 pub use self::colored::Colored;
+pub use self::json::Json;
 pub use self::non_colored::NonColored;
 use core::{self, Context, ContextItem};
 use core::errors::*;
@@ -35,12 +37,15 @@ pub trait Output {
 
         for e in errors.iter() {
             match *e {
-                ContextItem::ErrorPos(ref pos, ref message) => {
-                    self.print_error(message.as_str(), pos)?;
+                ContextItem::ErrorPos(ref pos, ref message, ref code) => {
+                    self.print_error(format!("{} [{}]", message, code).as_str(), pos)?;
                 }
                 ContextItem::InfoPos(ref pos, ref message) => {
                     self.print_info(message.as_str(), pos)?;
                 }
+                ContextItem::WarningPos(ref pos, ref message, ref code) => {
+                    self.print_info(format!("warning: {} [{}]", message, code).as_str(), pos)?;
+                }
             }
         }
 
@@ -96,7 +101,8 @@ pub trait Output {
 
     fn logger(&self) -> Box<log::Log + 'static>;
 
-    fn print(&self, m: &str) -> Result<()>;
+    /// Print a message without a position, at the given level.
+    fn print(&self, level: log::LogLevel, m: &str) -> Result<()>;
 
     fn print_info(&self, m: &str, p: &core::ErrorPos) -> Result<()>;
 
@@ -73,9 +73,9 @@ where
         })
     }
 
-    fn print(&self, m: &str) -> Result<()> {
+    fn print(&self, level: log::LogLevel, m: &str) -> Result<()> {
         let mut o = self.out.lock();
-        writeln!(o, "ERROR: {}", m)?;
+        writeln!(o, "{}: {}", level, m)?;
         Ok(())
     }
 
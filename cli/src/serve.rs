@@ -0,0 +1,271 @@
+//! Serve mode, which builds into memory and serves the result over HTTP.
+//!
+//! Rebuilds whenever any of the inputs change, like watch mode, and pushes a reload to all open
+//! HTML pages through a server-sent event stream.
+
+use core::{CapturingFilesystem, Context, RelativePathBuf};
+use core::errors::*;
+use futures::{Future, Stream};
+use futures::future::{ok, FutureResult};
+use futures::sync::mpsc::Sender;
+use hyper::{self, Body, Chunk, Method, StatusCode};
+use hyper::header::{CacheControl, CacheDirective, ContentLength, ContentType};
+use hyper::mime;
+use hyper::server::{Http, Request, Response, Service};
+use output::Output;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::mem;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::Duration;
+use tokio_core::reactor::{Core, Interval};
+use watch::{try_snapshot, POLL_INTERVAL};
+
+/// Path of the event stream which notifies pages to reload.
+const RELOAD_PATH: &str = "/__reload";
+
+/// Event sent to all pages after a successful build.
+const RELOAD_EVENT: &str = "data: reload\n\n";
+
+/// Script injected into every served HTML page, reloading it on a reload event.
+const RELOAD_SCRIPT: &str = "<script>\
+new EventSource(\"/__reload\").onmessage = function() { location.reload(); };\
+</script>";
+
+/// Run the given task into memory, serve its output on `addr`, and run it again every time one of
+/// its inputs change.
+///
+/// `initial` are paths which are always watched, the task adds any other paths it depends on.
+/// Failed builds are reported to the given output, and the previous build is served until the
+/// next successful one.
+pub fn serve<F>(
+    output: &Output,
+    addr: &SocketAddr,
+    initial: Vec<PathBuf>,
+    mut task: F,
+) -> Result<()>
+where
+    F: FnMut(Rc<Context>, &mut Vec<PathBuf>) -> Result<()>,
+{
+    let shared = Rc::new(Shared {
+        files: RefCell::new(HashMap::new()),
+        clients: RefCell::new(Vec::new()),
+    });
+
+    let mut core = Core::new()?;
+    let handle = core.handle();
+
+    let setup = {
+        let shared = shared.clone();
+
+        move || {
+            Ok(PreviewService {
+                shared: shared.clone(),
+            })
+        }
+    };
+
+    let server = Http::new().serve_addr_handle(addr, &handle, setup)?;
+    let local_addr = server.incoming_ref().local_addr();
+
+    let connections = handle.clone();
+
+    handle.spawn(
+        server
+            .for_each(move |conn| {
+                connections.spawn(
+                    conn.map(|_| ())
+                        .map_err(|e| warn!("connection failed: {}", e)),
+                );
+
+                Ok(())
+            })
+            .map_err(|e| error!("server failed: {}", e)),
+    );
+
+    let mut watched = build(output, &shared, &initial, &mut task)?;
+    let mut current = try_snapshot(&watched);
+
+    info!("Serving on http://{}", local_addr);
+
+    let interval = Interval::new(Duration::from_millis(POLL_INTERVAL), &handle)?;
+
+    let rebuild = interval.map_err(Error::from).for_each(|_| -> Result<()> {
+        let next = try_snapshot(&watched);
+
+        // keep serving the previous build, and retry on the next poll if the paths could not be
+        // read.
+        if next.is_none() || next == current {
+            return Ok(());
+        }
+
+        watched = build(output, &shared, &initial, &mut task)?;
+        current = try_snapshot(&watched);
+        Ok(())
+    });
+
+    core.run(rebuild)
+}
+
+/// Run the task with a fresh in-memory filesystem, and serve its output if it was successful.
+///
+/// Returns the paths to watch for changes.
+fn build<F>(
+    output: &Output,
+    shared: &Shared,
+    initial: &[PathBuf],
+    task: &mut F,
+) -> Result<Vec<PathBuf>>
+where
+    F: FnMut(Rc<Context>, &mut Vec<PathBuf>) -> Result<()>,
+{
+    let capturing = CapturingFilesystem::new();
+    let ctx = Rc::new(Context::new(capturing.filesystem()));
+
+    let mut watched = initial.to_vec();
+
+    match task(ctx.clone(), &mut watched) {
+        Ok(()) => {
+            let files = capturing
+                .files()
+                .borrow()
+                .iter()
+                .map(|(path, content)| (path.clone(), content.clone()))
+                .collect();
+
+            shared.update(files);
+            info!("Build successful, watching for changes");
+        }
+        Err(e) => {
+            output.handle_error(&e)?;
+            output.handle_context(ctx.as_ref())?;
+            info!("Build failed, serving the previous build");
+        }
+    }
+
+    Ok(watched)
+}
+
+/// State shared between all connections.
+struct Shared {
+    /// Files from the last successful build.
+    files: RefCell<HashMap<RelativePathBuf, Vec<u8>>>,
+    /// Event streams of open pages.
+    clients: RefCell<Vec<Sender<hyper::Result<Chunk>>>>,
+}
+
+impl Shared {
+    /// Serve the files of a new build, and notify all open pages.
+    fn update(&self, files: HashMap<RelativePathBuf, Vec<u8>>) {
+        *self.files.borrow_mut() = files;
+
+        let clients = mem::replace(&mut *self.clients.borrow_mut(), Vec::new());
+        let mut connected = Vec::new();
+
+        for mut client in clients {
+            // fails if the page has been closed.
+            if client.try_send(Ok(Chunk::from(RELOAD_EVENT))).is_ok() {
+                connected.push(client);
+            }
+        }
+
+        *self.clients.borrow_mut() = connected;
+    }
+
+    /// Handle a single request.
+    fn handle(&self, req: &Request) -> Response {
+        match *req.method() {
+            Method::Get | Method::Head => {}
+            _ => {
+                return text_response(
+                    StatusCode::MethodNotAllowed,
+                    format!("method not allowed: {}\n", req.method()),
+                )
+            }
+        }
+
+        if req.path() == RELOAD_PATH {
+            return self.subscribe();
+        }
+
+        let mut path = req.path().trim_left_matches('/').to_string();
+
+        if path.is_empty() || path.ends_with('/') {
+            path.push_str("index.html");
+        }
+
+        let files = self.files.borrow();
+
+        let content = match files.get(&RelativePathBuf::from(path.as_str())) {
+            Some(content) => content,
+            None => return text_response(StatusCode::NotFound, format!("not found: {}\n", path)),
+        };
+
+        let extension = Path::new(&path).extension().and_then(|e| e.to_str());
+
+        let (content_type, content) = match extension {
+            Some("html") => (mime::TEXT_HTML_UTF_8, inject_reload(content)),
+            Some("css") => (mime::TEXT_CSS, content.clone()),
+            Some("js") => (mime::TEXT_JAVASCRIPT, content.clone()),
+            _ => (mime::TEXT_PLAIN_UTF_8, content.clone()),
+        };
+
+        Response::new()
+            .with_status(StatusCode::Ok)
+            .with_header(ContentLength(content.len() as u64))
+            .with_header(ContentType(content_type))
+            .with_header(CacheControl(vec![CacheDirective::NoCache]))
+            .with_body(content)
+    }
+
+    /// Open an event stream which is notified after every successful build.
+    fn subscribe(&self) -> Response {
+        let (sender, body) = Body::pair();
+        self.clients.borrow_mut().push(sender);
+
+        Response::new()
+            .with_status(StatusCode::Ok)
+            .with_header(ContentType(mime::TEXT_EVENT_STREAM))
+            .with_header(CacheControl(vec![CacheDirective::NoCache]))
+            .with_body(body)
+    }
+}
+
+struct PreviewService {
+    shared: Rc<Shared>,
+}
+
+impl Service for PreviewService {
+    type Request = Request;
+    type Response = Response;
+    type Error = hyper::Error;
+    type Future = FutureResult<Response, hyper::Error>;
+
+    fn call(&self, req: Request) -> Self::Future {
+        let response = self.shared.handle(&req);
+        debug!("{} {}: {}", req.method(), req.path(), response.status());
+        ok(response)
+    }
+}
+
+/// Inject the reload script at the end of the body of an HTML page.
+fn inject_reload(content: &[u8]) -> Vec<u8> {
+    let content = String::from_utf8_lossy(content);
+
+    let content = match content.rfind("</body>") {
+        Some(index) => format!("{}{}{}", &content[..index], RELOAD_SCRIPT, &content[index..]),
+        None => format!("{}{}", content, RELOAD_SCRIPT),
+    };
+
+    content.into_bytes()
+}
+
+fn text_response(status: StatusCode, body: String) -> Response {
+    Response::new()
+        .with_status(status)
+        .with_header(ContentLength(body.len() as u64))
+        .with_header(ContentType::plaintext())
+        .with_body(body)
+}
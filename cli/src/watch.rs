@@ -0,0 +1,95 @@
+//! Watch mode, which re-runs a build whenever any of its inputs change.
+//!
+//! Changes are detected by polling the modification time of all watched files.
+
+use core::{Context, RealFilesystem};
+use core::errors::*;
+use output::Output;
+use std::collections::{BTreeMap, LinkedList};
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// How often watched paths are checked for changes, in milliseconds.
+pub const POLL_INTERVAL: u64 = 500;
+
+/// Record the modification time of all the given paths, recursing into directories.
+///
+/// Paths which do not exist are ignored, so that removing them is detected as a change.
+pub fn snapshot(paths: &[PathBuf]) -> Result<BTreeMap<PathBuf, SystemTime>> {
+    let mut out = BTreeMap::new();
+    let mut queue: LinkedList<PathBuf> = paths.iter().cloned().collect();
+
+    while let Some(path) = queue.pop_front() {
+        let m = match fs::metadata(&path) {
+            Ok(m) => m,
+            Err(_) => continue,
+        };
+
+        if m.is_dir() {
+            for e in fs::read_dir(&path)? {
+                queue.push_back(e?.path());
+            }
+
+            continue;
+        }
+
+        out.insert(path, m.modified()?);
+    }
+
+    Ok(out)
+}
+
+/// Take a snapshot of the given paths, logging any error.
+///
+/// Errors are usually transient, like a directory being removed while it is being scanned.
+pub fn try_snapshot(paths: &[PathBuf]) -> Option<BTreeMap<PathBuf, SystemTime>> {
+    match snapshot(paths) {
+        Ok(snapshot) => Some(snapshot),
+        Err(e) => {
+            warn!("failed to check for changes: {}", e.display());
+            None
+        }
+    }
+}
+
+/// Run the given task, and run it again every time one of its inputs change.
+///
+/// `initial` are paths which are always watched, the task adds any other paths it depends on.
+/// Every run gets a fresh context, which only writes output files whose content has changed.
+/// Diagnostics are printed to the given output, and do not stop watching.
+pub fn watch<F>(output: &Output, initial: Vec<PathBuf>, mut task: F) -> Result<()>
+where
+    F: FnMut(Rc<Context>, &mut Vec<PathBuf>) -> Result<()>,
+{
+    loop {
+        let filesystem = RealFilesystem::new().with_only_changed(true);
+        let ctx = Rc::new(Context::new(Box::new(filesystem)));
+
+        let mut watched = initial.clone();
+
+        match task(ctx.clone(), &mut watched) {
+            Ok(()) => info!("Build successful, watching for changes"),
+            Err(e) => {
+                output.handle_error(&e)?;
+                output.handle_context(ctx.as_ref())?;
+                info!("Build failed, watching for changes");
+            }
+        }
+
+        let current = try_snapshot(&watched);
+
+        loop {
+            thread::sleep(Duration::from_millis(POLL_INTERVAL));
+
+            let next = try_snapshot(&watched);
+
+            // retry on the next poll if the paths could not be read.
+            if next.is_some() && next != current {
+                break;
+            }
+        }
+    }
+}
@@ -362,12 +362,17 @@ fn derive(derive: Derive) -> DeriveResult {
 
             for e in errors.borrow().iter() {
                 match *e {
-                    core::ContextItem::ErrorPos(ref p, ref message) => {
+                    core::ContextItem::ErrorPos(ref p, ref message, ref code) => {
+                        let message = format!("{} [{}]", message, code);
                         error_markers.push(Marker::try_from_error_fb(p, message.as_str()));
                     }
                     core::ContextItem::InfoPos(ref p, ref message) => {
                         info_markers.push(Marker::try_from_error_fb(p, message.as_str()));
                     }
+                    core::ContextItem::WarningPos(ref p, ref message, ref code) => {
+                        let message = format!("warning: {} [{}]", message, code);
+                        info_markers.push(Marker::try_from_error_fb(p, message.as_str()));
+                    }
                 }
             }
 
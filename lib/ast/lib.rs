@@ -100,7 +100,7 @@ pub enum Type {
     /// ISO-8601 for date and time.
     DateTime,
     Name {
-        name: Name,
+        name: Loc<Name>,
     },
     Array {
         inner: Box<Type>,
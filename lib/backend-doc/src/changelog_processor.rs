@@ -0,0 +1,165 @@
+//! Changelog processor.
+//!
+//! Lists everything that changed in a version of a package, since the version before it.
+
+use super::Format;
+use core::RpVersionedPackage;
+use core::errors::*;
+use doc_builder::DocBuilder;
+use escape::Escape;
+use macros::FormatAttribute;
+use processor::Processor;
+use semck::diff::{Change, ChangeKind};
+use trans::Environment;
+
+/// Changes grouped by their kind, leaving out kinds without any changes.
+pub fn sections(changes: &[Change]) -> Vec<(&'static str, Vec<&Change>)> {
+    use self::ChangeKind::*;
+
+    let mut out = Vec::new();
+
+    for &(kind, title) in &[(Added, "Added"), (Removed, "Removed"), (Changed, "Changed")] {
+        let changes = changes.iter().filter(|c| c.kind == kind).collect::<Vec<_>>();
+
+        if !changes.is_empty() {
+            out.push((title, changes));
+        }
+    }
+
+    out
+}
+
+pub struct Data<'a> {
+    pub package: &'a RpVersionedPackage,
+    pub previous: &'a RpVersionedPackage,
+    pub changes: Vec<Change>,
+}
+
+define_processor!(ChangelogProcessor, Data<'env>, self,
+    process => {
+        self.write_doc(|| {
+            if self.format() == Format::Markdown {
+                return self.markdown_changelog();
+            }
+
+            html!(self, section {class => "section-content"} => {
+                html!(self, h1 {class => "section-title"} => {
+                    html!(self, span {class => "kind"} ~ "changes");
+                    html!(self, span {class => "name-package"} ~
+                          Escape(self.body.package.to_string().as_str()));
+                });
+
+                html!(self, p {} => {
+                    let previous_url = self.package_url(self.body.previous);
+                    write!(self.out(), "Since ")?;
+                    html!(self, a {class => "name-package", href => previous_url} ~
+                          Escape(self.body.previous.to_string().as_str()));
+                });
+
+                if self.body.changes.is_empty() {
+                    html!(self, div {class => "missing-doc"} ~ "no changes");
+                }
+
+                for (title, changes) in sections(&self.body.changes) {
+                    html!(self, h2 {class => "kind"} ~ title);
+
+                    html!(self, table {} => {
+                        for change in changes {
+                            html!(self, tr {class => format!("change change-{}", change.kind)} => {
+                                html!(self, td {class => "kind"} ~ change.item);
+                                html!(self, td {class => "change-path"} ~
+                                      Escape(change.path.as_str()));
+                                html!(self, td {class => "change-details"} ~
+                                      Escape(change.details.join(", ").as_str()));
+                            });
+                        }
+                    });
+                }
+            });
+
+            Ok(())
+        })
+    };
+
+    current_package => &self.body.package;
+);
+
+impl<'env> ChangelogProcessor<'env> {
+    fn markdown_changelog(&self) -> Result<()> {
+        writeln!(self.out(), "# Changes in `{}`", self.body.package)?;
+        writeln!(self.out())?;
+        writeln!(
+            self.out(),
+            "Since [`{}`]({})",
+            self.body.previous,
+            self.package_url(self.body.previous)
+        )?;
+
+        if self.body.changes.is_empty() {
+            writeln!(self.out())?;
+            writeln!(self.out(), "_No changes._")?;
+        }
+
+        for (title, changes) in sections(&self.body.changes) {
+            writeln!(self.out())?;
+            writeln!(self.out(), "## {}", title)?;
+            writeln!(self.out())?;
+            writeln!(self.out(), "| Kind | Path | Details |")?;
+            writeln!(self.out(), "|------|------|---------|")?;
+
+            for change in changes {
+                writeln!(
+                    self.out(),
+                    "| {} | `{}` | {} |",
+                    change.item,
+                    change.path,
+                    change.details.join(", ").replace('|', "\\|")
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use semck::diff::diff;
+    use trans::testing::file;
+
+    /// Describe the changes between two specifications, grouped like on the changelog page.
+    fn changelog(from: &str, to: &str) -> Vec<(&'static str, Vec<String>)> {
+        let changes = diff(&file(from), &file(to));
+
+        sections(&changes)
+            .into_iter()
+            .map(|(title, changes)| (title, changes.iter().map(ToString::to_string).collect()))
+            .collect()
+    }
+
+    #[test]
+    fn test_changelog() {
+        let from = "type Foo { id: u32; old: string; } type Gone {}";
+        let to = "type Foo { id: u64; added?: string; } type New {}";
+
+        let expected = vec![
+            ("Added", vec!["added field `Foo::added`", "added type `New`"]),
+            ("Removed", vec!["removed field `Foo::old`", "removed type `Gone`"]),
+            ("Changed", vec!["changed field `Foo::id`: type changed from `u32` to `u64`"]),
+        ];
+
+        let expected = expected
+            .into_iter()
+            .map(|(title, changes)| (title, changes.into_iter().map(String::from).collect()))
+            .collect::<Vec<(&str, Vec<String>)>>();
+
+        assert_eq!(expected, changelog(from, to));
+    }
+
+    #[test]
+    fn test_changelog_unchanged() {
+        let spec = "type Foo { id: u32; }";
+        assert!(changelog(spec, spec).is_empty());
+    }
+}
@@ -1,7 +1,9 @@
 //! Compiler for generating documentation.
 
-use super::{DOC_CSS_NAME, NORMALIZE_CSS_NAME};
-use core::{RpDecl, RpFile, RpVersionedPackage, WithPos};
+use super::{Deprecations, Format, DOC_CSS_NAME, INDEX, NORMALIZE_CSS_NAME, SEARCH_INDEX_NAME,
+            SEARCH_JS_NAME};
+use changelog_processor::{ChangelogProcessor, Data as ChangelogData};
+use core::{Handle, RelativePath, RelativePathBuf, RpDecl, RpFile, RpVersionedPackage, WithPos};
 use core::errors::*;
 use doc_builder::DocBuilder;
 use enum_processor::EnumProcessor;
@@ -9,13 +11,12 @@ use genco::IoFmt;
 use index_processor::{Data as IndexData, IndexProcessor};
 use interface_processor::InterfaceProcessor;
 use package_processor::{Data as PackageData, PackageProcessor};
-use processor::Processor;
+use processor::{previous_version, Processor};
+use search_index::SearchIndex;
+use semck::diff::diff;
 use service_processor::ServiceProcessor;
 use std::cell::RefCell;
-use std::fs;
-use std::fs::File;
 use std::io::Write;
-use std::path::PathBuf;
 use syntect::highlighting::Theme;
 use syntect::parsing::SyntaxSet;
 use trans::Environment;
@@ -23,14 +24,17 @@ use tuple_processor::TupleProcessor;
 use type_processor::TypeProcessor;
 
 const NORMALIZE_CSS: &[u8] = include_bytes!("static/normalize.css");
+const SEARCH_JS: &[u8] = include_bytes!("static/search.js");
 
 pub struct DocCompiler<'a> {
     pub env: Environment,
-    pub out_path: PathBuf,
+    pub handle: &'a Handle,
+    pub format: Format,
     pub skip_static: bool,
     pub theme_css: &'a [u8],
     pub syntax_theme: &'a Theme,
     pub syntax_set: &'a SyntaxSet,
+    pub deprecated: &'a Deprecations,
 }
 
 impl<'a> DocCompiler<'a> {
@@ -46,10 +50,19 @@ impl<'a> DocCompiler<'a> {
 
         for (package, file) in self.env.for_each_file() {
             self.write_package(package, file)?;
+            self.write_changelog(package, file)?;
         }
 
+        // search and styling only apply to HTML.
+        if self.format != Format::Html {
+            return Ok(());
+        }
+
+        self.write_search_index(self.env.for_each_file())?;
+
         if !self.skip_static {
             self.write_stylesheets()?;
+            self.write_scripts()?;
         }
 
         Ok(())
@@ -63,16 +76,16 @@ impl<'a> DocCompiler<'a> {
 
         // maintain to know where to import static resources from.
         let mut root = Vec::new();
-        let mut path = self.out_path.to_owned();
+        let mut path = RelativePathBuf::new();
 
         for part in package.parts {
             root.push("..");
             path = path.join(part.as_str());
+        }
 
-            if !path.is_dir() {
-                debug!("+dir: {}", path.display());
-                fs::create_dir_all(&path)?;
-            }
+        if !self.handle.is_dir(&path) {
+            debug!("+dir: {}", path.display());
+            self.handle.create_dir_all(&path)?;
         }
 
         let name = decl.name().parts.join(".");
@@ -80,76 +93,133 @@ impl<'a> DocCompiler<'a> {
         // complete path to root and static resources
         let root = root.join("/");
 
-        let out = path.join(format!("{}.{}.html", decl.kind(), name));
-        debug!("+file: {}", out.display());
-        let mut f = File::create(&out)?;
-        let mut fmt = IoFmt(&mut f);
-        let out = RefCell::new(DocBuilder::new(&mut fmt));
-
-        match *decl {
-            Interface(ref body) => InterfaceProcessor {
-                out: out,
-                env: &self.env,
-                syntax: (self.syntax_theme, self.syntax_set),
-                root: &root,
-                body: body,
-            }.process(),
-            Type(ref body) => TypeProcessor {
-                out: out,
-                env: &self.env,
-                syntax: (self.syntax_theme, self.syntax_set),
-                root: &root,
-                body: body,
-            }.process(),
-            Tuple(ref body) => TupleProcessor {
-                out: out,
-                env: &self.env,
-                syntax: (self.syntax_theme, self.syntax_set),
-                root: &root,
-                body: body,
-            }.process(),
-            Enum(ref body) => EnumProcessor {
-                out: out,
-                env: &self.env,
-                syntax: (self.syntax_theme, self.syntax_set),
-                root: &root,
-                body: body,
-            }.process(),
-            Service(ref body) => ServiceProcessor {
-                out: out,
-                env: &self.env,
-                syntax: (self.syntax_theme, self.syntax_set),
-                root: &root,
-                body: body,
-            }.process(),
+        let out_path = path.join(format!("{}.{}.{}", decl.kind(), name, self.format.ext()));
+        let mut buffer = Vec::new();
+
+        {
+            let mut fmt = IoFmt(&mut buffer);
+            let out = RefCell::new(DocBuilder::new(&mut fmt));
+
+            match *decl {
+                Interface(ref body) => InterfaceProcessor {
+                    out: out,
+                    env: &self.env,
+                    syntax: (self.syntax_theme, self.syntax_set),
+                    root: &root,
+                    deprecated: self.deprecated,
+                    format: self.format,
+                    body: body,
+                }.process(),
+                Type(ref body) => TypeProcessor {
+                    out: out,
+                    env: &self.env,
+                    syntax: (self.syntax_theme, self.syntax_set),
+                    root: &root,
+                    deprecated: self.deprecated,
+                    format: self.format,
+                    body: body,
+                }.process(),
+                Tuple(ref body) => TupleProcessor {
+                    out: out,
+                    env: &self.env,
+                    syntax: (self.syntax_theme, self.syntax_set),
+                    root: &root,
+                    deprecated: self.deprecated,
+                    format: self.format,
+                    body: body,
+                }.process(),
+                Enum(ref body) => EnumProcessor {
+                    out: out,
+                    env: &self.env,
+                    syntax: (self.syntax_theme, self.syntax_set),
+                    root: &root,
+                    deprecated: self.deprecated,
+                    format: self.format,
+                    body: body,
+                }.process(),
+                Service(ref body) => ServiceProcessor {
+                    out: out,
+                    env: &self.env,
+                    syntax: (self.syntax_theme, self.syntax_set),
+                    root: &root,
+                    deprecated: self.deprecated,
+                    format: self.format,
+                    body: body,
+                }.process(),
+            }?;
         }
+
+        debug!("+file: {}", out_path.display());
+        self.write(&out_path, &buffer)
+    }
+
+    /// Write the given content to a file in the output directory.
+    fn write(&self, path: &RelativePath, content: &[u8]) -> Result<()> {
+        let mut f = self.handle.create(path)?;
+        f.write_all(content)?;
+        f.flush()?;
+        Ok(())
     }
 
     /// Write stylesheets.
     fn write_stylesheets(&self) -> Result<()> {
-        if !self.out_path.is_dir() {
-            debug!("+dir: {}", self.out_path.display());
-            fs::create_dir_all(&self.out_path)?;
+        let root = RelativePathBuf::new();
+
+        if !self.handle.is_dir(&root) {
+            debug!("+dir: {}", root.display());
+            self.handle.create_dir_all(&root)?;
         }
 
-        let normalize_css = self.out_path.join(NORMALIZE_CSS_NAME);
+        let normalize_css = root.join(NORMALIZE_CSS_NAME);
 
         debug!("+css: {}", normalize_css.display());
-        let mut f = fs::File::create(normalize_css)?;
-        f.write_all(NORMALIZE_CSS)?;
+        self.write(&normalize_css, NORMALIZE_CSS)?;
 
-        let doc_css = self.out_path.join(DOC_CSS_NAME);
+        let doc_css = root.join(DOC_CSS_NAME);
 
         debug!("+css: {}", doc_css.display());
-        let mut f = fs::File::create(doc_css)?;
-        f.write_all(self.theme_css)?;
+        self.write(&doc_css, self.theme_css)?;
+
+        Ok(())
+    }
+
+    /// Write static scripts.
+    fn write_scripts(&self) -> Result<()> {
+        let search_js = RelativePathBuf::new().join(SEARCH_JS_NAME);
+
+        debug!("+js: {}", search_js.display());
+        self.write(&search_js, SEARCH_JS)?;
 
         Ok(())
     }
 
+    /// Write the search index, covering every package.
+    fn write_search_index<'it, I>(&self, entries: I) -> Result<()>
+    where
+        I: IntoIterator<Item = (&'it RpVersionedPackage, &'it RpFile)>,
+    {
+        let mut index = SearchIndex::new();
+
+        for (package, file) in entries {
+            index.add_file(package, file);
+        }
+
+        let root = RelativePathBuf::new();
+
+        if !self.handle.is_dir(&root) {
+            debug!("+dir: {}", root.display());
+            self.handle.create_dir_all(&root)?;
+        }
+
+        let search_index = root.join(SEARCH_INDEX_NAME);
+
+        debug!("+js: {}", search_index.display());
+        self.write(&search_index, index.to_script()?.as_bytes())
+    }
+
     /// Write the package index file index file.
     fn write_package(&self, package: &RpVersionedPackage, file: &RpFile) -> Result<()> {
-        let mut path = self.out_path.to_owned();
+        let mut path = RelativePathBuf::new();
 
         let mut root = Vec::new();
 
@@ -158,22 +228,61 @@ impl<'a> DocCompiler<'a> {
             path = path.join(part);
         }
 
-        let index_html = path.join("index.html");
-        let mut f = File::create(&index_html)?;
+        let index_path = path.join(format!("{}.{}", INDEX, self.format.ext()));
+        let mut buffer = Vec::new();
 
         PackageProcessor {
-            out: RefCell::new(DocBuilder::new(&mut IoFmt(&mut f))),
+            out: RefCell::new(DocBuilder::new(&mut IoFmt(&mut buffer))),
             env: &self.env,
             syntax: (self.syntax_theme, self.syntax_set),
             root: &root.join("/"),
+            deprecated: self.deprecated,
+            format: self.format,
             body: &PackageData {
                 package: package,
                 file: file,
             },
         }.process()?;
 
-        debug!("+file: {}", index_html.display());
-        Ok(())
+        debug!("+file: {}", index_path.display());
+        self.write(&index_path, &buffer)
+    }
+
+    /// Write the changes since the previous version of the package, if there is one.
+    fn write_changelog(&self, package: &RpVersionedPackage, file: &RpFile) -> Result<()> {
+        let (previous, previous_file) = match previous_version(&self.env, package) {
+            Some(previous) => previous,
+            None => return Ok(()),
+        };
+
+        let mut path = RelativePathBuf::new();
+
+        let mut root = Vec::new();
+
+        for part in package.as_package(|v| v.to_string()).parts {
+            root.push("..");
+            path = path.join(part);
+        }
+
+        let changes_path = path.join(format!("changes.{}", self.format.ext()));
+        let mut buffer = Vec::new();
+
+        ChangelogProcessor {
+            out: RefCell::new(DocBuilder::new(&mut IoFmt(&mut buffer))),
+            env: &self.env,
+            syntax: (self.syntax_theme, self.syntax_set),
+            root: &root.join("/"),
+            deprecated: self.deprecated,
+            format: self.format,
+            body: &ChangelogData {
+                package: package,
+                previous: previous,
+                changes: diff(previous_file, file),
+            },
+        }.process()?;
+
+        debug!("+file: {}", changes_path.display());
+        self.write(&changes_path, &buffer)
     }
 
     /// Write the root index file.
@@ -181,20 +290,154 @@ impl<'a> DocCompiler<'a> {
     where
         I: IntoIterator<Item = (&'it RpVersionedPackage, &'it RpFile)>,
     {
-        let index_html = self.out_path.join("index.html");
-        let mut f = File::create(&index_html)?;
+        let index_path = RelativePathBuf::new().join(format!("{}.{}", INDEX, self.format.ext()));
+        let mut buffer = Vec::new();
 
         let entries = entries.into_iter().collect();
 
         IndexProcessor {
-            out: RefCell::new(DocBuilder::new(&mut IoFmt(&mut f))),
+            out: RefCell::new(DocBuilder::new(&mut IoFmt(&mut buffer))),
             env: &self.env,
             syntax: (self.syntax_theme, self.syntax_set),
             root: &".",
+            deprecated: self.deprecated,
+            format: self.format,
             body: &IndexData { entries: entries },
         }.process()?;
 
-        debug!("+file: {}", index_html.display());
-        Ok(())
+        debug!("+file: {}", index_path.display());
+        self.write(&index_path, &buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::{CapturingFilesystem, Filesystem};
+    use std::collections::HashMap;
+    use trans::testing::environment;
+
+    const SPEC: &str = r#"
+    /// A foo.
+    type Foo {
+      /// The bar.
+      bar?: Bar;
+    }
+
+    type Bar {
+      id: u32;
+    }
+
+    service Svc {
+      #[http(path = "/bar/{id}")]
+      get(id: u32) -> Bar;
+    }
+    "#;
+
+    /// Compile documentation for the given specification, and return the content of every file.
+    fn compile(spec: &str, format: Format) -> HashMap<String, String> {
+        let fs = CapturingFilesystem::new();
+        let handle = fs.filesystem().open_root(None).expect("to open root");
+
+        let syntax_theme = Theme::default();
+        let syntax_set = ::load_syntax_set();
+        let deprecated = Deprecations::new();
+
+        DocCompiler {
+            env: environment(spec),
+            handle: handle.as_ref(),
+            format: format,
+            skip_static: true,
+            theme_css: &[],
+            syntax_theme: &syntax_theme,
+            syntax_set: &syntax_set,
+            deprecated: &deprecated,
+        }.compile()
+            .expect("to compile documentation");
+
+        let files = fs.files().borrow();
+
+        files
+            .iter()
+            .map(|(path, content)| {
+                let content = String::from_utf8(content.clone()).expect("utf-8 content");
+                (path.as_str().to_string(), content)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_markdown_files() {
+        let files = compile(SPEC, Format::Markdown);
+
+        let mut paths = files.keys().map(String::as_str).collect::<Vec<_>>();
+        paths.sort();
+
+        let expected = vec![
+            "index.md",
+            "test/index.md",
+            "test/service.Svc.md",
+            "test/type.Bar.md",
+            "test/type.Foo.md",
+        ];
+
+        assert_eq!(expected, paths);
+
+        assert_eq!(
+            "[Index](./index.md)\n\n# Index\n\n## Packages\n\n| Package | Description |\n\
+             |---------|-------------|\n| [`test`](./test/index.md) |  |\n",
+            files["index.md"]
+        );
+
+        let package = &files["test/index.md"];
+        assert!(package.starts_with(
+            "[Index](../index.md) &mdash; [Package: `test`](../test/index.md)\n\n\
+             # Package `test`\n"
+        ));
+        assert!(package.contains("| [`Foo`](type.Foo.md) | A foo. |\n"));
+        assert!(package.contains(
+            "## Services\n\n| Name | Description |\n|------|-------------|\n\
+             | [`Svc`](service.Svc.md) |  |\n"
+        ));
+    }
+
+    #[test]
+    fn test_markdown_type() {
+        let files = compile(SPEC, Format::Markdown);
+        let foo = &files["test/type.Foo.md"];
+
+        assert!(foo.starts_with(
+            "[Index](../index.md) &mdash; [Package: `test`](../test/index.md)\n\n# type `Foo`\n"
+        ));
+        assert!(foo.contains("```reproto\ntype Foo {\n  /// The bar.\n  bar?: Bar;\n}\n```\n"));
+        assert!(foo.contains(
+            "| Field | Type | Description |\n|-------|------|-------------|\n\
+             | `bar?` | [`Bar`](type.Bar.md) | The bar. |\n"
+        ));
+    }
+
+    #[test]
+    fn test_markdown_service() {
+        let files = compile(SPEC, Format::Markdown);
+        let svc = &files["test/service.Svc.md"];
+
+        assert!(svc.contains(
+            "`get`\n\n* Argument `id`: `u32`\n* Response: [`Bar`](type.Bar.md)\n"
+        ));
+        assert!(svc.contains(
+            "### Path parameters\n\n| Parameter | Type |\n|-----------|------|\n| `id` | `u32` |\n"
+        ));
+        assert!(svc.contains("### Response: [`Bar`](type.Bar.md)\n\n```json\n"));
+        assert!(svc.contains("### Example\n\n```bash\ncurl -X GET 'http://localhost:8080/bar/"));
+    }
+
+    #[test]
+    fn test_html_links() {
+        let files = compile(SPEC, Format::Html);
+        let foo = &files["test/type.Foo.html"];
+
+        assert!(foo.contains("href=\"type.Bar.html\""));
+        assert!(foo.contains("href=\"../test/index.html\""));
+        assert!(!files.contains_key("test/type.Foo.md"));
     }
 }
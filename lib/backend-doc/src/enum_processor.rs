@@ -1,27 +1,28 @@
 //! Processor for service declarations.
 
+use super::Format;
 use core::{Loc, RpEnumBody, RpVariant};
 use core::errors::*;
 use doc_builder::DocBuilder;
 use escape::Escape;
 use macros::FormatAttribute;
-use processor::Processor;
+use processor::{summary_all, Processor};
 use std::rc::Rc;
 use trans::Environment;
 
-define_processor!(EnumProcessor, RpEnumBody, self,
+define_processor!(EnumProcessor, Loc<RpEnumBody>, self,
     process => {
         self.write_doc(|| {
             let id = self.body.name.join("_");
+            let class = "section-content section-enum";
 
-            html!(self, section {id => &id, class => "section-content section-enum"} => {
+            self.element("section", &[("id", id.as_str()), ("class", class)], || {
                 self.section_title("enum", &self.body.name)?;
                 self.doc(&self.body.comment)?;
+                self.source(Loc::pos(self.body))?;
                 self.variants(self.body.variants.iter())?;
-                self.nested_decls(self.body.decls.iter())?;
-            });
-
-            Ok(())
+                self.nested_decls(self.body.decls.iter())
+            })
         })
     };
 
@@ -39,6 +40,27 @@ impl<'p> EnumProcessor<'p> {
             return Ok(());
         }
 
+        if self.format() == Format::Markdown {
+            writeln!(self.out())?;
+            writeln!(self.out(), "## Variants")?;
+            writeln!(self.out())?;
+            writeln!(self.out(), "| Variant | Value | Description |")?;
+            writeln!(self.out(), "|---------|-------|-------------|")?;
+
+            for variant in it {
+                writeln!(
+                    self.out(),
+                    "| <a name=\"{}\"></a>`{}` | `\"{}\"` | {} |",
+                    variant.name.join("_"),
+                    variant.ident.as_str(),
+                    variant.ordinal(),
+                    summary_all(&variant.comment)
+                )?;
+            }
+
+            return Ok(());
+        }
+
         for variant in it {
             let id = variant.name.join("_");
 
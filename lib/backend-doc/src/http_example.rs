@@ -0,0 +1,270 @@
+//! How to call an endpoint over HTTP.
+//!
+//! Built once per endpoint, and rendered by every output format.
+
+use core::{RpAccept, RpEndpoint, RpPathPart, RpPathSpec, RpServiceBody, RpType};
+use core::errors::*;
+use sample::Sampler;
+use serde_json::{self, Value};
+use trans::Environment;
+
+/// URL used in examples, unless the service specifies one.
+const DEFAULT_URL: &str = "http://localhost:8080";
+
+/// The request or the response of an endpoint.
+pub struct Payload<'a> {
+    /// Type of the payload.
+    pub ty: &'a RpType,
+    /// Language and content of a sample payload, unless the type can't be sampled.
+    pub example: Option<(&'static str, String)>,
+}
+
+/// How to call an endpoint over HTTP, with examples.
+pub struct HttpExample<'a> {
+    /// HTTP method of the endpoint.
+    pub method: &'a str,
+    /// Full URL of the endpoint, with variables in the path.
+    pub route: String,
+    /// Variables in the path, and their types if they are arguments.
+    pub vars: Vec<(&'a str, Option<&'a RpType>)>,
+    /// Body of the request, if the endpoint takes one.
+    pub request: Option<Payload<'a>>,
+    /// Response of the endpoint, if it has one.
+    pub response: Option<Payload<'a>>,
+    /// A `curl` command which calls the endpoint with sample values.
+    pub curl: String,
+}
+
+impl<'a> HttpExample<'a> {
+    /// Build the example for the given endpoint, or `None` if it is not exposed over HTTP.
+    pub fn new(
+        env: &Environment,
+        service: &'a RpServiceBody,
+        endpoint: &'a RpEndpoint,
+    ) -> Result<Option<HttpExample<'a>>> {
+        let path = match endpoint.http.path {
+            Some(ref path) => path,
+            None => return Ok(None),
+        };
+
+        let method = endpoint
+            .http
+            .method
+            .as_ref()
+            .map(|m| m.as_str())
+            .unwrap_or("GET");
+
+        let url = service
+            .http
+            .url
+            .as_ref()
+            .map(|url| url.trim_right_matches('/'))
+            .unwrap_or(DEFAULT_URL);
+
+        let vars = path.vars()
+            .map(|var| (var, endpoint.arguments.get(var).map(|a| a.1.ty())))
+            .collect::<Vec<_>>();
+
+        // same seed for every endpoint, so that examples are stable across builds.
+        let mut sampler = Sampler::new(env, 0);
+
+        let body = endpoint
+            .http
+            .body
+            .as_ref()
+            .and_then(|body| endpoint.arguments.get(body));
+
+        let mut request_value = None;
+
+        let request = match body {
+            Some(&(_, ref channel)) => {
+                request_value = sample(&mut sampler, service, channel.ty());
+
+                let example = match request_value {
+                    Some(ref value) => Some(("json", json(value)?)),
+                    None => None,
+                };
+
+                Some(Payload {
+                    ty: channel.ty(),
+                    example: example,
+                })
+            }
+            None => None,
+        };
+
+        let response = match endpoint.response {
+            Some(ref response) => {
+                let example = match sample(&mut sampler, service, response.ty()) {
+                    Some(Value::String(ref text)) if endpoint.http.accept == RpAccept::Text => {
+                        Some(("text", text.to_string()))
+                    }
+                    Some(ref value) => Some(("json", json(value)?)),
+                    None => None,
+                };
+
+                Some(Payload {
+                    ty: response.ty(),
+                    example: example,
+                })
+            }
+            None => None,
+        };
+
+        let curl = curl(
+            &mut sampler,
+            service,
+            method,
+            url,
+            path,
+            endpoint,
+            request_value.as_ref(),
+        )?;
+
+        Ok(Some(HttpExample {
+            method: method,
+            route: format!("{}{}", url, path),
+            vars: vars,
+            request: request,
+            response: response,
+            curl: curl,
+        }))
+    }
+}
+
+/// Build a `curl` command which calls the endpoint with sample values.
+fn curl(
+    sampler: &mut Sampler,
+    service: &RpServiceBody,
+    method: &str,
+    url: &str,
+    path: &RpPathSpec,
+    endpoint: &RpEndpoint,
+    request: Option<&Value>,
+) -> Result<String> {
+    let full = full_url(url, path, |var| {
+        endpoint
+            .arguments
+            .get(var)
+            .and_then(|a| sample(sampler, service, a.1.ty()))
+            .map(|value| match value {
+                Value::String(string) => string,
+                other => other.to_string(),
+            })
+            .unwrap_or_else(|| var.to_string())
+    });
+
+    let accept = match endpoint.http.accept {
+        RpAccept::Json => "application/json",
+        RpAccept::Text => "text/plain",
+    };
+
+    let mut curl = format!(
+        "curl -X {} {} \\\n  -H {}",
+        method,
+        shell_quote(&full),
+        shell_quote(&format!("Accept: {}", accept))
+    );
+
+    if let Some(request) = request {
+        let request = serde_json::to_string(request).map_err(|e| e.to_string())?;
+
+        curl.push_str(" \\\n  -H 'Content-Type: application/json'");
+        curl.push_str(&format!(" \\\n  -d {}", shell_quote(&request)));
+    }
+
+    Ok(curl)
+}
+
+/// Build the full URL of an endpoint, using the given function for the values of variables.
+fn full_url<F>(url: &str, path: &RpPathSpec, mut value: F) -> String
+where
+    F: FnMut(&str) -> String,
+{
+    let mut full = url.to_string();
+
+    for step in &path.steps {
+        full.push('/');
+
+        for part in &step.parts {
+            match *part {
+                RpPathPart::Segment(ref segment) => full.push_str(&percent_encode(segment)),
+                RpPathPart::Variable(ref var) => full.push_str(&percent_encode(&value(var))),
+            }
+        }
+    }
+
+    if path.steps.is_empty() {
+        full.push('/');
+    }
+
+    full
+}
+
+/// Percent-encode a path segment, leaving only unreserved characters as they are.
+fn percent_encode(value: &str) -> String {
+    let mut buffer = String::with_capacity(value.len());
+
+    for b in value.bytes() {
+        match b {
+            b'a'...b'z' | b'A'...b'Z' | b'0'...b'9' | b'-' | b'.' | b'_' | b'~' => {
+                buffer.push(b as char)
+            }
+            b => buffer.push_str(&format!("%{:02X}", b)),
+        }
+    }
+
+    buffer
+}
+
+/// Quote an argument for a POSIX shell.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Sample a value of the given type, or `None` if it can't be sampled.
+fn sample(sampler: &mut Sampler, service: &RpServiceBody, ty: &RpType) -> Option<Value> {
+    match sampler.sample_type(ty) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            warn!("{}: cannot sample `{}`: {}", service.name, ty, e.message());
+            None
+        }
+    }
+}
+
+/// Pretty-print a sample value.
+fn json(value: &Value) -> Result<String> {
+    serde_json::to_string_pretty(value).map_err(|e| e.to_string().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::RpPathStep;
+
+    #[test]
+    fn test_full_url() {
+        let path = RpPathSpec {
+            steps: vec![
+                RpPathStep {
+                    parts: vec![RpPathPart::Segment("foo bar".to_string())],
+                },
+                RpPathStep {
+                    parts: vec![RpPathPart::Variable("id".to_string())],
+                },
+            ],
+        };
+
+        let full = full_url(DEFAULT_URL, &path, |_| "a/b?c=$d'e".to_string());
+        assert_eq!("http://localhost:8080/foo%20bar/a%2Fb%3Fc%3D%24d%27e", full);
+
+        let root = RpPathSpec { steps: vec![] };
+        assert_eq!("http://localhost:8080/", full_url(DEFAULT_URL, &root, |v| v.to_string()));
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!("'it'\\''s'", shell_quote("it's"));
+    }
+}
@@ -1,11 +1,12 @@
 //! Processor for service declarations.
 
+use super::Format;
 use core::{RpFile, RpVersionedPackage};
 use core::errors::*;
 use doc_builder::DocBuilder;
 use escape::Escape;
 use macros::FormatAttribute;
-use processor::Processor;
+use processor::{summary, Processor};
 use trans::Environment;
 
 pub struct Data<'a> {
@@ -15,6 +16,10 @@ pub struct Data<'a> {
 define_processor!(IndexProcessor, Data<'env>, self,
     process => {
         self.write_doc(|| {
+            if self.format() == Format::Markdown {
+                return self.markdown_index();
+            }
+
             html!(self, section {class => "section-content"} => {
                 html!(self, h1 {class => "section-title"} ~ "Index");
 
@@ -42,4 +47,25 @@ define_processor!(IndexProcessor, Data<'env>, self,
     };
 );
 
-impl<'env> IndexProcessor<'env> {}
+impl<'env> IndexProcessor<'env> {
+    fn markdown_index(&self) -> Result<()> {
+        writeln!(self.out(), "# Index")?;
+        writeln!(self.out())?;
+        writeln!(self.out(), "## Packages")?;
+        writeln!(self.out())?;
+        writeln!(self.out(), "| Package | Description |")?;
+        writeln!(self.out(), "|---------|-------------|")?;
+
+        for (package, file) in self.body.entries.iter().cloned() {
+            writeln!(
+                self.out(),
+                "| [`{}`]({}) | {} |",
+                package,
+                self.package_url(package),
+                summary(&file.comment)
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,30 +1,31 @@
 //! Processor for service declarations.
 
-use core::{RpInterfaceBody, RpSubType};
+use super::Format;
+use core::{Loc, RpInterfaceBody, RpSubType};
 use core::errors::Result;
 use doc_builder::DocBuilder;
 use macros::FormatAttribute;
 use processor::Processor;
 use trans::Environment;
 
-define_processor!(InterfaceProcessor, RpInterfaceBody, self,
+define_processor!(InterfaceProcessor, Loc<RpInterfaceBody>, self,
     process => {
         self.write_doc(|| {
             let id = self.body.name.join("_");
+            let class = "section-content section-interface";
 
-            html!(self, section {id => &id, class => "section-content section-interface"} => {
+            self.element("section", &[("id", id.as_str()), ("class", class)], || {
                 self.section_title("interface", &self.body.name)?;
 
                 self.doc(&self.body.comment)?;
+                self.source(Loc::pos(self.body))?;
 
                 for sub_type in self.body.sub_types.iter() {
                     self.sub_type(sub_type)?;
                 }
 
-                self.nested_decls(self.body.decls.iter())?;
-            });
-
-            Ok(())
+                self.nested_decls(self.body.decls.iter())
+            })
         })
     };
 
@@ -35,12 +36,23 @@ impl<'p> InterfaceProcessor<'p> {
     fn sub_type(&self, sub_type: &RpSubType) -> Result<()> {
         let id = sub_type.name.join("_");
 
-        html!(self, h2 {id => id, class => "sub-type-title"} => {
-            html!(self, span {class => "kind"} ~ "subtype");
-            self.full_name_without_package(&sub_type.name)?;
-        });
-
-        self.doc(&self.body.comment)?;
+        match self.format() {
+            Format::Html => html!(self, h2 {id => id, class => "sub-type-title"} => {
+                html!(self, span {class => "kind"} ~ "subtype");
+                self.full_name_without_package(&sub_type.name)?;
+            }),
+            Format::Markdown => {
+                writeln!(self.out())?;
+                writeln!(
+                    self.out(),
+                    "## <a name=\"{}\"></a>Sub-type `{}`",
+                    id,
+                    sub_type.name
+                )?;
+            }
+        }
+
+        self.doc(&sub_type.comment)?;
 
         let fields = self.body.fields.iter().chain(sub_type.fields.iter());
         self.fields(fields)?;
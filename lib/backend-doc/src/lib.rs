@@ -7,14 +7,22 @@ extern crate pulldown_cmark;
 extern crate reproto_backend as backend;
 extern crate reproto_core as core;
 extern crate reproto_manifest as manifest;
+extern crate reproto_sample as sample;
+extern crate reproto_semck as semck;
 extern crate reproto_trans as trans;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
 extern crate syntect;
 
 #[macro_use]
 mod macros;
+mod changelog_processor;
 mod doc_builder;
 mod doc_compiler;
 mod escape;
+mod http_example;
 mod processor;
 mod service_processor;
 mod tuple_processor;
@@ -24,19 +32,25 @@ mod interface_processor;
 mod index_processor;
 mod package_processor;
 mod rendering;
+mod search_index;
 
 pub const NORMALIZE_CSS_NAME: &str = "normalize.css";
 pub const DOC_CSS_NAME: &str = "doc.css";
+pub const SEARCH_JS_NAME: &str = "search.js";
+pub const SEARCH_INDEX_NAME: &str = "search-index.js";
 pub const EXT: &str = "html";
+pub const MD_EXT: &str = "md";
 pub const INDEX: &str = "index";
 pub const DEFAULT_THEME: &str = "light";
 pub const DEFAULT_SYNTAX_THEME: &str = "ayu-mirage";
 
 use clap::{App, Arg, ArgMatches};
+use core::{Context, RpVersionedPackage};
 use core::errors::*;
 use doc_compiler::DocCompiler;
 use manifest::Manifest;
 use std::collections::HashMap;
+use std::rc::Rc;
 use syntect::dumps::from_binary;
 use syntect::highlighting::{Theme, ThemeSet};
 use syntect::parsing::SyntaxSet;
@@ -44,6 +58,27 @@ use trans::Environment;
 
 include!(concat!(env!("OUT_DIR"), "/themes.rs"));
 
+/// Deprecation notices of published versions of packages.
+pub type Deprecations = HashMap<RpVersionedPackage, String>;
+
+/// Format to generate documentation in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Html,
+    /// GitHub-flavoured markdown.
+    Markdown,
+}
+
+impl Format {
+    /// Extension of the generated files.
+    pub fn ext(&self) -> &'static str {
+        match *self {
+            Format::Html => EXT,
+            Format::Markdown => MD_EXT,
+        }
+    }
+}
+
 fn build_themes() -> HashMap<&'static str, &'static [u8]> {
     let mut m = HashMap::new();
 
@@ -96,6 +131,14 @@ pub fn shared_options<'a, 'b>(out: App<'a, 'b>) -> App<'a, 'b> {
             .help("List available syntax themes"),
     );
 
+    let out = out.arg(
+        Arg::with_name("format")
+            .long("format")
+            .takes_value(true)
+            .possible_values(&["html", "markdown"])
+            .help("Format to generate documentation in (default: html)"),
+    );
+
     let out = out.arg(
         Arg::with_name("skip-static")
             .long("skip-static")
@@ -199,7 +242,17 @@ fn list_syntax_themes() -> Result<()> {
     Ok(())
 }
 
-pub fn compile(env: Environment, matches: &ArgMatches, manifest: Manifest) -> Result<()> {
+/// Compile documentation for every package in the environment.
+///
+/// Files are written through the filesystem of the given context.
+/// `deprecated` contains deprecation notices for published versions, which are highlighted.
+pub fn compile(
+    ctx: Rc<Context>,
+    env: Environment,
+    matches: &ArgMatches,
+    manifest: Manifest,
+    deprecated: Deprecations,
+) -> Result<()> {
     let themes = build_themes();
 
     let mut done = false;
@@ -220,11 +273,12 @@ pub fn compile(env: Environment, matches: &ArgMatches, manifest: Manifest) -> Re
     }
 
     let skip_static = matches.is_present("skip-static");
-    let out = manifest
-        .output
-        .as_ref()
-        .ok_or("Missing `--out` or `output=`")?
-        .clone();
+    let handle = ctx.filesystem(manifest.output.as_ref().map(AsRef::as_ref))?;
+
+    let format = match matches.value_of("format") {
+        Some("markdown") => Format::Markdown,
+        _ => Format::Html,
+    };
 
     with_initialized(
         matches,
@@ -233,18 +287,16 @@ pub fn compile(env: Environment, matches: &ArgMatches, manifest: Manifest) -> Re
         |syntax_theme, syntax_set, theme_css| {
             let compiler = DocCompiler {
                 env: env,
-                out_path: out.clone(),
+                handle: handle.as_ref(),
+                format: format,
                 skip_static: skip_static,
                 theme_css: theme_css,
                 syntax_theme: syntax_theme,
                 syntax_set: syntax_set,
+                deprecated: &deprecated,
             };
 
             compiler.compile()
         },
-    )?;
-
-    println!("Wrote documentation in: {}", out.display());
-
-    Ok(())
+    )
 }
@@ -43,6 +43,8 @@ macro_rules! define_processor {
             pub env: &'env Environment,
             pub syntax: (&'env ::syntect::highlighting::Theme, &'env ::syntect::parsing::SyntaxSet),
             pub root: &'env str,
+            pub deprecated: &'env ::Deprecations,
+            pub format: ::Format,
             pub body: &'env $body,
         }
 
@@ -59,6 +61,14 @@ macro_rules! define_processor {
                 self.root
             }
 
+            fn deprecated(&self) -> &'env ::Deprecations {
+                self.deprecated
+            }
+
+            fn format(&self) -> ::Format {
+                self.format
+            }
+
             fn syntax(&self) -> (
                 &'env ::syntect::highlighting::Theme,
                 &'env ::syntect::parsing::SyntaxSet,
@@ -2,12 +2,13 @@
 //!
 //! Build an overview of available packages.
 
+use super::Format;
 use core::{RpDecl, RpFile, RpVersionedPackage};
 use core::errors::*;
 use doc_builder::DocBuilder;
 use escape::Escape;
 use macros::FormatAttribute;
-use processor::Processor;
+use processor::{previous_version, summary, Processor};
 use trans::Environment;
 
 pub struct Data<'a> {
@@ -17,7 +18,19 @@ pub struct Data<'a> {
 
 macro_rules! types_section {
     ($slf:ident, $var:ident, $name:expr) => {
-        if !$var.is_empty() {
+        if !$var.is_empty() && $slf.format() == Format::Markdown {
+            writeln!($slf.out())?;
+            writeln!($slf.out(), "## {}", $name)?;
+            writeln!($slf.out())?;
+            writeln!($slf.out(), "| Name | Description |")?;
+            writeln!($slf.out(), "|------|-------------|")?;
+
+            for v in $var {
+                write!($slf.out(), "| ")?;
+                $slf.full_name_without_package(&v.name)?;
+                writeln!($slf.out(), " | {} |", summary(&v.comment))?;
+            }
+        } else if !$var.is_empty() {
             html!($slf, h2 {class => "kind"} ~ $name);
 
             html!($slf, table {} => {
@@ -58,23 +71,47 @@ define_processor!(PackageProcessor, Data<'env>, self,
                 }
             }
 
-            html!(self, section {class => "section-content"} => {
-                html!(self, h1 {class => "section-title"} => {
-                    html!(self, span {class => "kind"} ~ "package");
-                    html!(self, span {class => "name-package"} ~
-                          Escape(self.body.package.to_string().as_str()));
-                });
+            self.element("section", &[("class", "section-content")], || {
+                match self.format() {
+                    Format::Html => html!(self, h1 {class => "section-title"} => {
+                        html!(self, span {class => "kind"} ~ "package");
+                        html!(self, span {class => "name-package"} ~
+                              Escape(self.body.package.to_string().as_str()));
+                    }),
+                    Format::Markdown => {
+                        writeln!(self.out(), "# Package `{}`", self.body.package)?;
+                    }
+                }
 
                 self.doc(self.body.file.comment.iter())?;
 
+                if let Some((previous, _)) = previous_version(self.env(), self.body.package) {
+                    let changes_url = format!("changes.{}", self.format().ext());
+
+                    match self.format() {
+                        Format::Html => html!(self, p {} => {
+                            html!(self, a {href => changes_url} ~
+                                  Escape(format!("Changes since {}", previous).as_str()));
+                        }),
+                        Format::Markdown => {
+                            writeln!(self.out())?;
+                            writeln!(
+                                self.out(),
+                                "[Changes since `{}`]({})",
+                                previous,
+                                changes_url
+                            )?;
+                        }
+                    }
+                }
+
                 types_section!(self, types, "Types");
                 types_section!(self, interfaces, "Interfaces");
                 types_section!(self, enums, "Enums");
                 types_section!(self, tuples, "Tuples");
                 types_section!(self, services, "Services");
-            });
-
-            Ok(())
+                Ok(())
+            })
         })
     };
 
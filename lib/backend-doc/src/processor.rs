@@ -1,17 +1,75 @@
 //! Processor trait.
 
-use super::{DOC_CSS_NAME, NORMALIZE_CSS_NAME};
-use core::{ForEachLoc, Loc, RpDecl, RpField, RpName, RpType, RpVersionedPackage, WithPos};
+use super::{Format, DOC_CSS_NAME, NORMALIZE_CSS_NAME, SEARCH_INDEX_NAME, SEARCH_JS_NAME};
+use core::{ForEachLoc, Loc, Pos, RpDecl, RpField, RpFile, RpName, RpType, RpVersionedPackage,
+           WithPos};
 use core::errors::*;
 use doc_builder::DocBuilder;
 use escape::Escape;
 use macros::FormatAttribute;
 use rendering::markdown_to_html;
+use std::io::Read;
 use std::ops::DerefMut;
 use syntect::highlighting::Theme;
 use syntect::parsing::SyntaxSet;
 use trans::Environment;
 
+/// Every documented version of the given package, from the oldest to the newest.
+///
+/// A package without a version is the one being worked on, and is considered the newest.
+pub fn versions<'a>(
+    env: &'a Environment,
+    package: &RpVersionedPackage,
+) -> Vec<(&'a RpVersionedPackage, &'a RpFile)> {
+    let mut versions = env.for_each_file()
+        .filter(|&(versioned, _)| versioned.package == package.package)
+        .collect::<Vec<_>>();
+
+    versions.sort_by_key(|&(versioned, _)| {
+        (versioned.version.is_none(), versioned.version.clone())
+    });
+    versions
+}
+
+/// The documented version which precedes the given package, if any.
+pub fn previous_version<'a>(
+    env: &'a Environment,
+    package: &RpVersionedPackage,
+) -> Option<(&'a RpVersionedPackage, &'a RpFile)> {
+    let versions = versions(env, package);
+
+    match versions.iter().position(|&(versioned, _)| versioned == package) {
+        Some(index) if index > 0 => Some(versions[index - 1]),
+        _ => None,
+    }
+}
+
+/// First line of documentation, suitable for a markdown table cell.
+pub fn summary(comment: &[String]) -> String {
+    comment
+        .iter()
+        .next()
+        .map(|line| line.trim().replace('|', "\\|"))
+        .unwrap_or_default()
+}
+
+/// All of the documentation on a single line, suitable for a markdown table cell.
+pub fn summary_all(comment: &[String]) -> String {
+    let lines = comment.iter().map(|line| line.trim()).collect::<Vec<_>>();
+    lines.join(" ").replace('|', "\\|")
+}
+
+/// Strip up to `indent` characters of leading whitespace from the given line.
+fn strip_indent(line: &str, indent: usize) -> &str {
+    let n: usize = line.chars()
+        .take(indent)
+        .take_while(|c| c.is_whitespace())
+        .map(char::len_utf8)
+        .sum();
+
+    &line[n..]
+}
+
 pub trait Processor<'env> {
     /// Access the current builder.
     fn out(&self) -> ::std::cell::RefMut<DocBuilder<'env>>;
@@ -22,6 +80,12 @@ pub trait Processor<'env> {
     /// Path to root.
     fn root(&self) -> &'env str;
 
+    /// Deprecation notices of published versions.
+    fn deprecated(&self) -> &'env ::Deprecations;
+
+    /// Format to render in.
+    fn format(&self) -> Format;
+
     /// Process the given request.
     fn process(self) -> Result<()>;
 
@@ -60,54 +124,160 @@ pub trait Processor<'env> {
             let path = name.package.as_package(|v| v.to_string()).parts.join("/");
 
             return Ok(format!(
-                "{}/{}/{}.{}.html{}",
+                "{}/{}/{}.{}.{}{}",
                 self.root(),
                 path,
                 kind,
                 parts.join("."),
+                self.format().ext(),
                 fragment,
             ));
         }
 
-        Ok(format!("{}.{}.html{}", kind, parts.join("."), fragment))
+        Ok(format!(
+            "{}.{}.{}{}",
+            kind,
+            parts.join("."),
+            self.format().ext(),
+            fragment
+        ))
     }
 
     fn markdown(&self, comment: &str) -> Result<()> {
-        if !comment.is_empty() {
-            let (theme, syntax_set) = self.syntax();
-            markdown_to_html(self.out().deref_mut(), comment, theme, syntax_set)?;
+        if comment.is_empty() {
+            return Ok(());
+        }
+
+        match self.format() {
+            Format::Html => {
+                let (theme, syntax_set) = self.syntax();
+                markdown_to_html(self.out().deref_mut(), comment, theme, syntax_set)?;
+            }
+            Format::Markdown => {
+                writeln!(self.out(), "{}", comment)?;
+            }
         }
 
         Ok(())
     }
 
+    /// Render a highlighted code block.
+    fn code(&self, lang: &str, content: &str) -> Result<()> {
+        if self.format() == Format::Markdown {
+            writeln!(self.out())?;
+        }
+
+        self.markdown(&format!("```{}\n{}\n```", lang, content))
+    }
+
+    /// Render the specification of a declaration as a code block.
+    ///
+    /// Only markdown includes it, since HTML links every type of the declaration instead.
+    fn source(&self, pos: &Pos) -> Result<()> {
+        if self.format() != Format::Markdown {
+            return Ok(());
+        }
+
+        let mut content = String::new();
+        pos.object.read()?.read_to_string(&mut content)?;
+
+        let snippet = content
+            .get(pos.start..pos.end)
+            .ok_or_else(|| format!("position out of bounds: {}-{}", pos.start, pos.end))?;
+
+        // following lines are indented relative to the start of the declaration.
+        let indent = content[..pos.start]
+            .rfind('\n')
+            .map(|n| pos.start - n - 1)
+            .unwrap_or(pos.start);
+
+        let lines = snippet
+            .lines()
+            .enumerate()
+            .map(|(n, line)| if n == 0 { line } else { strip_indent(line, indent) })
+            .collect::<Vec<_>>();
+
+        self.code("reproto", &lines.join("\n"))
+    }
+
+    /// Wrap everything written by `body` in an HTML element.
+    ///
+    /// Markdown has no equivalent, so the content is written as it is.
+    fn element<Body>(&self, element: &str, attributes: &[(&str, &str)], body: Body) -> Result<()>
+    where
+        Body: FnOnce() -> Result<()>,
+    {
+        if self.format() == Format::Markdown {
+            return body();
+        }
+
+        write!(self.out(), "<{}", element)?;
+
+        for &(key, value) in attributes {
+            write!(self.out(), " {}=\"{}\"", key, value)?;
+        }
+
+        write!(self.out(), ">")?;
+        self.out().new_line()?;
+        self.out().indent();
+        body()?;
+        self.out().new_line_unless_empty()?;
+        self.out().unindent();
+        write!(self.out(), "</{}>", element)?;
+        self.out().new_line()?;
+        Ok(())
+    }
+
     fn doc<'a, I>(&self, comment: I) -> Result<()>
     where
         I: IntoIterator<Item = &'a String>,
     {
         let mut it = comment.into_iter().peekable();
 
+        if self.format() == Format::Markdown {
+            writeln!(self.out())?;
+        }
+
         if it.peek().is_some() {
             let comment = it.map(ToOwned::to_owned).collect::<Vec<_>>();
             let comment = comment.join("\n");
-            html!(self, div { class => "doc" } => {
-                self.markdown(comment.as_str())?;
-            });
+
+            match self.format() {
+                Format::Html => html!(self, div { class => "doc" } => {
+                    self.markdown(comment.as_str())?;
+                }),
+                Format::Markdown => self.markdown(comment.as_str())?,
+            }
         } else {
-            html!(self, div { class => "missing-doc" } ~ Escape("no documentation :("));
+            match self.format() {
+                Format::Html => {
+                    html!(self, div { class => "missing-doc" } ~ Escape("no documentation :("))
+                }
+                Format::Markdown => writeln!(self.out(), "_No documentation._")?,
+            }
         }
 
         Ok(())
     }
 
     fn primitive(&self, name: &str) -> Result<()> {
-        html!(self, span {class => format!("type-{} type-primitive", name)} ~ name);
+        match self.format() {
+            Format::Html => {
+                html!(self, span {class => format!("type-{} type-primitive", name)} ~ name)
+            }
+            Format::Markdown => write!(self.out(), "`{}`", name)?,
+        }
+
         Ok(())
     }
 
     fn write_type(&self, ty: &RpType) -> Result<()> {
         use self::RpType::*;
 
+        if self.format() == Format::Markdown {
+            return self.write_markdown_type(ty);
+        }
+
         write!(self.out(), "<span class=\"ty\">")?;
 
         match *ty {
@@ -147,7 +317,45 @@ pub trait Processor<'env> {
         Ok(())
     }
 
+    /// Write a type as markdown, with links to any declarations it refers to.
+    fn write_markdown_type(&self, ty: &RpType) -> Result<()> {
+        use self::RpType::*;
+
+        match *ty {
+            Name { ref name } => self.full_name_without_package(name)?,
+            Array { ref inner } => {
+                write!(self.out(), "\\[")?;
+                self.write_type(inner)?;
+                write!(self.out(), "\\]")?;
+            }
+            Map { ref key, ref value } => {
+                write!(self.out(), "{{")?;
+                self.write_type(key)?;
+                write!(self.out(), ": ")?;
+                self.write_type(value)?;
+                write!(self.out(), "}}")?;
+            }
+            ref ty => self.primitive(&ty.to_string())?,
+        }
+
+        Ok(())
+    }
+
     fn field(&self, field: &RpField) -> Result<()> {
+        if self.format() == Format::Markdown {
+            let modifier = if field.is_optional() { "?" } else { "" };
+            write!(self.out(), "| `{}{}`", field.ident(), modifier)?;
+
+            if field.ident() != field.name() {
+                write!(self.out(), " as `\"{}\"`", field.name())?;
+            }
+
+            write!(self.out(), " | ")?;
+            self.write_type(&field.ty)?;
+            writeln!(self.out(), " | {} |", summary_all(&field.comment))?;
+            return Ok(());
+        }
+
         let mut classes = vec!["field"];
 
         if field.is_optional() {
@@ -186,12 +394,29 @@ pub trait Processor<'env> {
     where
         I: Iterator<Item = &'b Loc<RpField>>,
     {
+        let mut fields = fields.peekable();
+
+        if self.format() == Format::Markdown && fields.peek().is_some() {
+            writeln!(self.out())?;
+            writeln!(self.out(), "## Fields")?;
+            writeln!(self.out())?;
+            writeln!(self.out(), "| Field | Type | Description |")?;
+            writeln!(self.out(), "|-------|------|-------------|")?;
+        }
+
         fields.for_each_loc(|field| self.field(field))?;
         Ok(())
     }
 
     /// Render a nested declaration
     fn nested_decl(&self, decl: &RpDecl) -> Result<()> {
+        if self.format() == Format::Markdown {
+            write!(self.out(), "* {} ", decl.kind())?;
+            self.full_name_without_package(&decl.name())?;
+            writeln!(self.out(), ": {}", summary(decl.comment()))?;
+            return Ok(());
+        }
+
         html!(self, h2 {class => "decl-title"} => {
             html!(self, span {class => "kind"} ~ format!("nested {}", decl.kind()));
             self.full_name_without_package(&decl.name())?;
@@ -206,6 +431,14 @@ pub trait Processor<'env> {
     where
         I: Iterator<Item = &'b RpDecl>,
     {
+        let mut decls = decls.peekable();
+
+        if self.format() == Format::Markdown && decls.peek().is_some() {
+            writeln!(self.out())?;
+            writeln!(self.out(), "## Nested declarations")?;
+            writeln!(self.out())?;
+        }
+
         for decl in decls {
             self.nested_decl(decl).with_pos(decl.pos())?;
         }
@@ -215,6 +448,11 @@ pub trait Processor<'env> {
 
     /// Write a section title.
     fn section_title(&self, kind: &str, name: &RpName) -> Result<()> {
+        if self.format() == Format::Markdown {
+            writeln!(self.out(), "# {} `{}`", kind, name)?;
+            return Ok(());
+        }
+
         html!(self, h1 {class => "section-title"} => {
             html!(self, span {class => "kind"} ~ kind);
             self.full_name(name, Some(name))?;
@@ -223,11 +461,30 @@ pub trait Processor<'env> {
         Ok(())
     }
 
-    /// Write a complete HTML document.
+    /// Write a complete document.
     fn write_doc<Body>(&self, body: Body) -> Result<()>
     where
         Body: FnOnce() -> Result<()>,
     {
+        if self.format() == Format::Markdown {
+            write!(self.out(), "[Index]({}/index.{})", self.root(), self.format().ext())?;
+
+            if let Some(package) = self.current_package() {
+                let package_url = self.package_url(package);
+                write!(self.out(), " &mdash; [Package: `{}`]({})", package, package_url)?;
+                self.version_switcher(package)?;
+            }
+
+            writeln!(self.out())?;
+            writeln!(self.out())?;
+
+            if let Some(package) = self.current_package() {
+                self.deprecation(package)?;
+            }
+
+            return body();
+        }
+
         html!(self, html {} => {
             html!(self, head {} => {
                 html!(@open self, meta {charset => "utf-8"});
@@ -260,17 +517,114 @@ pub trait Processor<'env> {
                             let package_url = self.package_url(package);
                             html!(self, span {} ~ "&mdash;");
                             html!(self, a {href => package_url} ~ format!("Package: {}", package));
+                            self.version_switcher(package)?;
                         }
+
+                        html!(@open self, input {
+                            id => "search", type => "search", placeholder => "Search...",
+                            autocomplete => "off"
+                        });
+                        self.out().new_line()?;
                     });
 
+                    html!(self, div {id => "search-results", class => "search-results"} ~ "");
+
+                    if let Some(package) = self.current_package() {
+                        self.deprecation(package)?;
+                    }
+
                     body()?;
                 });
+
+                html!(self, script {
+                    src => format!("{}/{}", self.root(), SEARCH_INDEX_NAME)
+                } ~ "");
+
+                html!(self, script {src => format!("{}/{}", self.root(), SEARCH_JS_NAME)} ~ "");
             });
         });
 
         Ok(())
     }
 
+    /// Write links to every documented version of the given package.
+    fn version_switcher(&self, package: &RpVersionedPackage) -> Result<()> {
+        let versions = versions(self.env(), package);
+
+        if versions.len() < 2 {
+            return Ok(());
+        }
+
+        if self.format() == Format::Markdown {
+            write!(self.out(), " &mdash; Versions:")?;
+
+            for (version, _) in versions {
+                let mut label = version
+                    .version
+                    .as_ref()
+                    .map(ToString::to_string)
+                    .unwrap_or_else(|| "current".to_string());
+
+                // deprecated versions are struck through.
+                if self.deprecated().contains_key(version) {
+                    label = format!("~~{}~~", label);
+                }
+
+                if version == package {
+                    write!(self.out(), " **{}**", label)?;
+                } else {
+                    write!(self.out(), " [{}]({})", label, self.package_url(version))?;
+                }
+            }
+
+            return Ok(());
+        }
+
+        html!(self, span {class => "versions"} => {
+            html!(self, span {} ~ "Versions:");
+
+            for (version, _) in versions {
+                let mut classes = vec!["version"];
+
+                if version == package {
+                    classes.push("current");
+                }
+
+                if self.deprecated().contains_key(version) {
+                    classes.push("deprecated");
+                }
+
+                let label = version
+                    .version
+                    .as_ref()
+                    .map(ToString::to_string)
+                    .unwrap_or_else(|| "current".to_string());
+
+                html!(self, a {class => classes, href => self.package_url(version)} ~ label);
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Write a notice if the given version of a package has been deprecated.
+    fn deprecation(&self, package: &RpVersionedPackage) -> Result<()> {
+        if let Some(message) = self.deprecated().get(package) {
+            if self.format() == Format::Markdown {
+                writeln!(self.out(), "> **Deprecated:** {}", message)?;
+                writeln!(self.out())?;
+                return Ok(());
+            }
+
+            html!(self, div {class => "deprecated-notice"} => {
+                html!(self, strong {} ~ "Deprecated:");
+                html!(self, span {} ~ Escape(message.as_str()));
+            });
+        }
+
+        Ok(())
+    }
+
     fn package_url(&self, package: &RpVersionedPackage) -> String {
         let url = package
             .clone()
@@ -278,7 +632,7 @@ pub trait Processor<'env> {
             .parts
             .join("/");
 
-        format!("{}/{}/index.html", self.root(), url)
+        format!("{}/{}/index.{}", self.root(), url, self.format().ext())
     }
 
     fn fragment_filter(url: &str) -> String {
@@ -349,6 +703,11 @@ pub trait Processor<'env> {
 
     /// Local name fully linked.
     fn full_name_without_package(&self, name: &RpName) -> Result<()> {
+        if self.format() == Format::Markdown {
+            write!(self.out(), "[`{}`]({})", name, self.type_url(name)?)?;
+            return Ok(());
+        }
+
         let mut it = name.parts.iter();
         let local = it.next_back().ok_or_else(|| "local part of name required")?;
 
@@ -0,0 +1,151 @@
+//! Search index for the generated documentation.
+//!
+//! The index is written as a script which assigns a global variable, since browsers don't permit
+//! loading JSON from `file://` URLs.
+
+use core::{Loc, RpDecl, RpField, RpFile, RpName, RpVersionedPackage};
+use core::errors::*;
+use processor::Processor;
+use serde_json;
+use service_processor::ServiceProcessor;
+
+/// A single searchable item.
+#[derive(Debug, Serialize)]
+pub struct Entry {
+    /// Name of the item, like `Foo::Bar` or `Foo::Bar.field`.
+    pub name: String,
+    /// Kind of the item, like `type` or `field`.
+    pub kind: String,
+    pub package: String,
+    /// URL of the item, relative to the root of the documentation.
+    pub url: String,
+    /// Documentation of the item, as plain text.
+    pub doc: String,
+}
+
+#[derive(Debug, Default)]
+pub struct SearchIndex {
+    entries: Vec<Entry>,
+}
+
+impl SearchIndex {
+    pub fn new() -> SearchIndex {
+        SearchIndex::default()
+    }
+
+    /// Add the package, and every declaration in the given file.
+    pub fn add_file(&mut self, package: &RpVersionedPackage, file: &RpFile) {
+        let path = package_path(package);
+
+        self.entries.push(Entry {
+            name: package.to_string(),
+            kind: "package".to_string(),
+            package: package.to_string(),
+            url: format!("{}/index.html", path),
+            doc: file.comment.join(" "),
+        });
+
+        for decl in file.for_each_decl() {
+            self.add_decl(package, &path, decl);
+        }
+    }
+
+    /// Add a declaration, and all its members.
+    fn add_decl(&mut self, package: &RpVersionedPackage, path: &str, decl: &RpDecl) {
+        use self::RpDecl::*;
+
+        let name = decl.name();
+        let page = format!("{}/{}.{}.html", path, decl.kind(), name.parts.join("."));
+
+        self.entries.push(Entry {
+            name: name.join("::"),
+            kind: decl.kind().to_string(),
+            package: package.to_string(),
+            url: page.clone(),
+            doc: decl.comment().join(" "),
+        });
+
+        match *decl {
+            Type(ref body) => self.add_fields(package, &page, name, body.fields.iter()),
+            Tuple(ref body) => self.add_fields(package, &page, name, body.fields.iter()),
+            Interface(ref body) => {
+                self.add_fields(package, &page, name, body.fields.iter());
+
+                for sub_type in &body.sub_types {
+                    self.entries.push(Entry {
+                        name: sub_type.name.join("::"),
+                        kind: "subtype".to_string(),
+                        package: package.to_string(),
+                        url: format!("{}#{}", page, sub_type.name.join("_")),
+                        doc: sub_type.comment.join(" "),
+                    });
+
+                    self.add_fields(package, &page, &sub_type.name, sub_type.fields.iter());
+                }
+            }
+            Enum(ref body) => for variant in &body.variants {
+                self.entries.push(Entry {
+                    name: variant.name.join("::"),
+                    kind: "variant".to_string(),
+                    package: package.to_string(),
+                    url: format!("{}#{}", page, variant.name.join("_")),
+                    doc: variant.comment.join(" "),
+                });
+            },
+            Service(ref body) => for endpoint in body.endpoints.values() {
+                // same fragment as generated by the service processor.
+                let id = format!(
+                    "{}_{}",
+                    body.name,
+                    endpoint
+                        .id_parts(ServiceProcessor::fragment_filter)
+                        .join("_")
+                );
+
+                self.entries.push(Entry {
+                    name: format!("{}.{}", name.join("::"), endpoint.ident()),
+                    kind: "endpoint".to_string(),
+                    package: package.to_string(),
+                    url: format!("{}#{}", page, id),
+                    doc: endpoint.comment.join(" "),
+                });
+            },
+        }
+    }
+
+    /// Add the fields of a declaration.
+    fn add_fields<'a, I>(
+        &mut self,
+        package: &RpVersionedPackage,
+        page: &str,
+        name: &RpName,
+        fields: I,
+    ) where
+        I: IntoIterator<Item = &'a Loc<RpField>>,
+    {
+        for field in fields {
+            self.entries.push(Entry {
+                name: format!("{}.{}", name.join("::"), field.ident()),
+                kind: "field".to_string(),
+                package: package.to_string(),
+                url: page.to_string(),
+                doc: field.comment.join(" "),
+            });
+        }
+    }
+
+    /// Render the index as a script.
+    pub fn to_script(&self) -> Result<String> {
+        let entries = serde_json::to_string(&self.entries).map_err(|e| e.to_string())?;
+        Ok(format!("var searchIndex = {};\n", entries))
+    }
+}
+
+/// Path to the directory of a package, relative to the root of the documentation.
+fn package_path(package: &RpVersionedPackage) -> String {
+    package
+        .clone()
+        .as_package(ToString::to_string)
+        .parts
+        .join("/")
+}
@@ -1,31 +1,33 @@
 //! Processor for service declarations.
 
-use core::{Loc, RpEndpoint, RpServiceBody, WithPos};
+use super::Format;
+use core::{Loc, RpEndpoint, RpServiceBody, RpType, WithPos};
 use core::errors::*;
 use doc_builder::DocBuilder;
 use escape::Escape;
+use http_example::{HttpExample, Payload};
 use macros::FormatAttribute;
 use processor::Processor;
 use trans::Environment;
 
-define_processor!(ServiceProcessor, RpServiceBody, self,
+define_processor!(ServiceProcessor, Loc<RpServiceBody>, self,
     process => {
         self.write_doc(|| {
             let id = self.body.name.join("_");
+            let class = "section-content section-service";
 
-            html!(self, section {id => &id, class => "section-content section-service"} => {
+            self.element("section", &[("id", id.as_str()), ("class", class)], || {
                 self.section_title("service", &self.body.name)?;
 
                 self.doc(&self.body.comment)?;
+                self.source(Loc::pos(self.body))?;
 
                 for endpoint in self.body.endpoints.values() {
                     self.endpoint(endpoint)?;
                 }
 
-                self.nested_decls(self.body.decls.iter())?;
-            });
-
-            Ok(())
+                self.nested_decls(self.body.decls.iter())
+            })
         })
     };
 
@@ -40,50 +42,193 @@ impl<'p> ServiceProcessor<'p> {
             endpoint.id_parts(Self::fragment_filter).join("_")
         );
 
-        html!(self, h2 {class => "endpoint-title", id => id} => {
-            self.name_until(&self.body.name)?;
+        match self.format() {
+            Format::Html => html!(self, h2 {class => "endpoint-title", id => id} => {
+                self.name_until(&self.body.name)?;
 
-            html!(self, span {class => "endpoint-id"} ~ Escape(endpoint.safe_ident()));
-            html!(self, span {} ~ Escape("("));
+                html!(self, span {class => "endpoint-id"} ~ Escape(endpoint.safe_ident()));
+                html!(self, span {} ~ Escape("("));
 
-            let mut it = endpoint.arguments.values().peekable();
+                let mut it = endpoint.arguments.values().peekable();
 
-            while let Some(&(ref name, ref channel)) = it.next() {
-                html!(self, span {class => "endpoint-request-type"} => {
-                    html!(self, span {class => "name"} ~ Escape(name.as_str()));
-                    html!(self, span {class => "sep"} ~ Escape(":"));
+                while let Some(&(ref name, ref channel)) = it.next() {
+                    html!(self, span {class => "endpoint-request-type"} => {
+                        html!(self, span {class => "name"} ~ Escape(name.as_str()));
+                        html!(self, span {class => "sep"} ~ Escape(":"));
 
-                    if channel.is_streaming() {
-                        html!(self, span {class => "keyword"} ~ Escape("stream"));
-                    }
+                        if channel.is_streaming() {
+                            html!(self, span {class => "keyword"} ~ Escape("stream"));
+                        }
 
-                    let (req, pos) = Loc::borrow_pair(channel);
-                    self.write_type(req.ty()).with_pos(pos)?;
-                });
+                        let (req, pos) = Loc::borrow_pair(channel);
+                        self.write_type(req.ty()).with_pos(pos)?;
+                    });
+                }
+
+                html!(self, span {} ~ Escape(")"));
+
+                if let Some(response) = endpoint.response.as_ref().take().as_ref() {
+                    html!(self, span {class => "keyword"} ~ "&rarr;");
+
+                    html!(self, span {class => "endpoint-response-type"} => {
+                        if response.is_streaming() {
+                            html!(self, span {class => "endpoint-stream"} ~ Escape("stream"));
+                        }
+
+                        let (res, pos) = Loc::borrow_pair(response);
+                        self.write_type(res.ty()).with_pos(pos)?;
+                    });
+                }
+
+                if endpoint.ident() != endpoint.name() {
+                    html!(self, span {class => "keyword"} ~ Escape("as"));
+                    html!(self, span {} ~ Escape(endpoint.name()));
+                }
+            }),
+            Format::Markdown => self.markdown_endpoint_title(&id, endpoint)?,
+        }
+
+        self.doc(&endpoint.comment)?;
+        self.http(endpoint)?;
+        Ok(())
+    }
+
+    /// Write the title of an endpoint as markdown, followed by its arguments and response.
+    fn markdown_endpoint_title(&self, id: &str, endpoint: &RpEndpoint) -> Result<()> {
+        writeln!(self.out())?;
+        writeln!(self.out(), "## <a name=\"{}\"></a>`{}`", id, endpoint.ident())?;
+        writeln!(self.out())?;
+
+        for &(ref name, ref channel) in endpoint.arguments.values() {
+            let stream = if channel.is_streaming() { "stream " } else { "" };
+            write!(self.out(), "* Argument `{}`: {}", name.as_str(), stream)?;
+
+            let (req, pos) = Loc::borrow_pair(channel);
+            self.write_type(req.ty()).with_pos(pos)?;
+            writeln!(self.out())?;
+        }
+
+        if let Some(ref response) = endpoint.response {
+            let stream = if response.is_streaming() { "stream " } else { "" };
+            write!(self.out(), "* Response: {}", stream)?;
+
+            let (res, pos) = Loc::borrow_pair(response);
+            self.write_type(res.ty()).with_pos(pos)?;
+            writeln!(self.out())?;
+        }
+
+        if endpoint.ident() != endpoint.name() {
+            writeln!(self.out(), "* Name: `{}`", endpoint.name())?;
+        }
+
+        Ok(())
+    }
+
+    /// Render how to call the endpoint over HTTP, with examples.
+    fn http(&self, endpoint: &RpEndpoint) -> Result<()> {
+        let http = match HttpExample::new(self.env(), self.body, endpoint)? {
+            Some(http) => http,
+            None => return Ok(()),
+        };
+
+        self.element("div", &[("class", "endpoint-http")], || {
+            match self.format() {
+                Format::Html => html!(self, div {class => "endpoint-http-route"} => {
+                    html!(self, span {class => "http-method"} ~ Escape(http.method));
+                    html!(self, span {class => "http-path"} ~ Escape(http.route.as_str()));
+                }),
+                Format::Markdown => {
+                    writeln!(self.out())?;
+                    writeln!(self.out(), "`{} {}`", http.method, http.route)?;
+                }
             }
 
-            html!(self, span {} ~ Escape(")"));
+            if !http.vars.is_empty() {
+                self.http_title("Path parameters", None)?;
+                self.path_parameters(&http.vars)?;
+            }
 
-            if let Some(response) = endpoint.response.as_ref().take().as_ref() {
-                html!(self, span {class => "keyword"} ~ "&rarr;");
+            if let Some(ref request) = http.request {
+                self.http_title("Request body: ", Some(request.ty))?;
+                self.example(request)?;
+            }
 
-                html!(self, span {class => "endpoint-response-type"} => {
-                    if response.is_streaming() {
-                        html!(self, span {class => "endpoint-stream"} ~ Escape("stream"));
-                    }
+            if let Some(ref response) = http.response {
+                self.http_title("Response: ", Some(response.ty))?;
+                self.example(response)?;
+            }
 
-                    let (res, pos) = Loc::borrow_pair(response);
-                    self.write_type(res.ty()).with_pos(pos)?;
-                });
+            self.http_title("Example", None)?;
+            self.code("bash", &http.curl)
+        })
+    }
+
+    /// Write a title in the HTTP section of an endpoint, followed by a type if there is one.
+    fn http_title(&self, title: &str, ty: Option<&RpType>) -> Result<()> {
+        match (self.format(), ty) {
+            (Format::Html, Some(ty)) => html!(self, h3 {class => "endpoint-http-title"} => {
+                write!(self.out(), "{}", title)?;
+                self.write_type(ty)?;
+            }),
+            (Format::Html, None) => html!(self, h3 {class => "endpoint-http-title"} ~ title),
+            (Format::Markdown, ty) => {
+                writeln!(self.out())?;
+                write!(self.out(), "### {}", title)?;
+
+                if let Some(ty) = ty {
+                    self.write_type(ty)?;
+                }
+
+                writeln!(self.out())?;
             }
+        }
+
+        Ok(())
+    }
+
+    /// Write a table of the variables in the path, and their types.
+    fn path_parameters(&self, vars: &[(&str, Option<&RpType>)]) -> Result<()> {
+        if self.format() == Format::Markdown {
+            writeln!(self.out())?;
+            writeln!(self.out(), "| Parameter | Type |")?;
+            writeln!(self.out(), "|-----------|------|")?;
+
+            for &(var, ty) in vars {
+                write!(self.out(), "| `{}` | ", var)?;
 
-            if endpoint.ident() != endpoint.name() {
-                html!(self, span {class => "keyword"} ~ Escape("as"));
-                html!(self, span {} ~ Escape(endpoint.name()));
+                if let Some(ty) = ty {
+                    self.write_type(ty)?;
+                }
+
+                writeln!(self.out(), " |")?;
+            }
+
+            return Ok(());
+        }
+
+        html!(self, table {} => {
+            for &(var, ty) in vars {
+                html!(self, tr {} => {
+                    html!(self, td {class => "name"} ~ Escape(var));
+
+                    html!(self, td {} => {
+                        if let Some(ty) = ty {
+                            self.write_type(ty)?;
+                        }
+                    });
+                });
             }
         });
 
-        self.doc(&endpoint.comment)?;
+        Ok(())
+    }
+
+    /// Render the sample of a payload, if there is one.
+    fn example(&self, payload: &Payload) -> Result<()> {
+        if let Some((lang, ref content)) = payload.example {
+            self.code(lang, content)?;
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,141 @@
+/*
+ * Search through the documentation, using the index in search-index.js.
+ *
+ * Doesn't load anything dynamically, so that it works when browsing from file://.
+ */
+(function() {
+  var MAX_RESULTS = 50;
+
+  // the script is located in the root of the documentation.
+  var script = document.currentScript;
+  var root = script.src.substring(0, script.src.lastIndexOf("/") + 1);
+
+  function score(entry, terms) {
+    var name = entry.name.toLowerCase();
+    var local = name.split(/::|\./).pop();
+    var doc = entry.doc.toLowerCase();
+    var total = 0;
+
+    for (var i = 0; i < terms.length; i++) {
+      var term = terms[i];
+
+      // prefer exact matches on the last part of the name.
+      if (local === term) {
+        total += 4;
+        continue;
+      }
+
+      if (name.indexOf(term) >= 0) {
+        total += 2;
+        continue;
+      }
+
+      if (doc.indexOf(term) >= 0) {
+        total += 1;
+        continue;
+      }
+
+      return 0;
+    }
+
+    return total;
+  }
+
+  function search(query) {
+    var terms = query.toLowerCase().split(/\s+/).filter(function(t) { return t.length > 0; });
+
+    if (terms.length === 0 || typeof searchIndex === "undefined") {
+      return [];
+    }
+
+    var results = [];
+
+    for (var i = 0; i < searchIndex.length; i++) {
+      var s = score(searchIndex[i], terms);
+
+      if (s > 0) {
+        results.push({score: s, entry: searchIndex[i]});
+      }
+    }
+
+    results.sort(function(a, b) {
+      return b.score - a.score || a.entry.name.length - b.entry.name.length;
+    });
+
+    return results.slice(0, MAX_RESULTS).map(function(r) { return r.entry; });
+  }
+
+  function render(container, query, results) {
+    while (container.firstChild) {
+      container.removeChild(container.firstChild);
+    }
+
+    if (query.trim().length === 0) {
+      container.className = "search-results";
+      return;
+    }
+
+    container.className = "search-results active";
+
+    if (results.length === 0) {
+      var empty = document.createElement("div");
+      empty.className = "search-empty";
+      empty.textContent = "No results for `" + query + "`";
+      container.appendChild(empty);
+      return;
+    }
+
+    var table = document.createElement("table");
+
+    results.forEach(function(entry) {
+      var row = document.createElement("tr");
+
+      var kind = document.createElement("td");
+      kind.className = "kind";
+      kind.textContent = entry.kind;
+      row.appendChild(kind);
+
+      var name = document.createElement("td");
+      var link = document.createElement("a");
+      link.className = "name-local";
+      link.href = root + entry.url;
+      link.textContent = entry.name;
+      name.appendChild(link);
+
+      var pkg = document.createElement("span");
+      pkg.className = "name-package";
+      pkg.textContent = entry.package;
+      name.appendChild(pkg);
+      row.appendChild(name);
+
+      var doc = document.createElement("td");
+      doc.className = "package-item-doc";
+      doc.textContent = entry.doc;
+      row.appendChild(doc);
+
+      table.appendChild(row);
+    });
+
+    container.appendChild(table);
+  }
+
+  document.addEventListener("DOMContentLoaded", function() {
+    var input = document.getElementById("search");
+    var container = document.getElementById("search-results");
+
+    if (!input || !container) {
+      return;
+    }
+
+    input.addEventListener("input", function() {
+      render(container, input.value, search(input.value));
+    });
+
+    input.addEventListener("keydown", function(e) {
+      if (e.key === "Escape") {
+        input.value = "";
+        render(container, "", []);
+      }
+    });
+  });
+})();
@@ -1,28 +1,27 @@
 //! Processor for service declarations.
 
-use core::RpTypeBody;
+use core::{Loc, RpTypeBody};
 use core::errors::*;
 use doc_builder::DocBuilder;
-use macros::FormatAttribute;
 use processor::Processor;
 use trans::Environment;
 
-define_processor!(TypeProcessor, RpTypeBody, self,
+define_processor!(TypeProcessor, Loc<RpTypeBody>, self,
     process => {
         self.write_doc(|| {
             let id = self.body.name.join("_");
+            let class = "section-content section-type";
 
-            html!(self, section {id => &id, class => "section-content section-type"} => {
+            self.element("section", &[("id", id.as_str()), ("class", class)], || {
                 self.section_title("type", &self.body.name)?;
 
-                html!(self, div {class => "section-body"} => {
+                self.element("div", &[("class", "section-body")], || {
                     self.doc(&self.body.comment)?;
+                    self.source(Loc::pos(self.body))?;
                     self.fields(self.body.fields.iter())?;
-                    self.nested_decls(self.body.decls.iter())?;
-                });
-            });
-
-            Ok(())
+                    self.nested_decls(self.body.decls.iter())
+                })
+            })
         })
     };
 
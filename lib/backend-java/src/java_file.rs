@@ -45,7 +45,9 @@ where
         extra.package(self.package);
 
         debug!("+class: {}", path.display());
-        IoFmt(&mut handle.create(&path)?.as_mut()).write_file(file, &mut extra)?;
+        let mut f = handle.create(&path)?;
+        IoFmt(&mut f.as_mut()).write_file(file, &mut extra)?;
+        f.flush()?;
 
         Ok(())
     }
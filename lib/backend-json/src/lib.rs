@@ -72,11 +72,9 @@ fn compile(ctx: Rc<Context>, env: Environment, manifest: Manifest) -> Result<()>
         };
 
         debug!("+file: {}", path.display());
-        writeln!(
-            handle.create(&path)?,
-            "{}",
-            serde_json::to_string_pretty(file)?,
-        )?;
+        let mut f = handle.create(&path)?;
+        writeln!(f, "{}", serde_json::to_string_pretty(file)?)?;
+        f.flush()?;
     }
 
     Ok(())
@@ -882,7 +882,7 @@ impl<'el> PackageProcessor<'el> for Compiler<'el> {
 
             if !handle.is_file(&init_path) {
                 debug!("+init: {}", init_path.display());
-                handle.create(&init_path)?;
+                handle.create(&init_path)?.flush()?;
             }
         }
 
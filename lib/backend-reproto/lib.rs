@@ -141,7 +141,9 @@ fn compile(ctx: Rc<Context>, env: Environment, manifest: Manifest) -> Result<()>
         let body = body.join_line_spacing();
 
         debug!("+file: {}", path.display());
-        IoFmt(&mut handle.create(&path)?).write_file(body, &mut ())?;
+        let mut f = handle.create(&path)?;
+        IoFmt(&mut f).write_file(body, &mut ())?;
+        f.flush()?;
     }
 
     Ok(())
@@ -268,6 +268,8 @@ impl<'el> Compiler<'el> {
                 for child in children {
                     writeln!(f, "pub mod {};", child)?;
                 }
+
+                f.flush()?;
             }
         }
 
@@ -0,0 +1,568 @@
+//! Stable codes for diagnostics.
+//!
+//! Every diagnostic reported through a `Reporter` carries one of these codes, which makes it
+//! possible to search for, suppress, and document them.
+//!
+//! Codes must never be re-used or re-numbered once released.
+//!
+//! * `R00xx` - errors when loading specifications.
+//! * `R01xx` - semantic version violations.
+//! * `R02xx` - lint rules.
+
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Code {
+    /// Identifier of the code, like `R0001`.
+    pub id: &'static str,
+    /// Short summary of the diagnostic.
+    pub summary: &'static str,
+    /// Longer explanation, with an example and how to fix it.
+    pub explanation: &'static str,
+}
+
+impl fmt::Display for Code {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(self.id)
+    }
+}
+
+macro_rules! codes {
+    ($($name:ident, $id:expr, $summary:expr, $explanation:expr;)*) => {
+        $(
+        pub const $name: Code = Code {
+            id: $id,
+            summary: $summary,
+            explanation: $explanation,
+        };
+        )*
+
+        /// All known codes.
+        pub static ALL: &[Code] = &[$($name,)*];
+    }
+}
+
+codes! {
+    DUPLICATE_DEFINITION, "R0001", "name is already defined",
+r#"The same name is defined more than once in the same scope.
+
+This applies to fields, variants, sub-types and endpoints, both to their identifiers and to their
+names as they appear on the wire.
+
+Example:
+
+    type Foo {
+      id: string;
+      id: u32;
+    }
+
+Fix: rename or remove one of the definitions. If only the wire names collide, change one of them
+using `as "name"`."#;
+
+    BAD_STRATEGY, "R0002", "unknown sub-type strategy",
+r#"The `strategy` given to `#[type_info(...)]` is not supported.
+
+Example:
+
+    #[type_info(strategy = "wrapped")]
+    interface Foo {
+    }
+
+Fix: use a supported strategy, like `#[type_info(strategy = "tagged", tag = "type")]`."#;
+
+    CONFLICTING_ENDPOINT_ID, "R0003", "conflicting endpoint identifier",
+r#"Two endpoints in the same service translate to the same identifier.
+
+Example:
+
+    service Foo {
+      get_thing() -> Thing;
+      get_thing() -> Other;
+    }
+
+Fix: give each endpoint a unique name."#;
+
+    DUPLICATE_ARGUMENT, "R0004", "argument is already present",
+r#"An endpoint has more than one argument with the same name.
+
+Example:
+
+    service Foo {
+      get(id: string, id: u32) -> Thing;
+    }
+
+Fix: rename or remove one of the arguments."#;
+
+    UNUSED_HTTP_ARGUMENT, "R0005", "argument not used in #[http(...)] attribute",
+r#"An endpoint with a `#[http(...)]` attribute has an argument which isn't part of the path, and
+isn't the request body.
+
+Example:
+
+    service Foo {
+      #[http(path = "/things")]
+      get(id: string) -> Thing;
+    }
+
+Fix: reference the argument in the path, like `#[http(path = "/things/{id}")]`, or remove it."#;
+
+    UNSUPPORTED_RESPONSE, "R0006", "response type not supported for accept",
+r#"The `accept` of an HTTP endpoint can only handle `string` responses, unless it is
+`application/json`.
+
+Example:
+
+    service Foo {
+      #[http(accept = "text/plain")]
+      get() -> Thing;
+    }
+
+Fix: change the response to `string`, or use `accept = "application/json"`."#;
+
+    CONFLICTING_FIELD, "R0007", "conflict in field",
+r#"Two fields in the same declaration have the same identifier or wire name.
+
+Example:
+
+    type Foo {
+      foo_bar: string;
+      fooBar: string;
+    }
+
+Fix: rename or remove one of the fields."#;
+
+    DUPLICATE_ATTRIBUTE_WORD, "R0008", "attribute word already present",
+r#"The same attribute word is used more than once on the same item.
+
+Example:
+
+    #[deprecated]
+    #[deprecated]
+    type Foo {
+    }
+
+Fix: remove the duplicate attribute."#;
+
+    DUPLICATE_ATTRIBUTE, "R0009", "attribute already present",
+r#"The same attribute is specified more than once on the same item.
+
+Example:
+
+    #[http(path = "/foo")]
+    #[http(method = "POST")]
+    get() -> Thing;
+
+Fix: merge the attributes into one, like `#[http(path = "/foo", method = "POST")]`."#;
+
+    CONFLICTING_DECLARATION, "R0010", "conflicting declaration",
+r#"Two declarations with the same name are loaded into the same package.
+
+This typically happens when two files resolve to the same package.
+
+Example:
+
+    type Foo {
+    }
+
+    tuple Foo {
+    }
+
+Fix: rename one of the declarations, or make sure that only one file provides the package."#;
+
+    UNKNOWN_ATTRIBUTE, "R0011", "unknown attribute",
+r#"An attribute, or an entry of an attribute, is not recognized for the item it is attached to.
+
+Example:
+
+    #[tpye_info(strategy = "tagged", tag = "type")]
+    interface Foo {
+    }
+
+Fix: correct the spelling of the attribute, or remove it."#;
+
+    MISSING_PREFIX, "R0012", "missing prefix",
+r#"A type is referenced through a prefix which hasn't been imported with `use`.
+
+Example:
+
+    type Foo {
+      bar: common::Bar;
+    }
+
+Fix: import the package under the prefix, like `use common;`, or correct the prefix."#;
+
+    NO_SUCH_ARGUMENT, "R0013", "no such argument",
+r#"The path or body of a `#[http(...)]` attribute references an argument which the endpoint
+doesn't have.
+
+Example:
+
+    service Foo {
+      #[http(path = "/things/{id}")]
+      get(name: string) -> Thing;
+    }
+
+Fix: rename the variable to match one of the arguments, or add the argument."#;
+
+    UNSUPPORTED_MEDIA_TYPE, "R0014", "unsupported media type",
+r#"The `accept` of an HTTP endpoint is a media type which isn't supported.
+
+Only `application/json` and `text/plain` are supported.
+
+Example:
+
+    service Foo {
+      #[http(accept = "application/xml")]
+      get() -> Thing;
+    }
+
+Fix: use one of the supported media types."#;
+
+    NO_SUCH_METHOD, "R0015", "no such HTTP method",
+r#"The `method` of an HTTP endpoint is not a known HTTP method.
+
+Supported methods are `GET`, `POST`, `PUT`, `UPDATE`, `DELETE`, `PATCH`, and `HEAD`.
+
+Example:
+
+    service Foo {
+      #[http(method = "get")]
+      get() -> Thing;
+    }
+
+Fix: use one of the supported methods, in upper case."#;
+
+    EXPECTED_STRING, "R0016", "expected string",
+r#"The name of a sub-type, as it appears on the wire, must be a string.
+
+Example:
+
+    interface Foo {
+      Bar as 42;
+    }
+
+Fix: use a string, like `Bar as "bar";`."#;
+
+    ILLEGAL_NAMING, "R0017", "illegal naming",
+r#"A naming attribute, like `#![field_naming(...)]`, has a value which isn't a known naming.
+
+Supported namings are `upper_camel`, `lower_camel`, `upper_snake`, and `lower_snake`.
+
+Example:
+
+    #![field_naming(kebab)]
+
+Fix: use one of the supported namings."#;
+
+    ALIAS_IN_USE, "R0018", "alias already in use",
+r#"Two `use` declarations import packages under the same prefix.
+
+The prefix defaults to the last part of the package.
+
+Example:
+
+    use foo.common;
+    use bar.common;
+
+Fix: give one of the packages a different prefix, like `use bar.common as bar_common;`."#;
+
+    NO_PACKAGE_FOUND, "R0019", "no package found",
+r#"A package imported with `use` couldn't be found, or no version of it matches the given
+version requirement.
+
+Example:
+
+    use common "^2";
+
+Fix: make sure the package is available through a path or a repository, or change the version
+requirement."#;
+
+    PACKAGE_ALREADY_REGISTERED, "R0020", "package already registered",
+r#"The same version of a package is loaded more than once.
+
+This typically happens when two files resolve to the same package and version.
+
+Fix: make sure that only one file provides each version of a package."#;
+
+    DECL_REMOVED, "R0101", "declaration removed",
+r#"A declaration was removed without bumping the major version.
+
+Clients compiled against the previous version might depend on the declaration.
+
+Example: `type Foo` is present in version `1.0.0`, but missing in `1.1.0`.
+
+Fix: restore the declaration, or publish the change as a new major version."#;
+
+    DECL_ADDED, "R0102", "declaration added",
+r#"A declaration was added in a patch version.
+
+Additions are only permitted in minor versions.
+
+Example: `type Foo` is missing in version `1.0.0`, but present in `1.0.1`.
+
+Fix: publish the change as a new minor version."#;
+
+    FIELD_REMOVED, "R0103", "field removed",
+r#"A field was removed without bumping the major version.
+
+Example: `type Foo { bar: string; }` in `1.0.0` becomes `type Foo {}` in `1.1.0`.
+
+Fix: restore the field, or publish the change as a new major version."#;
+
+    VARIANT_REMOVED, "R0104", "variant removed",
+r#"An enum variant was removed without bumping the major version.
+
+Example: `enum Foo { A, B }` in `1.0.0` becomes `enum Foo { A }` in `1.1.0`.
+
+Fix: restore the variant, or publish the change as a new major version."#;
+
+    FIELD_ADDED, "R0105", "field added",
+r#"A field was added in a patch version.
+
+Example: `type Foo {}` in `1.0.0` becomes `type Foo { bar?: string; }` in `1.0.1`.
+
+Fix: publish the change as a new minor version."#;
+
+    VARIANT_ADDED, "R0106", "variant added",
+r#"An enum variant was added in a patch version.
+
+Example: `enum Foo { A }` in `1.0.0` becomes `enum Foo { A, B }` in `1.0.1`.
+
+Fix: publish the change as a new minor version."#;
+
+    FIELD_TYPE_CHANGED, "R0107", "field type changed",
+r#"The type of a field changed without bumping the major version.
+
+Example: `bar: string;` in `1.0.0` becomes `bar: u32;` in `1.1.0`.
+
+Fix: add a new field with the new type instead, or publish the change as a new major version."#;
+
+    FIELD_NAME_CHANGED, "R0108", "field name changed",
+r#"The wire name of a field changed without bumping the major version.
+
+Example: `bar: string;` in `1.0.0` becomes `bar: string as "baz";` in `1.1.0`.
+
+Fix: restore the previous name, or publish the change as a new major version."#;
+
+    VARIANT_ORDINAL_CHANGED, "R0109", "variant ordinal changed",
+r#"The ordinal of an enum variant changed without bumping the major version.
+
+Example: `A as "a"` in `1.0.0` becomes `A as "alpha"` in `1.1.0`.
+
+Fix: restore the previous ordinal, or publish the change as a new major version."#;
+
+    FIELD_REQUIRED_CHANGED, "R0110", "field changed to be required",
+r#"An optional field became required without bumping the major version.
+
+Example: `bar?: string;` in `1.0.0` becomes `bar: string;` in `1.1.0`.
+
+Fix: keep the field optional, or publish the change as a new major version."#;
+
+    REQUIRED_FIELD_ADDED, "R0111", "required field added",
+r#"A required field was added without bumping the major version.
+
+Clients of the previous version won't send the field.
+
+Example: `type Foo {}` in `1.0.0` becomes `type Foo { bar: string; }` in `1.1.0`.
+
+Fix: make the field optional (`bar?: string;`), or publish the change as a new major version."#;
+
+    FIELD_MODIFIER_CHANGED, "R0112", "field modifier changed",
+r#"The modifier of a field changed in a patch version.
+
+Example: `bar: string;` in `1.0.0` becomes `bar?: string;` in `1.0.1`.
+
+Fix: publish the change as a new minor version."#;
+
+    ENDPOINT_ADDED, "R0113", "endpoint added",
+r#"An endpoint was added in a patch version.
+
+Example: `service Foo {}` in `1.0.0` becomes `service Foo { get() -> Thing; }` in `1.0.1`.
+
+Fix: publish the change as a new minor version."#;
+
+    ENDPOINT_REMOVED, "R0114", "endpoint removed",
+r#"An endpoint was removed without bumping the major version.
+
+Example: `service Foo { get() -> Thing; }` in `1.0.0` becomes `service Foo {}` in `1.1.0`.
+
+Fix: restore the endpoint, or publish the change as a new major version."#;
+
+    ENDPOINT_REQUEST_CHANGED, "R0115", "endpoint request type changed",
+r#"The request type of an endpoint changed without bumping the major version.
+
+Example: `set(Thing)` in `1.0.0` becomes `set(Other)` in `1.1.0`.
+
+Fix: add a new endpoint instead, or publish the change as a new major version."#;
+
+    ENDPOINT_RESPONSE_CHANGED, "R0116", "endpoint response type changed",
+r#"The response type of an endpoint changed without bumping the major version.
+
+Example: `get() -> Thing` in `1.0.0` becomes `get() -> Other` in `1.1.0`.
+
+Fix: add a new endpoint instead, or publish the change as a new major version."#;
+
+    SUB_TYPE_REMOVED, "R0117", "sub-type removed",
+r#"A sub-type was removed from an interface without bumping the major version.
+
+Example: `interface Foo { A; B; }` in `1.0.0` becomes `interface Foo { A; }` in `1.1.0`.
+
+Fix: restore the sub-type, or publish the change as a new major version."#;
+
+    SUB_TYPE_NAME_CHANGED, "R0118", "sub-type name changed",
+r#"The wire name of a sub-type changed without bumping the major version.
+
+Example: `A as "a";` in `1.0.0` becomes `A as "alpha";` in `1.1.0`.
+
+Fix: restore the previous name, or publish the change as a new major version."#;
+
+    SUB_TYPE_STRATEGY_CHANGED, "R0119", "sub-type strategy changed",
+r#"The strategy used to tell the sub-types of an interface apart changed without bumping the major
+version.
+
+Example: `#[type_info(strategy = "tagged", tag = "type")]` in `1.0.0` becomes
+`#[type_info(strategy = "tagged", tag = "kind")]` in `1.1.0`.
+
+Fix: restore the previous strategy, or publish the change as a new major version."#;
+
+    TUPLE_MEMBER_MOVED, "R0120", "tuple member moved",
+r#"A member of a tuple moved to a different position without bumping the major version.
+
+Tuples are encoded by position, so moving a member changes how it is serialized.
+
+Example: `tuple Foo { a: string; b: u32; }` in `1.0.0` becomes `tuple Foo { b: u32; a: string; }`
+in `1.1.0`.
+
+Fix: restore the previous order, or publish the change as a new major version."#;
+
+    ENDPOINT_NAME_CHANGED, "R0121", "endpoint name changed",
+r#"The wire name of an endpoint changed without bumping the major version.
+
+Example: `get() -> Thing as "Get";` in `1.0.0` becomes `get() -> Thing as "GetThing";` in `1.1.0`.
+
+Fix: restore the previous name, or publish the change as a new major version."#;
+
+    ENDPOINT_HTTP_PATH_CHANGED, "R0122", "endpoint HTTP path changed",
+r#"The HTTP path of an endpoint changed without bumping the major version.
+
+Renaming a variable in the path is permitted, since the name is not part of the request.
+
+Example: `#[http(path = "/things")]` in `1.0.0` becomes `#[http(path = "/items")]` in `1.1.0`.
+
+Fix: restore the previous path, or publish the change as a new major version."#;
+
+    ENDPOINT_HTTP_METHOD_CHANGED, "R0123", "endpoint HTTP method changed",
+r#"The HTTP method of an endpoint changed without bumping the major version.
+
+Example: `#[http(method = "POST")]` in `1.0.0` becomes `#[http(method = "PUT")]` in `1.1.0`.
+
+Fix: restore the previous method, or publish the change as a new major version."#;
+
+    ENDPOINT_HTTP_BODY_CHANGED, "R0124", "endpoint HTTP body changed",
+r#"The argument used as the HTTP body of an endpoint changed without bumping the major version.
+
+Example: `#[http(body = "thing")]` in `1.0.0` becomes `#[http(body = "other")]` in `1.1.0`.
+
+Fix: restore the previous body argument, or publish the change as a new major version."#;
+
+    NAME_REUSED, "R0125", "name reused",
+r#"A field, variant, or endpoint was added with a name that was removed in an earlier published
+version, or which is listed as reserved.
+
+Clients built against the earlier version would interpret the new member as the old one.
+
+Example: `name: string;` is removed in `1.1.0` and added back as `name: u32;` in `1.2.0`.
+
+Fix: pick a different name, and add the old one to `#[reserved(...)]`."#;
+
+    RESERVE_REMOVED, "R0126", "removed name not reserved",
+r#"A field removed in an earlier published version is not listed as reserved.
+
+Example: `name: string;` is removed from `type Foo` in `1.1.0`.
+
+Fix: add the name to the reserved list of the type, like `#[reserved(name)]`."#;
+
+    MISSING_DOCS, "R0201", "missing documentation",
+r#"A declaration has no documentation comment.
+
+Lint rule: `missing_docs`.
+
+Example:
+
+    type Foo {
+    }
+
+Fix: document the declaration, like:
+
+    /// A thing called foo.
+    type Foo {
+    }"#;
+
+    NON_SNAKE_CASE_FIELD, "R0202", "field name is not snake_case",
+r#"The name of a field is not written in snake_case.
+
+Lint rule: `non_snake_case_field`.
+
+Example:
+
+    type Foo {
+      fooBar: string;
+    }
+
+Fix: rename the field to `foo_bar`. Use `as "fooBar"` if the name on the wire has to stay the
+same."#;
+
+    ANY_TYPE, "R0203", "use of the any type",
+r#"A field, or an endpoint argument or response, uses the `any` type.
+
+Lint rule: `any_type`.
+
+Example:
+
+    type Foo {
+      data: any;
+    }
+
+Fix: replace `any` with a declared type."#;
+
+    MISSING_HTTP_METHOD, "R0204", "endpoint has no explicit HTTP method",
+r#"An endpoint doesn't explicitly specify which HTTP method to use.
+
+Lint rule: `missing_http_method`.
+
+Example:
+
+    service Foo {
+      #[http(path = "/things")]
+      get_things() -> [Thing];
+    }
+
+Fix: specify the method, like `#[http(path = "/things", method = "GET")]`."#;
+}
+
+/// Lookup the code with the given identifier.
+pub fn lookup(id: &str) -> Option<&'static Code> {
+    ALL.iter().find(|c| c.id.eq_ignore_ascii_case(id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_unique_codes() {
+        let mut seen = HashSet::new();
+
+        for code in ALL {
+            assert!(seen.insert(code.id), "duplicate code: {}", code.id);
+        }
+    }
+
+    #[test]
+    fn test_lookup() {
+        assert_eq!(Some(&DUPLICATE_DEFINITION), lookup("R0001"));
+        assert_eq!(Some(&DUPLICATE_DEFINITION), lookup("r0001"));
+        assert_eq!(None, lookup("R9999"));
+    }
+}
@@ -5,7 +5,7 @@
 //! This is preferred over results, since it permits reporting complex errors and their
 //! corresponding locations.
 
-use {ErrorPos, Filesystem, Handle};
+use {Code, ErrorPos, Filesystem, Handle};
 use errors::{Error, Result};
 use std::cell::{BorrowError, Ref, RefCell};
 use std::fmt;
@@ -14,10 +14,14 @@ use std::rc::Rc;
 use std::result;
 
 pub enum ContextItem {
-    /// A positional error.
-    ErrorPos(ErrorPos, String),
+    /// A positional error, with its stable code.
+    ErrorPos(ErrorPos, String, Code),
     /// A positional information string.
     InfoPos(ErrorPos, String),
+    /// A positional warning, with its stable code.
+    ///
+    /// Warnings do not cause the reporter to fail.
+    WarningPos(ErrorPos, String, Code),
 }
 
 /// Context for a single reproto run.
@@ -37,9 +41,26 @@ pub struct Reporter<'a> {
 }
 
 impl<'a> Reporter<'a> {
-    pub fn err<P: Into<ErrorPos>, E: fmt::Display>(mut self, pos: P, error: E) -> Self {
+    /// Report an error, identified by the given stable code.
+    pub fn err<P, E>(mut self, code: Code, pos: P, error: E) -> Self
+    where
+        P: Into<ErrorPos>,
+        E: fmt::Display,
+    {
         self.errors
-            .push(ContextItem::ErrorPos(pos.into(), error.to_string()));
+            .push(ContextItem::ErrorPos(pos.into(), error.to_string(), code));
+
+        self
+    }
+
+    /// Report a warning, identified by the given stable code.
+    pub fn warn<P, W>(mut self, code: Code, pos: P, warning: W) -> Self
+    where
+        P: Into<ErrorPos>,
+        W: fmt::Display,
+    {
+        self.errors
+            .push(ContextItem::WarningPos(pos.into(), warning.to_string(), code));
 
         self
     }
@@ -52,11 +73,18 @@ impl<'a> Reporter<'a> {
     }
 
     /// Close the reporter, saving any reported errors to the context.
+    ///
+    /// Returns an error unless only warnings were reported.
     pub fn close(self) -> Option<Error> {
         if self.errors.is_empty() {
             return None;
         }
 
+        let only_warnings = self.errors.iter().all(|e| match *e {
+            ContextItem::WarningPos(..) => true,
+            _ => false,
+        });
+
         let ctx = self.ctx;
 
         let mut errors = ctx.errors
@@ -64,6 +92,11 @@ impl<'a> Reporter<'a> {
             .expect("exclusive mutable access");
 
         errors.extend(self.errors);
+
+        if only_warnings {
+            return None;
+        }
+
         Some(Error::new("Error in Context"))
     }
 }
@@ -114,6 +147,7 @@ impl Context {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use codes;
     use fs::CapturingFilesystem;
     use object::{BytesObject, Object};
     use pos::Pos;
@@ -134,8 +168,8 @@ mod tests {
 
         let a: Result<()> = result.map_err(|e| {
             ctx.report()
-                .err(pos, e)
-                .err(other_pos, "previously reported here")
+                .err(codes::DUPLICATE_DEFINITION, pos, e)
+                .err(codes::DUPLICATE_DEFINITION, other_pos, "previously reported here")
                 .into()
         });
 
@@ -148,4 +182,25 @@ mod tests {
 
         assert_eq!(2, ctx.errors().unwrap().len());
     }
+
+    #[test]
+    fn test_warnings() {
+        let object = BytesObject::new("test".to_string(), Arc::new(Vec::new()));
+        let pos: Pos = (Rc::new(object.clone_object()), 0usize, 0usize).into();
+
+        let ctx = Context::new(Box::new(CapturingFilesystem::new()));
+
+        let e = ctx.report()
+            .warn(codes::DUPLICATE_DEFINITION, pos.clone(), "a warning")
+            .close();
+        assert!(e.is_none());
+
+        let e = ctx.report()
+            .warn(codes::DUPLICATE_DEFINITION, pos.clone(), "a warning")
+            .err(codes::DUPLICATE_DEFINITION, pos, "an error")
+            .close();
+        assert!(e.is_some());
+
+        assert_eq!(3, ctx.errors().unwrap().len());
+    }
 }
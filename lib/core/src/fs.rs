@@ -5,7 +5,7 @@ use errors::Result;
 use linked_hash_map::LinkedHashMap;
 use std::cell::RefCell;
 use std::fs;
-use std::io;
+use std::io::{self, Read, Write};
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
@@ -20,6 +20,9 @@ pub trait Handle {
     fn create_dir_all(&self, path: &RelativePath) -> Result<()>;
 
     /// Create the given file (for writing).
+    ///
+    /// The file must be flushed when written, which reports any errors that occurred while
+    /// writing it.
     fn create(&self, path: &RelativePath) -> Result<Box<io::Write>>;
 }
 
@@ -29,12 +32,44 @@ pub trait Filesystem {
     fn open_root(&self, root: Option<&Path>) -> Result<Box<Handle>>;
 }
 
+/// Write the given content to a file, unless the file already has the same content.
+///
+/// This leaves the modification time of unchanged files untouched.
+pub fn write_if_changed(path: &Path, content: &[u8]) -> Result<()> {
+    if path.is_file() {
+        let mut existing = Vec::new();
+        fs::File::open(path)?.read_to_end(&mut existing)?;
+
+        if existing.as_slice() == content {
+            return Ok(());
+        }
+    }
+
+    fs::File::create(path)?.write_all(content)?;
+    Ok(())
+}
+
 /// Real filesystem implementation.
-pub struct RealFilesystem {}
+pub struct RealFilesystem {
+    /// Only write files whose content has changed.
+    only_changed: bool,
+}
 
 impl RealFilesystem {
     pub fn new() -> RealFilesystem {
-        Self {}
+        Self {
+            only_changed: false,
+        }
+    }
+
+    /// Only write files whose content has changed.
+    ///
+    /// Files are buffered in memory, and written when flushed.
+    pub fn with_only_changed(self, only_changed: bool) -> RealFilesystem {
+        Self {
+            only_changed: only_changed,
+            ..self
+        }
     }
 }
 
@@ -45,10 +80,14 @@ impl Filesystem for RealFilesystem {
         })?
             .to_owned();
 
-        return Ok(Box::new(RealHandle { root: root }));
+        return Ok(Box::new(RealHandle {
+            root: root,
+            only_changed: self.only_changed,
+        }));
 
         struct RealHandle {
             root: PathBuf,
+            only_changed: bool,
         }
 
         impl Handle for RealHandle {
@@ -67,12 +106,54 @@ impl Filesystem for RealFilesystem {
 
             fn create(&self, path: &RelativePath) -> Result<Box<io::Write>> {
                 let path = path.to_path(&self.root);
+
+                if self.only_changed {
+                    return Ok(Box::new(ChangedFileCreate {
+                        path: path,
+                        buffer: Vec::new(),
+                        dirty: true,
+                    }));
+                }
+
                 Ok(Box::new(fs::File::create(&path)?))
             }
         }
     }
 }
 
+/// An 'open file' which is only written when flushed, and if its content has changed.
+///
+/// Content which is never flushed is discarded, like the content of an aborted write.
+struct ChangedFileCreate {
+    path: PathBuf,
+    buffer: Vec<u8>,
+    /// If the buffer has not been written since it was last modified.
+    dirty: bool,
+}
+
+impl io::Write for ChangedFileCreate {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.dirty = true;
+        self.buffer.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+
+        write_if_changed(&self.path, &self.buffer).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::Other,
+                format!("failed to write: {}: {}", self.path.display(), e.message()),
+            )
+        })?;
+
+        self.dirty = false;
+        Ok(())
+    }
+}
+
 /// Capture all filesystem operations in-memory.
 ///
 /// Used (among other things) for rendering output in WASM.
@@ -0,0 +1,179 @@
+//! Directed graphs, used to find cycles between packages and declarations.
+
+use std::cmp;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+/// A directed graph, with nodes identified by strings.
+#[derive(Debug, Default)]
+pub struct Graph {
+    /// All nodes in the graph.
+    pub nodes: BTreeSet<String>,
+    /// Edges from each node to the nodes it references.
+    pub edges: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl Graph {
+    /// Add a node, without any edges.
+    pub fn node(&mut self, node: String) {
+        self.nodes.insert(node);
+    }
+
+    /// Add an edge, and both of the nodes it connects.
+    pub fn edge(&mut self, from: String, to: String) {
+        self.nodes.insert(from.clone());
+        self.nodes.insert(to.clone());
+        self.edges
+            .entry(from)
+            .or_insert_with(BTreeSet::new)
+            .insert(to);
+    }
+
+    /// Find all cycles in the graph.
+    ///
+    /// Each cycle is a strongly connected component with more than one node, or a single node which
+    /// references itself.
+    pub fn cycles(&self) -> Vec<Vec<String>> {
+        let mut tarjan = Tarjan {
+            graph: self,
+            index: 0,
+            indexes: HashMap::new(),
+            lowlinks: HashMap::new(),
+            stack: Vec::new(),
+            on_stack: HashSet::new(),
+            components: Vec::new(),
+        };
+
+        for node in &self.nodes {
+            if !tarjan.indexes.contains_key(node.as_str()) {
+                tarjan.connect(node.as_str());
+            }
+        }
+
+        let mut cycles = Vec::new();
+
+        for mut component in tarjan.components {
+            let is_cycle = if component.len() == 1 {
+                let node = &component[0];
+
+                self.edges
+                    .get(node)
+                    .map(|edges| edges.contains(node))
+                    .unwrap_or(false)
+            } else {
+                true
+            };
+
+            if is_cycle {
+                component.sort();
+                cycles.push(component);
+            }
+        }
+
+        cycles.sort();
+        cycles
+    }
+}
+
+/// Tarjan's algorithm for finding strongly connected components.
+struct Tarjan<'a> {
+    graph: &'a Graph,
+    index: usize,
+    indexes: HashMap<&'a str, usize>,
+    lowlinks: HashMap<&'a str, usize>,
+    stack: Vec<&'a str>,
+    on_stack: HashSet<&'a str>,
+    components: Vec<Vec<String>>,
+}
+
+impl<'a> Tarjan<'a> {
+    fn connect(&mut self, node: &'a str) {
+        self.indexes.insert(node, self.index);
+        self.lowlinks.insert(node, self.index);
+        self.index += 1;
+        self.stack.push(node);
+        self.on_stack.insert(node);
+
+        let graph = self.graph;
+
+        for next in graph.edges.get(node).into_iter().flat_map(|e| e.iter()) {
+            let next = next.as_str();
+
+            let lowlink = if !self.indexes.contains_key(next) {
+                self.connect(next);
+                self.lowlinks[next]
+            } else if self.on_stack.contains(next) {
+                self.indexes[next]
+            } else {
+                continue;
+            };
+
+            let lowlink = cmp::min(self.lowlinks[node], lowlink);
+            self.lowlinks.insert(node, lowlink);
+        }
+
+        if self.lowlinks[node] != self.indexes[node] {
+            return;
+        }
+
+        let mut component = Vec::new();
+
+        while let Some(n) = self.stack.pop() {
+            self.on_stack.remove(n);
+            component.push(n.to_string());
+
+            if n == node {
+                break;
+            }
+        }
+
+        self.components.push(component);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(edges: &[(&str, &str)]) -> Graph {
+        let mut graph = Graph::default();
+
+        for &(from, to) in edges {
+            graph.edge(from.to_string(), to.to_string());
+        }
+
+        graph
+    }
+
+    #[test]
+    fn test_two_cycle() {
+        let graph = graph(&[("a", "b"), ("b", "a"), ("b", "c")]);
+        assert_eq!(vec![vec!["a", "b"]], graph.cycles());
+    }
+
+    #[test]
+    fn test_self_loop() {
+        let graph = graph(&[("a", "a"), ("a", "b")]);
+        assert_eq!(vec![vec!["a"]], graph.cycles());
+    }
+
+    #[test]
+    fn test_acyclic() {
+        let mut graph = graph(&[("a", "b"), ("b", "c"), ("a", "c")]);
+        graph.node("d".to_string());
+        assert!(graph.cycles().is_empty());
+    }
+
+    #[test]
+    fn test_multiple_cycles() {
+        let graph = graph(&[
+            ("a", "b"),
+            ("b", "c"),
+            ("c", "a"),
+            ("c", "d"),
+            ("d", "e"),
+            ("e", "d"),
+        ]);
+
+        assert_eq!(vec![vec!["a", "b", "c"], vec!["d", "e"]], graph.cycles());
+    }
+}
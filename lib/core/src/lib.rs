@@ -20,6 +20,7 @@ mod as_loc;
 mod rp_code;
 mod error_pos;
 mod for_each_loc;
+mod graph;
 mod loc;
 mod mime;
 mod object;
@@ -40,6 +41,7 @@ mod rp_name;
 mod rp_number;
 mod rp_package;
 mod rp_package_format;
+mod rp_reference;
 mod rp_reg;
 mod rp_required_package;
 mod rp_service_body;
@@ -56,14 +58,17 @@ mod context;
 mod attributes;
 mod resolver;
 mod fs;
+pub mod codes;
 pub mod errors;
 pub mod utils;
 
 pub use self::attributes::{Attributes, Selection};
+pub use self::codes::Code;
 pub use self::context::{Context, ContextItem, Reporter};
 pub use self::error_pos::ErrorPos;
 pub use self::for_each_loc::ForEachLoc;
-pub use self::fs::{CapturingFilesystem, Filesystem, Handle, RealFilesystem};
+pub use self::graph::Graph;
+pub use self::fs::{write_if_changed, CapturingFilesystem, Filesystem, Handle, RealFilesystem};
 pub use self::loc::Loc;
 pub use self::mime::Mime;
 pub use self::object::{BytesObject, EmptyObject, Object, PathObject, StdinObject};
@@ -88,6 +93,7 @@ pub use self::rp_number::RpNumber;
 pub use self::rp_package::RpPackage;
 pub use self::rp_package_format::RpPackageFormat;
 pub use self::rp_path_spec::{RpPathPart, RpPathSpec, RpPathStep};
+pub use self::rp_reference::{RpNesting, RpReference, RpReferenceSite};
 pub use self::rp_reg::RpReg;
 pub use self::rp_required_package::RpRequiredPackage;
 pub use self::rp_service_body::{RpServiceBody, RpServiceBodyHttp};
@@ -7,6 +7,9 @@ macro_rules! decl_body {
             pub ident: String,
             pub comment: Vec<String>,
             pub decls: Vec<$crate::rp_decl::RpDecl>,
+            /// Lint rules which are allowed for the declaration.
+            #[serde(skip_serializing_if = "::std::collections::BTreeSet::is_empty")]
+            pub allow: ::std::collections::BTreeSet<String>,
             $($rest)*
         }
     };
@@ -1,6 +1,8 @@
 use {Object, RpPackage, RpRequiredPackage, Version};
 use errors::Result;
+use std::cell::RefCell;
 use std::fmt;
+use std::rc::Rc;
 
 /// A resolved package.
 #[derive(Debug)]
@@ -62,3 +64,14 @@ impl Resolver for EmptyResolver {
         Ok(vec![])
     }
 }
+
+/// A resolver which is shared between several environments.
+impl<T: Resolver + ?Sized> Resolver for Rc<RefCell<T>> {
+    fn resolve(&mut self, package: &RpRequiredPackage) -> Result<Vec<Resolved>> {
+        self.borrow_mut().resolve(package)
+    }
+
+    fn resolve_by_prefix(&mut self, package: &RpPackage) -> Result<Vec<ResolvedByPrefix>> {
+        self.borrow_mut().resolve_by_prefix(package)
+    }
+}
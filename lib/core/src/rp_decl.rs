@@ -2,6 +2,7 @@
 
 use super::{Loc, Pos, RpEnumBody, RpInterfaceBody, RpName, RpReg, RpServiceBody, RpTupleBody,
             RpTypeBody};
+use std::collections::BTreeSet;
 use std::fmt;
 use std::rc::Rc;
 use std::vec;
@@ -84,6 +85,19 @@ impl RpDecl {
         }
     }
 
+    /// Lint rules which are allowed for the declaration.
+    pub fn allow(&self) -> &BTreeSet<String> {
+        use self::RpDecl::*;
+
+        match *self {
+            Type(ref body) => &body.allow,
+            Interface(ref body) => &body.allow,
+            Enum(ref body) => &body.allow,
+            Tuple(ref body) => &body.allow,
+            Service(ref body) => &body.allow,
+        }
+    }
+
     /// Convert a declaration into its registered types.
     pub fn to_reg(&self) -> Vec<RpReg> {
         use self::RpDecl::*;
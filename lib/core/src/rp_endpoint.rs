@@ -2,6 +2,7 @@
 
 use super::{Attributes, Loc, RpChannel, RpPathSpec};
 use linked_hash_map::LinkedHashMap;
+use std::collections::BTreeSet;
 use std::default;
 
 #[derive(Debug, Clone, Serialize)]
@@ -82,6 +83,9 @@ pub struct RpEndpoint {
     pub response: Option<Loc<RpChannel>>,
     /// HTTP configuration.
     pub http: RpEndpointHttp,
+    /// Lint rules which are allowed for the endpoint.
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    pub allow: BTreeSet<String>,
 }
 
 impl RpEndpoint {
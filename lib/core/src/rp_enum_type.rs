@@ -1,6 +1,7 @@
 //! Model for enum types
 
 use super::{RpField, RpModifier, RpType, RpValue};
+use std::collections::BTreeSet;
 use std::fmt;
 
 #[derive(Debug, Clone, Serialize)]
@@ -35,6 +36,7 @@ impl RpEnumType {
             comment: vec![],
             ty: self.as_type(),
             field_as: None,
+            allow: BTreeSet::new(),
         }
     }
 }
@@ -1,6 +1,7 @@
 //! Data Models for fields
 
 use super::{RpModifier, RpType};
+use std::collections::BTreeSet;
 
 #[derive(Debug, Clone, Serialize, PartialEq, Eq)]
 pub struct RpField {
@@ -18,6 +19,9 @@ pub struct RpField {
     /// Alias of field in JSON.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub field_as: Option<String>,
+    /// Lint rules which are allowed for the field.
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    pub allow: BTreeSet<String>,
 }
 
 impl RpField {
@@ -1,12 +1,14 @@
 //! File declarations
 
-use super::RpDecl;
+use super::{RpDecl, RpVersionedPackage};
 use std::collections::LinkedList;
 
 #[derive(Debug, Clone, Serialize)]
 pub struct RpFile {
     pub comment: Vec<String>,
     pub decls: Vec<RpDecl>,
+    /// Packages imported through `use` declarations.
+    pub uses: Vec<RpVersionedPackage>,
 }
 
 /// Iterator over all declarations in a file.
@@ -98,6 +98,13 @@ impl RpName {
         }
     }
 
+    /// Identify the declaration, including the package it belongs to, like `foo.bar::Baz`.
+    ///
+    /// Unlike `Display`, this ignores the prefix that the name was imported through.
+    pub fn qualified(&self) -> String {
+        format!("{}::{}", self.package, self.join("::"))
+    }
+
     pub fn is_same(&self, other: &RpName) -> bool {
         self.package == other.package && self.parts == other.parts
     }
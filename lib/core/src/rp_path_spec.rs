@@ -1,5 +1,6 @@
 //! Path specifications
 
+use std::fmt;
 use std::vec;
 
 /// A part of a step.
@@ -53,3 +54,24 @@ impl RpPathSpec {
         }
     }
 }
+
+impl fmt::Display for RpPathSpec {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        if self.steps.is_empty() {
+            return fmt.write_str("/");
+        }
+
+        for step in &self.steps {
+            fmt.write_str("/")?;
+
+            for part in &step.parts {
+                match *part {
+                    RpPathPart::Variable(ref var) => write!(fmt, "{{{}}}", var)?,
+                    RpPathPart::Segment(ref segment) => fmt.write_str(segment)?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,165 @@
+//! References from declarations to other declarations.
+
+use super::{Loc, Pos, RpDecl, RpField, RpFile, RpName, RpType};
+use std::fmt;
+
+/// Where in a declaration a reference is made.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RpReferenceSite<'a> {
+    /// Field of a type, interface, or sub-type.
+    Field(&'a str),
+    /// Member of a tuple, at the given position.
+    TupleMember(usize, &'a str),
+    /// Argument of an endpoint.
+    Argument(&'a str, &'a str),
+    /// Response of an endpoint.
+    Response(&'a str),
+}
+
+impl<'a> fmt::Display for RpReferenceSite<'a> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        use self::RpReferenceSite::*;
+
+        match *self {
+            Field(field) => write!(fmt, "field `{}`", field),
+            TupleMember(index, field) => write!(fmt, "tuple member #{} `{}`", index, field),
+            Argument(endpoint, argument) => {
+                write!(fmt, "argument `{}` of endpoint `{}`", argument, endpoint)
+            }
+            Response(endpoint) => write!(fmt, "response of endpoint `{}`", endpoint),
+        }
+    }
+}
+
+/// How a reference is nested in the type at the site.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RpNesting {
+    /// Element of an array.
+    ArrayElement,
+    /// Key of a map.
+    MapKey,
+    /// Value of a map.
+    MapValue,
+}
+
+impl fmt::Display for RpNesting {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        use self::RpNesting::*;
+
+        match *self {
+            ArrayElement => fmt.write_str("array element"),
+            MapKey => fmt.write_str("map key"),
+            MapValue => fmt.write_str("map value"),
+        }
+    }
+}
+
+/// A reference from one declaration to another, by name.
+#[derive(Debug, Clone)]
+pub struct RpReference<'a> {
+    /// Declaration which makes the reference.
+    pub owner: &'a RpName,
+    /// Kind of the declaration making the reference, like `type` or `sub-type`.
+    pub owner_kind: &'static str,
+    /// Where in the declaration the reference is made.
+    pub site: RpReferenceSite<'a>,
+    /// Containers the name is nested in, from the outermost.
+    pub nesting: Vec<RpNesting>,
+    /// The referenced name.
+    pub name: &'a RpName,
+    /// Position of the field, argument, or response making the reference.
+    pub pos: &'a Pos,
+}
+
+impl RpFile {
+    /// Collect every reference made by the declarations in this file.
+    pub fn references(&self) -> Vec<RpReference> {
+        use self::RpDecl::*;
+
+        let mut out = Vec::new();
+
+        for decl in self.for_each_decl() {
+            match *decl {
+                Type(ref body) => {
+                    fields(&mut out, &body.name, "type", &body.fields);
+                }
+                Tuple(ref body) => for (index, field) in body.fields.iter().enumerate() {
+                    let site = RpReferenceSite::TupleMember(index, field.ident());
+                    let pos = Loc::pos(field);
+                    walk(&mut out, &body.name, "tuple", &site, pos, &field.ty, &mut vec![]);
+                },
+                Interface(ref body) => {
+                    fields(&mut out, &body.name, "interface", &body.fields);
+
+                    for sub_type in &body.sub_types {
+                        fields(&mut out, &sub_type.name, "sub-type", &sub_type.fields);
+                    }
+                }
+                Enum(_) => {}
+                Service(ref body) => for endpoint in body.endpoints.values() {
+                    for &(ref argument, ref channel) in endpoint.arguments.values() {
+                        let site = RpReferenceSite::Argument(endpoint.ident(), argument.as_str());
+                        let (pos, ty) = (Loc::pos(channel), channel.ty());
+                        walk(&mut out, &body.name, "service", &site, pos, ty, &mut vec![]);
+                    }
+
+                    if let Some(ref response) = endpoint.response {
+                        let site = RpReferenceSite::Response(endpoint.ident());
+                        let (pos, ty) = (Loc::pos(response), response.ty());
+                        walk(&mut out, &body.name, "service", &site, pos, ty, &mut vec![]);
+                    }
+                },
+            }
+        }
+
+        return out;
+
+        fn fields<'a>(
+            out: &mut Vec<RpReference<'a>>,
+            owner: &'a RpName,
+            owner_kind: &'static str,
+            fields: &'a [Loc<RpField>],
+        ) {
+            for field in fields {
+                let site = RpReferenceSite::Field(field.ident());
+                walk(out, owner, owner_kind, &site, Loc::pos(field), &field.ty, &mut vec![]);
+            }
+        }
+
+        fn walk<'a>(
+            out: &mut Vec<RpReference<'a>>,
+            owner: &'a RpName,
+            owner_kind: &'static str,
+            site: &RpReferenceSite<'a>,
+            pos: &'a Pos,
+            ty: &'a RpType,
+            nesting: &mut Vec<RpNesting>,
+        ) {
+            match *ty {
+                RpType::Name { ref name } => out.push(RpReference {
+                    owner: owner,
+                    owner_kind: owner_kind,
+                    site: site.clone(),
+                    nesting: nesting.clone(),
+                    name: name,
+                    pos: pos,
+                }),
+                RpType::Array { ref inner } => {
+                    nesting.push(RpNesting::ArrayElement);
+                    walk(out, owner, owner_kind, site, pos, inner, nesting);
+                    nesting.pop();
+                }
+                RpType::Map { ref key, ref value } => {
+                    nesting.push(RpNesting::MapKey);
+                    walk(out, owner, owner_kind, site, pos, key, nesting);
+                    nesting.pop();
+
+                    nesting.push(RpNesting::MapValue);
+                    walk(out, owner, owner_kind, site, pos, value, nesting);
+                    nesting.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+}
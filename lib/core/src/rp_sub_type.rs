@@ -1,6 +1,7 @@
 //! Model for sub-types
 
 use super::{Loc, RpCode, RpDecl, RpField, RpName};
+use std::collections::BTreeSet;
 
 #[derive(Debug, Clone, Serialize)]
 pub struct RpSubType {
@@ -9,6 +10,9 @@ pub struct RpSubType {
     pub comment: Vec<String>,
     /// Inner declarations.
     pub decls: Vec<RpDecl>,
+    /// Lint rules which are allowed for the sub-type.
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    pub allow: BTreeSet<String>,
     pub fields: Vec<Loc<RpField>>,
     pub codes: Vec<Loc<RpCode>>,
     #[serde(skip_serializing_if = "Option::is_none")]
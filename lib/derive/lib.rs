@@ -211,7 +211,9 @@ impl<'a> FieldInit<'a> {
                     name
                 };
 
-                Type::Name { name: name }
+                Type::Name {
+                    name: Loc::new(name, self.pos.clone()),
+                }
             }
         };
 
@@ -0,0 +1,396 @@
+//! Linter for specifications.
+//!
+//! A linter is a set of rules which are checked against every declaration, field, and endpoint.
+//! Each rule has a level which decides if it's ignored, reported as a warning, or reported as an
+//! error. Rules can also be allowed locally using the `#[allow(<rule>)]` attribute, which applies
+//! to the item it's attached to and everything declared inside of it.
+
+extern crate reproto_core as core;
+extern crate reproto_naming as naming;
+
+mod rules;
+
+pub use self::rules::{AnyType, MissingDocs, MissingHttpMethod, NonSnakeCaseField};
+use core::{Code, Context, ErrorPos, Loc, RpDecl, RpEndpoint, RpField, RpFile};
+use core::errors::*;
+use std::collections::BTreeSet;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    /// Rule is not checked.
+    Allow,
+    /// Violations are reported as warnings.
+    Warn,
+    /// Violations are reported as errors.
+    Deny,
+}
+
+/// A single violation of a rule.
+pub struct Lint {
+    pub pos: ErrorPos,
+    pub message: String,
+}
+
+impl Lint {
+    pub fn new<P: Into<ErrorPos>, M: Into<String>>(pos: P, message: M) -> Lint {
+        Lint {
+            pos: pos.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// A rule which can be checked by the linter.
+///
+/// All checks default to doing nothing, so a rule only has to implement the ones it cares about.
+pub trait Rule {
+    /// Name of the rule, as used in the manifest and in `#[allow(...)]`.
+    fn name(&self) -> &'static str;
+
+    /// Code used when reporting violations of the rule.
+    fn code(&self) -> Code;
+
+    /// Level of the rule, unless configured otherwise.
+    fn default_level(&self) -> Level {
+        Level::Warn
+    }
+
+    /// Check the given declaration.
+    fn check_decl(&self, _decl: &RpDecl, _lints: &mut Vec<Lint>) {}
+
+    /// Check the given field.
+    fn check_field(&self, _field: &Loc<RpField>, _lints: &mut Vec<Lint>) {}
+
+    /// Check the given endpoint.
+    fn check_endpoint(&self, _endpoint: &Loc<RpEndpoint>, _lints: &mut Vec<Lint>) {}
+}
+
+/// Summary of a linter run.
+#[derive(Debug, Default)]
+pub struct Summary {
+    /// Number of reported warnings.
+    pub warnings: u64,
+    /// Number of reported errors.
+    pub errors: u64,
+}
+
+impl Summary {
+    /// Merge another summary into this one.
+    pub fn merge(&mut self, other: Summary) {
+        self.warnings += other.warnings;
+        self.errors += other.errors;
+    }
+}
+
+pub struct Linter {
+    rules: Vec<(Box<Rule>, Level)>,
+}
+
+impl Linter {
+    /// Build a linter without any rules.
+    pub fn new() -> Linter {
+        Linter { rules: Vec::new() }
+    }
+
+    /// Build a linter with all built-in rules.
+    pub fn builtin() -> Linter {
+        Linter::new()
+            .with_rule(MissingDocs)
+            .with_rule(NonSnakeCaseField)
+            .with_rule(AnyType)
+            .with_rule(MissingHttpMethod)
+    }
+
+    /// Add the given rule, using its default level.
+    pub fn with_rule<R: 'static + Rule>(mut self, rule: R) -> Linter {
+        let level = rule.default_level();
+        self.rules.push((Box::new(rule), level));
+        self
+    }
+
+    /// Set the level of the rule with the given name.
+    pub fn set_level(&mut self, name: &str, level: Level) -> Result<()> {
+        if let Some(rule) = self.rules.iter_mut().find(|r| r.0.name() == name) {
+            rule.1 = level;
+            return Ok(());
+        }
+
+        let names: Vec<&str> = self.rules.iter().map(|r| r.0.name()).collect();
+
+        Err(format!(
+            "no such lint rule `{}`, expected one of: {}",
+            name,
+            names.join(", ")
+        ).into())
+    }
+
+    /// Lint all declarations in the given file, reporting violations to the context.
+    pub fn lint_file(&self, ctx: &Context, file: &RpFile) -> Summary {
+        let mut summary = Summary::default();
+        let allowed = BTreeSet::new();
+
+        for decl in &file.decls {
+            self.decl(ctx, &mut summary, &allowed, decl);
+        }
+
+        summary
+    }
+
+    fn decl(
+        &self,
+        ctx: &Context,
+        summary: &mut Summary,
+        allowed: &BTreeSet<String>,
+        decl: &RpDecl,
+    ) {
+        use core::RpDecl::*;
+
+        let allowed = union(allowed, decl.allow());
+
+        self.check(ctx, summary, &allowed, |rule, lints| {
+            rule.check_decl(decl, lints)
+        });
+
+        let decls = match *decl {
+            Type(ref body) => {
+                self.fields(ctx, summary, &allowed, &body.fields);
+                &body.decls
+            }
+            Tuple(ref body) => {
+                self.fields(ctx, summary, &allowed, &body.fields);
+                &body.decls
+            }
+            Interface(ref body) => {
+                self.fields(ctx, summary, &allowed, &body.fields);
+
+                for sub_type in &body.sub_types {
+                    let allowed = union(&allowed, &sub_type.allow);
+
+                    self.fields(ctx, summary, &allowed, &sub_type.fields);
+
+                    for decl in &sub_type.decls {
+                        self.decl(ctx, summary, &allowed, decl);
+                    }
+                }
+
+                &body.decls
+            }
+            Enum(ref body) => &body.decls,
+            Service(ref body) => {
+                for endpoint in body.endpoints.values() {
+                    let allowed = union(&allowed, &endpoint.allow);
+
+                    self.check(ctx, summary, &allowed, |rule, lints| {
+                        rule.check_endpoint(endpoint, lints)
+                    });
+                }
+
+                &body.decls
+            }
+        };
+
+        for decl in decls {
+            self.decl(ctx, summary, &allowed, decl);
+        }
+    }
+
+    fn fields(
+        &self,
+        ctx: &Context,
+        summary: &mut Summary,
+        allowed: &BTreeSet<String>,
+        fields: &[Loc<RpField>],
+    ) {
+        for field in fields {
+            let allowed = union(allowed, &field.allow);
+
+            self.check(ctx, summary, &allowed, |rule, lints| {
+                rule.check_field(field, lints)
+            });
+        }
+    }
+
+    /// Run the given check for all rules which are not allowed, and report any violations.
+    fn check<F>(&self, ctx: &Context, summary: &mut Summary, allowed: &BTreeSet<String>, check: F)
+    where
+        F: Fn(&Rule, &mut Vec<Lint>),
+    {
+        for &(ref rule, level) in &self.rules {
+            if level == Level::Allow || allowed.contains(rule.name()) {
+                continue;
+            }
+
+            let mut lints = Vec::new();
+            check(rule.as_ref(), &mut lints);
+
+            for lint in lints {
+                let message = format!("{} ({})", lint.message, rule.name());
+
+                let r = ctx.report();
+
+                let r = match level {
+                    Level::Deny => {
+                        summary.errors += 1;
+                        r.err(rule.code(), lint.pos, message)
+                    }
+                    _ => {
+                        summary.warnings += 1;
+                        r.warn(rule.code(), lint.pos, message)
+                    }
+                };
+
+                r.close();
+            }
+        }
+    }
+}
+
+/// Build the union of two sets of allowed rules.
+fn union(a: &BTreeSet<String>, b: &BTreeSet<String>) -> BTreeSet<String> {
+    a.union(b).cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate reproto_trans as trans;
+
+    use self::trans::Environment;
+    use super::*;
+    use core::{BytesObject, CapturingFilesystem, ContextItem, EmptyResolver};
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    /// Lint the given specification with a single rule, at the given level.
+    ///
+    /// Returns the level and message of every reported violation.
+    fn lint<R: 'static + Rule>(rule: R, level: Option<Level>, spec: &str) -> Vec<(Level, String)> {
+        let name = rule.name();
+        let mut linter = Linter::new().with_rule(rule);
+
+        if let Some(level) = level {
+            linter.set_level(name, level).expect("to set level");
+        }
+
+        let ctx = Rc::new(Context::new(Box::new(CapturingFilesystem::new())));
+        let mut env = Environment::new(ctx.clone(), None, Box::new(EmptyResolver));
+        let object = BytesObject::new("test".to_string(), Arc::new(spec.as_bytes().to_vec()));
+        env.import_object(&object, None).expect("to import specification");
+
+        for (_, file) in env.for_each_file() {
+            linter.lint_file(&ctx, file);
+        }
+
+        let errors = ctx.errors().expect("no other borrows");
+
+        errors
+            .iter()
+            .map(|e| match *e {
+                ContextItem::ErrorPos(_, ref message, _) => (Level::Deny, message.clone()),
+                ContextItem::WarningPos(_, ref message, _) => (Level::Warn, message.clone()),
+                ContextItem::InfoPos(..) => panic!("unexpected info"),
+            })
+            .collect()
+    }
+
+    fn warn(message: &str) -> Vec<(Level, String)> {
+        vec![(Level::Warn, message.to_string())]
+    }
+
+    fn deny(message: &str) -> Vec<(Level, String)> {
+        vec![(Level::Deny, message.to_string())]
+    }
+
+    #[test]
+    fn test_missing_docs() {
+        let violating = "type Foo {}";
+        let message = "missing documentation for type `Foo` (missing_docs)";
+
+        assert_eq!(warn(message), lint(MissingDocs, None, violating));
+        assert_eq!(deny(message), lint(MissingDocs, Some(Level::Deny), violating));
+        assert!(lint(MissingDocs, Some(Level::Allow), violating).is_empty());
+
+        assert!(lint(MissingDocs, None, "/// A foo.\ntype Foo {}").is_empty());
+        assert!(lint(MissingDocs, None, "#[allow(missing_docs)]\ntype Foo {}").is_empty());
+    }
+
+    #[test]
+    fn test_non_snake_case_field() {
+        let violating = "type Foo { fooBar: string; }";
+        let message = "field `fooBar` should be snake_case, like `foo_bar` (non_snake_case_field)";
+
+        assert_eq!(warn(message), lint(NonSnakeCaseField, None, violating));
+        assert_eq!(
+            deny(message),
+            lint(NonSnakeCaseField, Some(Level::Deny), violating)
+        );
+        assert!(lint(NonSnakeCaseField, Some(Level::Allow), violating).is_empty());
+
+        assert!(lint(NonSnakeCaseField, None, "type Foo { foo_bar: string; }").is_empty());
+
+        let allowed = "type Foo { #[allow(non_snake_case_field)] fooBar: string; }";
+        assert!(lint(NonSnakeCaseField, None, allowed).is_empty());
+
+        // allowing a rule on a declaration applies to all of its fields.
+        let allowed = "#[allow(non_snake_case_field)] type Foo { fooBar: string; }";
+        assert!(lint(NonSnakeCaseField, None, allowed).is_empty());
+    }
+
+    #[test]
+    fn test_any_type() {
+        let violating = "type Foo { value: {string: [any]}; }";
+        let message = "field `value` uses the `any` type (any_type)";
+
+        assert_eq!(warn(message), lint(AnyType, None, violating));
+        assert_eq!(deny(message), lint(AnyType, Some(Level::Deny), violating));
+        assert!(lint(AnyType, Some(Level::Allow), violating).is_empty());
+
+        assert!(lint(AnyType, None, "type Foo { value: {string: [u32]}; }").is_empty());
+        assert!(lint(AnyType, None, "type Foo { #[allow(any_type)] value: any; }").is_empty());
+
+        let violating = "service Foo { get(value: any) -> any; }";
+
+        assert_eq!(
+            vec![
+                (
+                    Level::Warn,
+                    "argument `value` uses the `any` type (any_type)".to_string(),
+                ),
+                (
+                    Level::Warn,
+                    "response of `get` uses the `any` type (any_type)".to_string(),
+                ),
+            ],
+            lint(AnyType, None, violating)
+        );
+
+        let allowed = "service Foo { #[allow(any_type)] get(value: any) -> any; }";
+        assert!(lint(AnyType, None, allowed).is_empty());
+    }
+
+    #[test]
+    fn test_missing_http_method() {
+        let violating = "service Foo { get() -> string; }";
+        let message = "endpoint `get` has no explicit method, like #[http(method = \"GET\")] \
+                       (missing_http_method)";
+
+        assert_eq!(warn(message), lint(MissingHttpMethod, None, violating));
+        assert_eq!(
+            deny(message),
+            lint(MissingHttpMethod, Some(Level::Deny), violating)
+        );
+        assert!(lint(MissingHttpMethod, Some(Level::Allow), violating).is_empty());
+
+        let clean = "service Foo { #[http(method = \"GET\")] get() -> string; }";
+        assert!(lint(MissingHttpMethod, None, clean).is_empty());
+
+        let allowed = "service Foo { #[allow(missing_http_method)] get() -> string; }";
+        assert!(lint(MissingHttpMethod, None, allowed).is_empty());
+    }
+
+    #[test]
+    fn test_set_level_unknown_rule() {
+        let mut linter = Linter::builtin();
+        assert!(linter.set_level("missing_docs", Level::Deny).is_ok());
+        assert!(linter.set_level("no_such_rule", Level::Deny).is_err());
+    }
+}
@@ -0,0 +1,136 @@
+//! Built-in lint rules.
+
+use super::{Lint, Rule};
+use core::{codes, Code, Loc, RpDecl, RpEndpoint, RpField, RpType};
+use naming::{self, Naming};
+
+/// All declarations must be documented.
+pub struct MissingDocs;
+
+impl Rule for MissingDocs {
+    fn name(&self) -> &'static str {
+        "missing_docs"
+    }
+
+    fn code(&self) -> Code {
+        codes::MISSING_DOCS
+    }
+
+    fn check_decl(&self, decl: &RpDecl, lints: &mut Vec<Lint>) {
+        if decl.comment().is_empty() {
+            lints.push(Lint::new(
+                decl.pos(),
+                format!("missing documentation for {} `{}`", decl.kind(), decl.ident()),
+            ));
+        }
+    }
+}
+
+/// Field identifiers must be snake_case.
+pub struct NonSnakeCaseField;
+
+impl Rule for NonSnakeCaseField {
+    fn name(&self) -> &'static str {
+        "non_snake_case_field"
+    }
+
+    fn code(&self) -> Code {
+        codes::NON_SNAKE_CASE_FIELD
+    }
+
+    fn check_field(&self, field: &Loc<RpField>, lints: &mut Vec<Lint>) {
+        let snake = naming::to_lower_snake().convert(field.ident());
+
+        if snake != field.ident() {
+            lints.push(Lint::new(
+                Loc::pos(field),
+                format!(
+                    "field `{}` should be snake_case, like `{}`",
+                    field.ident(),
+                    snake
+                ),
+            ));
+        }
+    }
+}
+
+/// The `any` type must not be used.
+pub struct AnyType;
+
+impl AnyType {
+    /// Check if the given type is, or contains, `any`.
+    fn contains_any(ty: &RpType) -> bool {
+        use core::RpType::*;
+
+        match *ty {
+            Any => true,
+            Array { ref inner } => Self::contains_any(inner),
+            Map { ref key, ref value } => Self::contains_any(key) || Self::contains_any(value),
+            _ => false,
+        }
+    }
+}
+
+impl Rule for AnyType {
+    fn name(&self) -> &'static str {
+        "any_type"
+    }
+
+    fn code(&self) -> Code {
+        codes::ANY_TYPE
+    }
+
+    fn check_field(&self, field: &Loc<RpField>, lints: &mut Vec<Lint>) {
+        if Self::contains_any(&field.ty) {
+            lints.push(Lint::new(
+                Loc::pos(field),
+                format!("field `{}` uses the `any` type", field.ident()),
+            ));
+        }
+    }
+
+    fn check_endpoint(&self, endpoint: &Loc<RpEndpoint>, lints: &mut Vec<Lint>) {
+        for &(ref name, ref channel) in endpoint.arguments.values() {
+            if Self::contains_any(channel.ty()) {
+                lints.push(Lint::new(
+                    Loc::pos(channel),
+                    format!("argument `{}` uses the `any` type", name.as_str()),
+                ));
+            }
+        }
+
+        if let Some(ref response) = endpoint.response {
+            if Self::contains_any(response.ty()) {
+                lints.push(Lint::new(
+                    Loc::pos(response),
+                    format!("response of `{}` uses the `any` type", endpoint.ident()),
+                ));
+            }
+        }
+    }
+}
+
+/// Endpoints must specify an explicit HTTP method.
+pub struct MissingHttpMethod;
+
+impl Rule for MissingHttpMethod {
+    fn name(&self) -> &'static str {
+        "missing_http_method"
+    }
+
+    fn code(&self) -> Code {
+        codes::MISSING_HTTP_METHOD
+    }
+
+    fn check_endpoint(&self, endpoint: &Loc<RpEndpoint>, lints: &mut Vec<Lint>) {
+        if endpoint.http.method.is_none() {
+            lints.push(Lint::new(
+                Loc::pos(endpoint),
+                format!(
+                    "endpoint `{}` has no explicit method, like #[http(method = \"GET\")]",
+                    endpoint.ident()
+                ),
+            ));
+        }
+    }
+}
@@ -17,7 +17,7 @@ use core::errors::Result;
 use naming::Naming;
 use relative_path::{RelativePath, RelativePathBuf};
 use std::any::Any;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 use std::io::Read;
 use std::path::{Path, PathBuf};
@@ -243,6 +243,32 @@ impl TryFromToml for Publish {
     }
 }
 
+impl TryFromToml for Trust {
+    fn try_from_string(_: &Path, id: &str, value: String) -> Result<Self> {
+        let package = RpPackage::parse(id);
+
+        Ok(Trust {
+            package: package,
+            keys: vec![value],
+        })
+    }
+
+    fn try_from_value(_: &Path, id: &str, value: toml::Value) -> Result<Self> {
+        let package = RpPackage::parse(id);
+        let body: ImTrust = value.try_into()?;
+
+        return Ok(Trust {
+            package: package,
+            keys: body.keys,
+        });
+
+        #[derive(Debug, Clone, Deserialize)]
+        pub struct ImTrust {
+            pub keys: Vec<String>,
+        }
+    }
+}
+
 impl TryFromToml for RpRequiredPackage {
     fn try_from_string(_: &Path, id: &str, value: String) -> Result<Self> {
         let package = RpPackage::parse(id);
@@ -422,6 +448,14 @@ pub struct Publish {
     pub version: Version,
 }
 
+/// Keys trusted to sign deployments of a protected package prefix.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Trust {
+    pub package: RpPackage,
+    /// Hex-encoded ed25519 public keys.
+    pub keys: Vec<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct ManifestFile {
     pub path: PathBuf,
@@ -447,6 +481,31 @@ pub struct Repository {
     pub index: Option<String>,
     /// URL to use to objects storage.
     pub objects: Option<String>,
+    /// Directory with vendored dependencies, used as both index and objects storage.
+    pub vendor: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Workspace {
+    /// Manifests which are members of the workspace.
+    ///
+    /// Each member is either a path to a manifest, or a directory containing `reproto.toml`.
+    pub members: Vec<PathBuf>,
+}
+
+/// Level of a lint rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintLevel {
+    Allow,
+    Warn,
+    Deny,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Lint {
+    /// Configured levels of lint rules, by the name of the rule.
+    pub levels: BTreeMap<String, LintLevel>,
 }
 
 /// The first part when the manifest was read.
@@ -486,6 +545,8 @@ pub struct Manifest {
     pub stdin: bool,
     /// Packages to publish.
     pub publish: Vec<Publish>,
+    /// Package prefixes which must be signed by trusted keys.
+    pub trust: Vec<Trust>,
     /// Modules to enable.
     pub modules: Vec<Box<Any>>,
     /// Additional paths specified.
@@ -500,6 +561,10 @@ pub struct Manifest {
     pub repository: Repository,
     /// Documentation settings.
     pub doc: Doc,
+    /// Workspace configuration.
+    pub workspace: Workspace,
+    /// Lint configuration.
+    pub lint: Lint,
 }
 
 impl Manifest {
@@ -511,6 +576,7 @@ impl Manifest {
             files: Vec::default(),
             stdin: false,
             publish: Vec::default(),
+            trust: Vec::default(),
             modules: Vec::default(),
             paths: Vec::default(),
             output: Option::default(),
@@ -518,6 +584,8 @@ impl Manifest {
             id_converter: Option::default(),
             repository: Repository::default(),
             doc: Doc::default(),
+            workspace: Workspace::default(),
+            lint: Lint::default(),
         }
     }
 }
@@ -549,7 +617,7 @@ fn take_section<F>(value: &mut toml::value::Table, name: &str, mut func: F) -> R
 where
     F: FnMut(&mut toml::value::Table) -> Result<()>,
 {
-    let mut inner = take_field::<toml::value::Table>(value, "repository")?;
+    let mut inner = take_field::<toml::value::Table>(value, name)?;
     func(&mut inner)?;
     check_empty(&inner).map_err(|e| format!("{}: {}", name, e.display()))?;
     Ok(())
@@ -573,6 +641,9 @@ pub fn load_common_manifest(
     manifest
         .publish
         .extend(parse_section(base, take_field(value, "publish")?)?);
+    manifest
+        .trust
+        .extend(parse_section(base, take_field(value, "trust")?)?);
 
     manifest.paths.extend(
         take_field::<Vec<RelativePathBuf>>(value, "paths")?
@@ -604,17 +675,55 @@ pub fn load_common_manifest(
         manifest.doc = doc;
     }
 
+    take_section(value, "workspace", |workspace| {
+        load_workspace(&mut manifest.workspace, base, workspace)
+    })?;
+
+    take_section(value, "lint", |lint| load_lint(&mut manifest.lint, lint))?;
+
     return Ok(());
 
     /// Load and apply all repository-specific information.
     pub fn load_repository(
         repository: &mut Repository,
-        _base: &Path,
+        base: &Path,
         value: &mut toml::value::Table,
     ) -> Result<()> {
         repository.no_repository = take_field(value, "no_repository")?;
         repository.index = take_field(value, "index")?;
         repository.objects = take_field(value, "objects")?;
+        repository.vendor =
+            take_field::<Option<RelativePathBuf>>(value, "vendor")?.map(|v| v.to_path(base));
+        Ok(())
+    }
+
+    /// Load all workspace-specific information.
+    pub fn load_workspace(
+        workspace: &mut Workspace,
+        base: &Path,
+        value: &mut toml::value::Table,
+    ) -> Result<()> {
+        workspace.members.extend(
+            take_field::<Vec<RelativePathBuf>>(value, "members")?
+                .iter()
+                .map(|r| r.to_path(base)),
+        );
+
+        Ok(())
+    }
+
+    /// Load the levels of all configured lint rules.
+    pub fn load_lint(lint: &mut Lint, value: &mut toml::value::Table) -> Result<()> {
+        let names: Vec<String> = value.keys().cloned().collect();
+
+        for name in names {
+            let level = take_field::<Option<LintLevel>>(value, name.as_str())?;
+
+            if let Some(level) = level {
+                lint.levels.insert(name, level);
+            }
+        }
+
         Ok(())
     }
 
@@ -709,6 +818,121 @@ pub fn read_manifest(lang: &Lang, mut preamble: ManifestPreamble) -> Result<Mani
     Ok(manifest)
 }
 
+/// Update the version of a package in the `[publish]` section of a manifest.
+///
+/// The manifest is edited as text, to preserve its formatting and comments.
+/// If the package is missing it is added to the section, and the section is added if needed.
+pub fn update_publish_version(content: &str, package: &RpPackage, version: &Version) -> String {
+    let key = package.to_string();
+
+    let mut out: Vec<String> = Vec::new();
+    let mut section = String::new();
+    // index of the `[publish]` header, if present.
+    let mut publish = None;
+    let mut updated = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+
+        if trimmed.starts_with('[') {
+            section = trimmed
+                .trim_left_matches('[')
+                .trim_right_matches(']')
+                .trim()
+                .to_string();
+
+            if section == "publish" {
+                publish = Some(out.len());
+            }
+
+            out.push(line.to_string());
+            continue;
+        }
+
+        if !updated {
+            // like `[publish.toystore]`.
+            let table =
+                section.starts_with("publish.") && same_key(&section["publish.".len()..], &key);
+
+            let replaced = if section == "publish" {
+                value_of(line, &key).and_then(|start| replace_version(line, start, version))
+            } else if table {
+                value_of(line, "version").and_then(|start| replace_version(line, start, version))
+            } else {
+                None
+            };
+
+            if let Some(replaced) = replaced {
+                out.push(replaced);
+                updated = true;
+                continue;
+            }
+        }
+
+        out.push(line.to_string());
+    }
+
+    if !updated {
+        let quoted = key.chars()
+            .any(|c| !(c.is_alphanumeric() || c == '_' || c == '-'));
+
+        let entry = if quoted {
+            format!("\"{}\" = \"{}\"", key, version)
+        } else {
+            format!("{} = \"{}\"", key, version)
+        };
+
+        match publish {
+            Some(index) => out.insert(index + 1, entry),
+            None => {
+                if out.last().map(|l| !l.trim().is_empty()).unwrap_or(false) {
+                    out.push(String::new());
+                }
+
+                out.push("[publish]".to_string());
+                out.push(entry);
+            }
+        }
+    }
+
+    let mut content = out.join("\n");
+    content.push('\n');
+    return content;
+
+    /// Test if a key, which might be quoted, is the expected key.
+    fn same_key(candidate: &str, key: &str) -> bool {
+        candidate.trim().trim_matches('"') == key
+    }
+
+    /// Find where the value of the given key starts, if the line assigns to it.
+    fn value_of(line: &str, key: &str) -> Option<usize> {
+        let eq = line.find('=')?;
+
+        if !same_key(&line[..eq], key) {
+            return None;
+        }
+
+        Some(eq + 1)
+    }
+
+    /// Replace the version string in the value starting at `start`.
+    ///
+    /// The value is either a string, or an inline table with a `version` key.
+    fn replace_version(line: &str, start: usize, version: &Version) -> Option<String> {
+        let mut start = start;
+
+        if line[start..].trim_left().starts_with('{') {
+            start += line[start..].find("version")?;
+            start += line[start..].find('=')?;
+        }
+
+        let open = start + line[start..].find('"')? + 1;
+        let close = open + line[open..].find('"')?;
+
+        Some(format!("{}{}{}", &line[..open], version, &line[close..]))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -790,6 +1014,20 @@ mod tests {
         assert_eq!(1, manifest.publish.len());
     }
 
+    #[test]
+    pub fn test_trust_string() {
+        let manifest = include_manifest!("tests/trust_string.reproto");
+        assert_eq!(1, manifest.trust.len());
+        assert_eq!(1, manifest.trust[0].keys.len());
+    }
+
+    #[test]
+    pub fn test_trust_table() {
+        let manifest = include_manifest!("tests/trust_table.reproto");
+        assert_eq!(1, manifest.trust.len());
+        assert_eq!(2, manifest.trust[0].keys.len());
+    }
+
     #[test]
     pub fn test_files_string() {
         let manifest = include_manifest!("tests/files_string.reproto");
@@ -831,4 +1069,91 @@ mod tests {
             manifest.repository.objects.as_ref().map(String::as_str)
         );
     }
+
+    #[test]
+    pub fn test_repository_vendor() {
+        let manifest = include_manifest!("tests/repository_vendor.reproto");
+
+        assert_eq!(
+            Some(Path::new(".").join("tests").join("vendor")),
+            manifest.repository.vendor
+        );
+    }
+
+    #[test]
+    pub fn test_workspace() {
+        let manifest = include_manifest!("tests/workspace.reproto");
+
+        assert_eq!(
+            vec![
+                Path::new(".").join("tests").join("api"),
+                Path::new(".").join("tests").join("client").join("reproto.toml"),
+            ],
+            manifest.workspace.members
+        );
+    }
+
+    #[test]
+    pub fn test_lint() {
+        let manifest = include_manifest!("tests/lint.reproto");
+
+        let mut expected = BTreeMap::new();
+        expected.insert("missing_docs".to_string(), LintLevel::Deny);
+        expected.insert("any_type".to_string(), LintLevel::Allow);
+
+        assert_eq!(expected, manifest.lint.levels);
+    }
+
+    #[test]
+    pub fn test_update_publish_version() {
+        let package = RpPackage::parse("toystore");
+        let version = Version::parse("1.1.0").expect("bad version");
+
+        let update = |content: &str| update_publish_version(content, &package, &version);
+
+        assert_eq!(
+            "[publish]\ntoystore = \"1.1.0\" # comment\n",
+            update("[publish]\ntoystore = \"1.0.1\" # comment\n")
+        );
+
+        assert_eq!(
+            "[publish]\ntoystore = {version = \"1.1.0\"}\n",
+            update("[publish]\ntoystore = {version = \"1.0.1\"}\n")
+        );
+
+        assert_eq!(
+            "[publish.toystore]\nversion = \"1.1.0\"\n",
+            update("[publish.toystore]\nversion = \"1.0.1\"\n")
+        );
+
+        assert_eq!(
+            "[publish]\ntoystore = \"1.1.0\"\nother = \"1.0.0\"\n",
+            update("[publish]\nother = \"1.0.0\"\n")
+        );
+
+        assert_eq!(
+            "paths = [\"src\"]\n\n[publish]\ntoystore = \"1.1.0\"\n",
+            update("paths = [\"src\"]\n")
+        );
+    }
+
+    #[test]
+    pub fn test_update_publish_version_quoted() {
+        let package = RpPackage::parse("io.reproto");
+        let version = Version::parse("2.0.0").expect("bad version");
+
+        assert_eq!(
+            "[publish.\"io.reproto\"]\nversion = \"2.0.0\"\n",
+            update_publish_version(
+                "[publish.\"io.reproto\"]\nversion = \"1.0.0\"\n",
+                &package,
+                &version,
+            )
+        );
+
+        assert_eq!(
+            "[publish]\n\"io.reproto\" = \"2.0.0\"\n",
+            update_publish_version("", &package, &version)
+        );
+    }
 }
@@ -0,0 +1,585 @@
+//! Mock HTTP server for services.
+//!
+//! Routes requests to the endpoints of the services in an `Environment`, using the path and method
+//! declared with `#[http(...)]`. Request bodies are validated against the declared argument, and
+//! responses are generated from the declared response type, unless a fixture exists for the
+//! endpoint.
+//!
+//! Fixtures are looked up as `<fixtures>/<Service>/<endpoint>.json`, or `.txt` for endpoints which
+//! respond with `text/plain`, and are read on every request.
+
+#[macro_use]
+extern crate log;
+extern crate futures;
+extern crate hyper;
+extern crate reproto_core as core;
+extern crate reproto_sample as sample;
+extern crate reproto_trans as trans;
+extern crate reproto_validate as validate;
+extern crate serde_json;
+
+mod path;
+
+use core::{RpAccept, RpDecl, RpEndpoint, RpHttpMethod, RpPackage, RpPathSpec, RpType};
+use core::errors::*;
+use futures::{Future, Stream};
+use futures::future::ok;
+use hyper::{Method, StatusCode};
+use hyper::header::{ContentLength, ContentType};
+use hyper::server::{Http, Request, Response, Service};
+use sample::Sampler;
+use serde_json::Value;
+use std::cell::Cell;
+use std::fs::File;
+use std::io::Read;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::rc::Rc;
+use trans::Environment;
+use validate::Validator;
+
+/// An endpoint which is served by the mock server.
+#[derive(Debug, Clone)]
+struct Route {
+    /// Identifier of the service the endpoint belongs to.
+    service: String,
+    /// Identifier of the endpoint.
+    endpoint: String,
+    method: Method,
+    path: RpPathSpec,
+    /// Type of the request body, if any.
+    body: Option<RpType>,
+    /// Types of the path variables.
+    vars: Vec<(String, RpType)>,
+    /// Type of the response, if any.
+    response: Option<RpType>,
+    accept: RpAccept,
+}
+
+impl Route {
+    /// Build a route for the given endpoint.
+    ///
+    /// Errors with the reason if the endpoint can't be mocked.
+    fn new(service: &str, endpoint: &RpEndpoint) -> ::std::result::Result<Route, &'static str> {
+        let path = match endpoint.http.path {
+            Some(ref path) => path.clone(),
+            None => return Err("no #[http(path = ...)] specified"),
+        };
+
+        let streaming = endpoint
+            .arguments
+            .values()
+            .map(|a| &a.1)
+            .chain(endpoint.response.iter())
+            .any(|c| c.is_streaming());
+
+        if streaming {
+            return Err("streaming endpoints are not supported");
+        }
+
+        let vars = path.vars()
+            .flat_map(|var| {
+                endpoint
+                    .arguments
+                    .get(var)
+                    .map(|a| (var.to_string(), a.1.ty().clone()))
+            })
+            .collect();
+
+        let body = endpoint
+            .http
+            .body
+            .as_ref()
+            .and_then(|body| endpoint.arguments.get(body))
+            .map(|a| a.1.ty().clone());
+
+        let method = match endpoint.http.method {
+            Some(ref method) => to_method(method),
+            None => Method::Get,
+        };
+
+        Ok(Route {
+            service: service.to_string(),
+            endpoint: endpoint.ident().to_string(),
+            method: method,
+            path: path,
+            body: body,
+            vars: vars,
+            response: endpoint.response.as_ref().map(|r| r.ty().clone()),
+            accept: endpoint.http.accept.clone(),
+        })
+    }
+}
+
+/// A mock server for all services in a set of packages.
+pub struct Mock {
+    env: Environment,
+    routes: Vec<Route>,
+    fixtures: Option<PathBuf>,
+    seed: u64,
+}
+
+impl Mock {
+    /// Setup a mock server for all services declared in the given packages.
+    pub fn new(env: Environment, packages: &[RpPackage]) -> Result<Mock> {
+        let mut routes = Vec::new();
+
+        for (versioned, file) in env.for_each_file() {
+            if !packages.contains(&versioned.package) {
+                continue;
+            }
+
+            for decl in &file.decls {
+                services(decl, &mut routes);
+            }
+        }
+
+        if routes.is_empty() {
+            let packages: Vec<String> = packages.iter().map(|p| p.to_string()).collect();
+
+            return Err(format!(
+                "no endpoints to mock in package(s): {}",
+                packages.join(", ")
+            ).into());
+        }
+
+        return Ok(Mock {
+            env: env,
+            routes: routes,
+            fixtures: None,
+            seed: 0,
+        });
+
+        fn services(decl: &RpDecl, routes: &mut Vec<Route>) {
+            if let RpDecl::Service(ref body) = *decl {
+                for endpoint in body.endpoints.values() {
+                    match Route::new(&body.ident, endpoint) {
+                        Ok(route) => routes.push(route),
+                        Err(reason) => {
+                            warn!("{}::{}: not mocked, {}", body.ident, endpoint.ident(), reason)
+                        }
+                    }
+                }
+            }
+
+            for decl in decl.decls() {
+                services(decl, routes);
+            }
+        }
+    }
+
+    /// Serve fixtures from the given directory, when available.
+    pub fn with_fixtures(self, fixtures: PathBuf) -> Mock {
+        Mock {
+            fixtures: Some(fixtures),
+            ..self
+        }
+    }
+
+    /// Seed to generate responses from.
+    pub fn with_seed(self, seed: u64) -> Mock {
+        Mock { seed: seed, ..self }
+    }
+
+    /// Serve the mocked endpoints on the given address, until the process is stopped.
+    pub fn serve(self, addr: &SocketAddr) -> Result<()> {
+        for route in &self.routes {
+            info!(
+                "{} {} -> {}::{}",
+                route.method,
+                route.path,
+                route.service,
+                route.endpoint
+            );
+        }
+
+        let shared = Rc::new(Shared {
+            mock: self,
+            requests: Cell::new(0),
+        });
+
+        let setup = move || {
+            Ok(MockService {
+                shared: shared.clone(),
+            })
+        };
+
+        let server = Http::new().bind(addr, setup)?;
+
+        info!("Listening on http://{}", server.local_addr()?);
+        server.run()?;
+        Ok(())
+    }
+}
+
+/// State shared between all connections.
+struct Shared {
+    mock: Mock,
+    /// Number of handled requests, used to vary the generated responses.
+    requests: Cell<u64>,
+}
+
+impl Shared {
+    /// Find the route matching the given request.
+    ///
+    /// Responds with an error if there's no such route.
+    fn route(
+        &self,
+        method: &Method,
+        path: &str,
+    ) -> ::std::result::Result<(usize, Vec<(String, String)>), Response> {
+        let mut path_matched = false;
+
+        for (index, route) in self.mock.routes.iter().enumerate() {
+            let vars = match path::match_path(&route.path, path) {
+                Some(vars) => vars,
+                None => continue,
+            };
+
+            if route.method != *method {
+                path_matched = true;
+                continue;
+            }
+
+            return Ok((index, vars));
+        }
+
+        if path_matched {
+            return Err(text_response(
+                StatusCode::MethodNotAllowed,
+                format!("method {} not allowed for {}\n", method, path),
+            ));
+        }
+
+        Err(text_response(
+            StatusCode::NotFound,
+            format!("no endpoint for {}\n", path),
+        ))
+    }
+
+    fn handle(&self, index: usize, vars: Vec<(String, String)>, body: &[u8]) -> Response {
+        match self.try_handle(&self.mock.routes[index], vars, body) {
+            Ok(response) => response,
+            Err(e) => {
+                error!("{}", e.display());
+                text_response(StatusCode::InternalServerError, format!("{}\n", e.display()))
+            }
+        }
+    }
+
+    fn try_handle(
+        &self,
+        route: &Route,
+        vars: Vec<(String, String)>,
+        body: &[u8],
+    ) -> Result<Response> {
+        let validator = Validator::new(&self.mock.env);
+        let mut violations = Vec::new();
+
+        for (name, value) in vars {
+            let ty = match route.vars.iter().find(|v| v.0 == name) {
+                Some(&(_, ref ty)) => ty,
+                None => continue,
+            };
+
+            let value = path_value(ty, value);
+
+            for v in validator.validate_type(ty, &value)? {
+                violations.push(format!("path variable `{}`: {}", name, v.message));
+            }
+        }
+
+        if let Some(ref ty) = route.body {
+            match serde_json::from_slice::<Value>(body) {
+                Ok(value) => for v in validator.validate_type(ty, &value)? {
+                    violations.push(format!("body: {}", v));
+                },
+                Err(e) => violations.push(format!("body: bad JSON: {}", e)),
+            }
+        }
+
+        if !violations.is_empty() {
+            violations.push(String::new());
+            return Ok(text_response(StatusCode::BadRequest, violations.join("\n")));
+        }
+
+        let ty = match route.response {
+            Some(ref ty) => ty,
+            None => return Ok(Response::new().with_status(StatusCode::NoContent)),
+        };
+
+        if let Some(response) = self.fixture(route, ty)? {
+            return Ok(response);
+        }
+
+        let requests = self.requests.get();
+        self.requests.set(requests + 1);
+
+        let value = Sampler::new(&self.mock.env, self.mock.seed.wrapping_add(requests))
+            .sample_type(ty)?;
+
+        let response = match route.accept {
+            RpAccept::Json => json_response(serde_json::to_string(&value)?),
+            RpAccept::Text => match value {
+                Value::String(string) => text_response(StatusCode::Ok, string),
+                value => text_response(StatusCode::Ok, value.to_string()),
+            },
+        };
+
+        Ok(response)
+    }
+
+    /// Respond with the fixture for the given route, if there is one.
+    fn fixture(&self, route: &Route, ty: &RpType) -> Result<Option<Response>> {
+        let fixtures = match self.mock.fixtures {
+            Some(ref fixtures) => fixtures,
+            None => return Ok(None),
+        };
+
+        let ext = match route.accept {
+            RpAccept::Json => "json",
+            RpAccept::Text => "txt",
+        };
+
+        let path = fixtures
+            .join(&route.service)
+            .join(format!("{}.{}", route.endpoint, ext));
+
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let mut content = String::new();
+
+        File::open(&path)
+            .and_then(|mut f| f.read_to_string(&mut content))
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        if route.accept == RpAccept::Text {
+            return Ok(Some(text_response(StatusCode::Ok, content)));
+        }
+
+        let value: Value = serde_json::from_str(&content)
+            .map_err(|e| format!("{}: bad JSON: {}", path.display(), e))?;
+
+        // fixtures are served as-is, even when they don't match the declaration.
+        for v in Validator::new(&self.mock.env).validate_type(ty, &value)? {
+            warn!("{}: does not match `{}`: {}", path.display(), ty, v);
+        }
+
+        Ok(Some(json_response(content)))
+    }
+}
+
+struct MockService {
+    shared: Rc<Shared>,
+}
+
+impl Service for MockService {
+    type Request = Request;
+    type Response = Response;
+    type Error = hyper::Error;
+    type Future = Box<Future<Item = Response, Error = hyper::Error>>;
+
+    fn call(&self, req: Request) -> Self::Future {
+        let method = req.method().clone();
+        let path = req.path().to_string();
+
+        let (index, vars) = match self.shared.route(&method, &path) {
+            Ok(found) => found,
+            Err(response) => {
+                info!("{} {}: {}", method, path, response.status());
+                return Box::new(ok(response));
+            }
+        };
+
+        let shared = self.shared.clone();
+
+        Box::new(req.body().concat2().map(move |body| {
+            let response = shared.handle(index, vars, &body);
+            info!("{} {}: {}", method, path, response.status());
+            response
+        }))
+    }
+}
+
+/// Convert the method of an endpoint.
+fn to_method(method: &RpHttpMethod) -> Method {
+    use core::RpHttpMethod::*;
+
+    match *method {
+        GET => Method::Get,
+        POST => Method::Post,
+        PUT => Method::Put,
+        UPDATE => Method::Extension("UPDATE".to_string()),
+        DELETE => Method::Delete,
+        PATCH => Method::Patch,
+        HEAD => Method::Head,
+    }
+}
+
+/// Convert the value of a path variable to JSON, so that it can be validated.
+///
+/// Values which can't be converted are kept as strings, which causes validation to fail.
+fn path_value(ty: &RpType, value: String) -> Value {
+    use core::RpType::*;
+
+    match *ty {
+        Signed { .. } => match value.parse::<i64>() {
+            Ok(n) => Value::from(n),
+            Err(_) => Value::String(value),
+        },
+        Unsigned { .. } => match value.parse::<u64>() {
+            Ok(n) => Value::from(n),
+            Err(_) => Value::String(value),
+        },
+        Float | Double => match value.parse::<f64>() {
+            Ok(n) => Value::from(n),
+            Err(_) => Value::String(value),
+        },
+        Boolean => match value.as_str() {
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            _ => Value::String(value),
+        },
+        _ => Value::String(value),
+    }
+}
+
+fn json_response(body: String) -> Response {
+    Response::new()
+        .with_status(StatusCode::Ok)
+        .with_header(ContentLength(body.len() as u64))
+        .with_header(ContentType::json())
+        .with_body(body)
+}
+
+fn text_response(status: StatusCode, body: String) -> Response {
+    Response::new()
+        .with_status(status)
+        .with_header(ContentLength(body.len() as u64))
+        .with_header(ContentType::plaintext())
+        .with_body(body)
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate tempdir;
+
+    use self::tempdir::TempDir;
+    use super::*;
+    use std::fs;
+    use std::io::Write;
+    use trans::testing::environment;
+
+    const SPEC: &str = r#"
+    type Foo {
+      id: u32;
+    }
+
+    service Svc {
+      #[http(path = "/foo/{id}", method = "POST", body = "foo")]
+      set(id: u32, foo: Foo) -> Foo;
+
+      get() -> Foo;
+
+      #[http(path = "/stream")]
+      stream() -> stream Foo;
+    }
+    "#;
+
+    fn shared(mock: Mock) -> Shared {
+        Shared {
+            mock: mock,
+            requests: Cell::new(0),
+        }
+    }
+
+    fn mock() -> Mock {
+        Mock::new(environment(SPEC), &[RpPackage::parse("test")]).expect("to mock")
+    }
+
+    /// Handle a request, and return the status and body of the response.
+    fn request(shared: &Shared, method: Method, path: &str, body: &str) -> (StatusCode, String) {
+        let response = match shared.route(&method, path) {
+            Ok((index, vars)) => shared.handle(index, vars, body.as_bytes()),
+            Err(response) => response,
+        };
+
+        let status = response.status();
+        let body = response.body().concat2().wait().expect("to read body");
+        (status, String::from_utf8(body.to_vec()).expect("utf-8 body"))
+    }
+
+    #[test]
+    fn test_route_new() {
+        let mock = mock();
+
+        // endpoints without a path, or which stream, are not mocked.
+        let routes = mock.routes
+            .iter()
+            .map(|r| (r.endpoint.as_str(), r.method.clone(), r.path.to_string()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec![("set", Method::Post, "/foo/{id}".to_string())], routes);
+
+        let route = &mock.routes[0];
+        assert_eq!("Svc", route.service);
+        assert_eq!(vec!["id"], route.vars.iter().map(|v| v.0.as_str()).collect::<Vec<_>>());
+        assert!(route.body.is_some());
+        assert!(route.response.is_some());
+    }
+
+    #[test]
+    fn test_route_matching() {
+        let shared = shared(mock());
+
+        let (index, vars) = shared.route(&Method::Post, "/foo/42").ok().expect("to match");
+        assert_eq!(0, index);
+        assert_eq!(vec![("id".to_string(), "42".to_string())], vars);
+
+        let status = |method, path| shared.route(&method, path).err().map(|r| r.status());
+
+        assert_eq!(Some(StatusCode::MethodNotAllowed), status(Method::Get, "/foo/42"));
+        assert_eq!(Some(StatusCode::NotFound), status(Method::Post, "/bar/42"));
+    }
+
+    #[test]
+    fn test_invalid_request() {
+        let shared = shared(mock());
+
+        let (status, _) = request(&shared, Method::Post, "/foo/42", r#"{"id": 1}"#);
+        assert_eq!(StatusCode::Ok, status);
+
+        let (status, body) = request(&shared, Method::Post, "/foo/42", r#"{"id": "one"}"#);
+        assert_eq!(StatusCode::BadRequest, status);
+        assert!(body.starts_with("body: "));
+
+        let (status, body) = request(&shared, Method::Post, "/foo/42", "{");
+        assert_eq!(StatusCode::BadRequest, status);
+        assert!(body.starts_with("body: bad JSON: "));
+
+        let (status, body) = request(&shared, Method::Post, "/foo/abc", r#"{"id": 1}"#);
+        assert_eq!(StatusCode::BadRequest, status);
+        assert!(body.starts_with("path variable `id`: "));
+    }
+
+    #[test]
+    fn test_fixture() {
+        let dir = TempDir::new("mock").unwrap();
+        let fixtures = dir.path().join("Svc");
+        fs::create_dir_all(&fixtures).unwrap();
+
+        // fixtures are served as-is, even if they don't match the declaration.
+        File::create(fixtures.join("set.json"))
+            .and_then(|mut f| f.write_all(b"{\"id\": \"fixture\"}"))
+            .unwrap();
+
+        let shared = shared(mock().with_fixtures(dir.path().to_owned()));
+
+        assert_eq!(
+            (StatusCode::Ok, "{\"id\": \"fixture\"}".to_string()),
+            request(&shared, Method::Post, "/foo/42", r#"{"id": 1}"#)
+        );
+    }
+}
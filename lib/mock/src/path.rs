@@ -0,0 +1,150 @@
+//! Matching of request paths against path specifications.
+
+use core::{RpPathPart, RpPathSpec, RpPathStep};
+
+/// Match the given request path against a path specification.
+///
+/// Returns the value of every variable in the specification if it matches.
+pub fn match_path(spec: &RpPathSpec, path: &str) -> Option<Vec<(String, String)>> {
+    let steps: Vec<&RpPathStep> = spec.steps.iter().filter(|s| !s.parts.is_empty()).collect();
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    if steps.len() != segments.len() {
+        return None;
+    }
+
+    let mut vars = Vec::new();
+
+    for (step, segment) in steps.into_iter().zip(segments) {
+        if !match_step(step, segment, &mut vars) {
+            return None;
+        }
+    }
+
+    Some(vars)
+}
+
+/// Match a single segment of a request path against a step.
+///
+/// A variable captures everything up until the literal that follows it, or until the end of the
+/// segment.
+fn match_step(step: &RpPathStep, mut segment: &str, vars: &mut Vec<(String, String)>) -> bool {
+    let mut parts = step.parts.iter().peekable();
+
+    while let Some(part) = parts.next() {
+        match *part {
+            RpPathPart::Segment(ref literal) => {
+                if !segment.starts_with(literal.as_str()) {
+                    return false;
+                }
+
+                segment = &segment[literal.len()..];
+            }
+            RpPathPart::Variable(ref var) => {
+                let end = match parts.peek() {
+                    Some(&&RpPathPart::Segment(ref literal)) => segment.find(literal.as_str()),
+                    _ => Some(segment.len()),
+                };
+
+                let end = match end {
+                    Some(end) => end,
+                    None => return false,
+                };
+
+                if end == 0 {
+                    return false;
+                }
+
+                vars.push((var.to_string(), decode(&segment[..end])));
+                segment = &segment[end..];
+            }
+        }
+    }
+
+    segment.is_empty()
+}
+
+/// Decode a percent-encoded path segment.
+///
+/// Malformed escapes are kept as they are.
+fn decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = ::std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+
+            if let Some(b) = hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                out.push(b);
+                i += 3;
+                continue;
+            }
+        }
+
+        out.push(bytes[i]);
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(steps: Vec<Vec<RpPathPart>>) -> RpPathSpec {
+        RpPathSpec {
+            steps: steps
+                .into_iter()
+                .map(|parts| RpPathStep { parts: parts })
+                .collect(),
+        }
+    }
+
+    fn segment(s: &str) -> RpPathPart {
+        RpPathPart::Segment(s.to_string())
+    }
+
+    fn var(s: &str) -> RpPathPart {
+        RpPathPart::Variable(s.to_string())
+    }
+
+    fn vars(pairs: &[(&str, &str)]) -> Option<Vec<(String, String)>> {
+        Some(
+            pairs
+                .iter()
+                .map(|&(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_match_path() {
+        let s = spec(vec![vec![segment("foo")], vec![var("id")]]);
+
+        assert_eq!(vars(&[("id", "42")]), match_path(&s, "/foo/42"));
+        assert_eq!(vars(&[("id", "a b")]), match_path(&s, "/foo/a%20b"));
+        assert_eq!(None, match_path(&s, "/foo"));
+        assert_eq!(None, match_path(&s, "/bar/42"));
+        assert_eq!(None, match_path(&s, "/foo/42/baz"));
+    }
+
+    #[test]
+    fn test_match_mixed_step() {
+        let s = spec(vec![vec![var("name"), segment(".json")]]);
+
+        assert_eq!(vars(&[("name", "foo")]), match_path(&s, "/foo.json"));
+        assert_eq!(None, match_path(&s, "/.json"));
+        assert_eq!(None, match_path(&s, "/foo.xml"));
+    }
+
+    #[test]
+    fn test_match_root() {
+        let s = spec(vec![]);
+
+        assert_eq!(vars(&[]), match_path(&s, "/"));
+        assert_eq!(None, match_path(&s, "/foo"));
+    }
+}
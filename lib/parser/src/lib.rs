@@ -244,6 +244,8 @@ mod tests {
             parts: vec!["Hello".to_owned(), "World".to_owned()],
         };
 
+        let c = Loc::new(c, (new_context(), 0, 12));
+
         assert_type_spec_eq!(Type::String, "string");
         assert_type_spec_eq!(Type::Name { name: c }, "Hello::World");
     }
@@ -13,7 +13,7 @@ use futures::{Future, Stream};
 use futures::future::{err, ok};
 use hyper::{Client, Method, Request, StatusCode};
 use hyper::header::ContentLength;
-use repository::{CachedObjects, Checksum, HexSlice, Objects, ObjectsConfig};
+use repository::{to_checksum, CachedObjects, Checksum, HexSlice, Objects, ObjectsConfig};
 use std::io::Read;
 use std::sync::Arc;
 use std::time::Duration;
@@ -137,6 +137,10 @@ pub fn objects_from_url(config: ObjectsConfig, url: &Url) -> Result<Box<Objects>
             .missing_cache_time
             .unwrap_or_else(|| Duration::new(60, 0));
 
+        // every url is cached separately, since garbage is collected against the objects which
+        // are referenced by a single index.
+        let cache_dir = cache_dir.join(cache_name(url)?);
+
         return Ok(Box::new(CachedObjects::new(
             cache_dir,
             missing_cache_time,
@@ -146,3 +150,13 @@ pub fn objects_from_url(config: ObjectsConfig, url: &Url) -> Result<Box<Objects>
 
     Ok(Box::new(http_objects))
 }
+
+/// Name of the cache directory for objects loaded from the given url.
+fn cache_name(url: &Url) -> Result<String> {
+    let tail = to_checksum(url.as_str().as_bytes())?;
+
+    Ok(match url.host_str() {
+        Some(host) => format!("{}-{}", host, tail),
+        None => format!("unknown-{}", tail),
+    })
+}
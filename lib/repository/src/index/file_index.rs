@@ -4,7 +4,9 @@ use core::errors::*;
 use index::{Deployment, Index};
 use objects::{FileObjects, Objects};
 use serde_json;
+use signing::Signature;
 use std::fs::{self, File};
+use std::collections::LinkedList;
 use std::io::{BufRead, BufReader, Read, Write};
 use std::path::{Path, PathBuf};
 
@@ -126,8 +128,13 @@ impl FileIndex {
 
 impl Index for FileIndex {
     fn resolve(&self, package: &RpPackage, range: &Range) -> Result<Vec<Deployment>> {
-        self.read_package(package, |d| range.matches(&d.version))
-            .map(|r| r.0)
+        // yanked deployments are only honored when explicitly pinned.
+        // There are no lockfiles, so an exact range like `=1.2.3` is what counts as pinned.
+        let pinned = range.is_exact();
+
+        self.read_package(package, |d| {
+            range.matches(&d.version) && (pinned || !d.yanked)
+        }).map(|r| r.0)
     }
 
     fn all(&self, package: &RpPackage) -> Result<Vec<Deployment>> {
@@ -144,27 +151,98 @@ impl Index for FileIndex {
         checksum: &Checksum,
         package: &RpPackage,
         version: &Version,
+        signature: Option<Signature>,
         force: bool,
     ) -> Result<()> {
-        let (mut deployments, other_match) = self.read_package(package, |d| d.version != *version)?;
+        let deployment = Deployment::new(version.clone(), checksum.clone(), signature);
+        self.put_deployment(package, deployment, force)
+    }
+
+    fn put_deployment(&self, package: &RpPackage, deployment: Deployment, force: bool) -> Result<()> {
+        let (mut deployments, other_match) =
+            self.read_package(package, |d| d.version != deployment.version)?;
 
         if other_match {
             if !force {
-                return Err(format!("{}@{}: already published", package, version).into());
+                return Err(format!(
+                    "{}@{}: already published",
+                    package, deployment.version
+                ).into());
             }
         }
 
-        deployments.push(Deployment::new(version.clone(), checksum.clone()));
+        deployments.push(deployment);
         deployments.sort_by(|a, b| a.version.cmp(&b.version));
         self.write_package(package, deployments)?;
         Ok(())
     }
 
+    fn packages(&self) -> Result<Vec<RpPackage>> {
+        let mut packages = Vec::new();
+        let mut queue = LinkedList::new();
+        queue.push_back((RpPackage::empty(), self.path.clone()));
+
+        while let Some((package, path)) = queue.pop_front() {
+            if path.join(METADATA_JSON).is_file() {
+                packages.push(package.clone());
+            }
+
+            for entry in fs::read_dir(&path)? {
+                let entry = entry?;
+                let path = entry.path();
+
+                if !path.is_dir() {
+                    continue;
+                }
+
+                let file_name = entry.file_name();
+
+                let name = file_name
+                    .to_str()
+                    .ok_or_else(|| format!("illegal path: {}", path.display()))?;
+
+                // skip hidden directories, like `.git`.
+                if name.starts_with('.') {
+                    continue;
+                }
+
+                queue.push_back((package.clone().join_part(name), path));
+            }
+        }
+
+        packages.sort();
+        Ok(packages)
+    }
+
     fn get_deployments(&self, package: &RpPackage, version: &Version) -> Result<Vec<Deployment>> {
         self.read_package(package, |d| d.version == *version)
             .map(|r| r.0)
     }
 
+    fn set_yanked(
+        &self,
+        package: &RpPackage,
+        version: &Version,
+        yanked: bool,
+        deprecated: Option<&str>,
+    ) -> Result<()> {
+        let (mut deployments, _) = self.read_package(package, |_| true)?;
+        let mut found = false;
+
+        for deployment in deployments.iter_mut().filter(|d| d.version == *version) {
+            deployment.yanked = yanked;
+            deployment.deprecated = deprecated.map(ToOwned::to_owned);
+            found = true;
+        }
+
+        if !found {
+            return Err(format!("{}@{}: no such deployment", package, version).into());
+        }
+
+        self.write_package(package, deployments)?;
+        Ok(())
+    }
+
     fn objects_from_index(&self, relative_path: &RelativePath) -> Result<Box<Objects>> {
         let path = relative_path.to_path(&self.path);
         Ok(Box::new(FileObjects::new(&path)))
@@ -221,3 +299,57 @@ fn read_config<P: AsRef<Path> + ?Sized>(path: &P) -> Result<Config> {
 
     Ok(config)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempdir::TempDir;
+
+    fn checksum(n: u8) -> Checksum {
+        Checksum::new(vec![n; 32])
+    }
+
+    fn versions(deployments: Vec<Deployment>) -> Vec<String> {
+        deployments.into_iter().map(|d| d.version.to_string()).collect()
+    }
+
+    #[test]
+    fn test_yanked_only_resolved_when_pinned() {
+        let dir = TempDir::new("file_index").unwrap();
+        init_file_index(dir.path()).unwrap();
+        let index = FileIndex::new(dir.path()).unwrap();
+
+        let package = RpPackage::parse("foo.bar");
+
+        for (n, version) in ["1.0.0", "1.1.0"].iter().enumerate() {
+            let version = Version::parse(version).unwrap();
+            index
+                .put_version(&checksum(n as u8), &package, &version, None, false)
+                .unwrap();
+        }
+
+        let yanked = Version::parse("1.1.0").unwrap();
+        index
+            .set_yanked(&package, &yanked, true, Some("broken"))
+            .unwrap();
+
+        let range = Range::parse("^1.0.0").unwrap();
+        assert_eq!(
+            vec!["1.0.0".to_string()],
+            versions(index.resolve(&package, &range).unwrap())
+        );
+
+        let exact = Range::parse("=1.1.0").unwrap();
+        let pinned = index.resolve(&package, &exact).unwrap();
+        assert_eq!(vec!["1.1.0".to_string()], versions(pinned.clone()));
+        assert!(pinned[0].yanked);
+        assert_eq!(Some("broken".to_string()), pinned[0].deprecated);
+
+        index.set_yanked(&package, &yanked, false, None).unwrap();
+
+        assert_eq!(
+            vec!["1.0.0".to_string(), "1.1.0".to_string()],
+            versions(index.resolve(&package, &range).unwrap())
+        );
+    }
+}
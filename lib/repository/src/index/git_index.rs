@@ -4,6 +4,7 @@ use core::errors::*;
 use git::GitRepo;
 use index::{file_index, Deployment, Index};
 use objects::{FileObjects, GitObjects, Objects};
+use signing::Signature;
 use std::rc::Rc;
 use update::Update;
 use url::Url;
@@ -33,7 +34,14 @@ impl Index for GitIndex {
         self.file_index.all(package)
     }
 
-    fn put_version(&self, _: &Checksum, _: &RpPackage, _: &Version, _: bool) -> Result<()> {
+    fn put_version(
+        &self,
+        _: &Checksum,
+        _: &RpPackage,
+        _: &Version,
+        _: Option<Signature>,
+        _: bool,
+    ) -> Result<()> {
         Err(format!(
             "Index does not support publishing: {}",
             self.url.to_string()
@@ -44,6 +52,24 @@ impl Index for GitIndex {
         self.file_index.get_deployments(package, version)
     }
 
+    fn put_deployment(&self, _: &RpPackage, _: Deployment, _: bool) -> Result<()> {
+        Err(format!(
+            "Index does not support publishing: {}",
+            self.url.to_string()
+        ).into())
+    }
+
+    fn packages(&self) -> Result<Vec<RpPackage>> {
+        self.file_index.packages()
+    }
+
+    fn set_yanked(&self, _: &RpPackage, _: &Version, _: bool, _: Option<&str>) -> Result<()> {
+        Err(format!(
+            "Index does not support yanking: {}",
+            self.url.to_string()
+        ).into())
+    }
+
     fn objects_url(&self) -> Result<&str> {
         self.file_index.objects_url()
     }
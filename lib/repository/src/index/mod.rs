@@ -1,13 +1,15 @@
 mod file_index;
 mod git_index;
 
-pub use self::file_index::init_file_index;
+pub use self::file_index::{init_file_index, FileIndex};
 use self::git_index::GitIndex;
 use checksum::Checksum;
 use core::{Range, RelativePath, RpPackage, Version};
 use core::errors::*;
 use git;
 use objects::Objects;
+use signing::Signature;
+use std::collections::BTreeSet;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use update::Update;
@@ -23,17 +25,33 @@ pub struct IndexConfig {
 pub struct Deployment {
     pub version: Version,
     pub object: Checksum,
+    /// Yanked deployments are only resolved when pinned to their exact version.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub yanked: bool,
+    /// Deprecation message to show when the deployment is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<String>,
+    /// Signature of the publisher.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<Signature>,
 }
 
 impl Deployment {
-    pub fn new(version: Version, object: Checksum) -> Deployment {
+    pub fn new(version: Version, object: Checksum, signature: Option<Signature>) -> Deployment {
         Deployment {
             version: version,
             object: object,
+            yanked: false,
+            deprecated: None,
+            signature: signature,
         }
     }
 }
 
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
 pub trait Index {
     /// Resolve the given version of a package.
     fn resolve(&self, package: &RpPackage, range: &Range) -> Result<Vec<Deployment>>;
@@ -48,11 +66,32 @@ pub trait Index {
         checksum: &Checksum,
         package: &RpPackage,
         version: &Version,
+        signature: Option<Signature>,
         force: bool,
     ) -> Result<()>;
 
     fn get_deployments(&self, package: &RpPackage, version: &Version) -> Result<Vec<Deployment>>;
 
+    /// Put a complete deployment into the index, as-is.
+    ///
+    /// Used when copying deployments between indexes.
+    fn put_deployment(&self, package: &RpPackage, deployment: Deployment, force: bool)
+        -> Result<()>;
+
+    /// List all packages which have deployments in the index.
+    fn packages(&self) -> Result<Vec<RpPackage>>;
+
+    /// Mark the given version of a package as yanked, or un-yank it.
+    ///
+    /// `deprecated` is the message to show when a yanked deployment is used.
+    fn set_yanked(
+        &self,
+        package: &RpPackage,
+        version: &Version,
+        yanked: bool,
+        deprecated: Option<&str>,
+    ) -> Result<()>;
+
     /// Get an objects URL as configured in the index.
     ///
     /// If relative, will cause objects to be loaded from the same repository as the index.
@@ -78,7 +117,14 @@ impl Index for NoIndex {
         Ok(vec![])
     }
 
-    fn put_version(&self, _: &Checksum, _: &RpPackage, _: &Version, _: bool) -> Result<()> {
+    fn put_version(
+        &self,
+        _: &Checksum,
+        _: &RpPackage,
+        _: &Version,
+        _: Option<Signature>,
+        _: bool,
+    ) -> Result<()> {
         Err("Empty Index".into())
     }
 
@@ -86,6 +132,18 @@ impl Index for NoIndex {
         Ok(vec![])
     }
 
+    fn put_deployment(&self, _: &RpPackage, _: Deployment, _: bool) -> Result<()> {
+        Err("Empty Index".into())
+    }
+
+    fn packages(&self) -> Result<Vec<RpPackage>> {
+        Ok(vec![])
+    }
+
+    fn set_yanked(&self, _: &RpPackage, _: &Version, _: bool, _: Option<&str>) -> Result<()> {
+        Err("Empty Index".into())
+    }
+
     /// Get an objects URL as configured in the index.
     ///
     /// If relative, will cause objects to be loaded from the same repository as the index.
@@ -99,6 +157,19 @@ impl Index for NoIndex {
     }
 }
 
+/// Collect the objects referenced by any deployment in the given index.
+pub fn referenced_objects(index: &Index) -> Result<BTreeSet<Checksum>> {
+    let mut referenced = BTreeSet::new();
+
+    for package in index.packages()? {
+        for deployment in index.all(&package)? {
+            referenced.insert(deployment.object);
+        }
+    }
+
+    Ok(referenced)
+}
+
 /// Setup an index for the given path.
 pub fn index_from_path(path: &Path) -> Result<file_index::FileIndex> {
     if !path.is_dir() {
@@ -10,7 +10,10 @@ extern crate serde;
 #[macro_use]
 extern crate serde_derive;
 extern crate serde_json;
+#[cfg(test)]
+extern crate tempdir;
 extern crate toml;
+extern crate untrusted;
 extern crate url;
 
 mod checksum;
@@ -22,16 +25,20 @@ mod objects;
 mod repository;
 mod resolver;
 mod sha256;
+mod signing;
+mod trust;
 mod update;
 
 pub use self::checksum::Checksum;
 pub use self::git::GitRepo;
 pub use self::hex_slice::HexSlice;
-pub use self::index::{index_from_path, index_from_url, init_file_index, Index, IndexConfig,
-                      NoIndex};
-pub use self::objects::{objects_from_path, objects_from_url, CachedObjects, FileObjects,
+pub use self::index::{index_from_path, index_from_url, init_file_index, referenced_objects,
+                      Deployment, FileIndex, Index, IndexConfig, NoIndex};
+pub use self::objects::{objects_from_path, objects_from_url, CachedObjects, FileObjects, Gc,
                         NoObjects, Objects, ObjectsConfig};
 pub use self::repository::Repository;
 pub use self::resolver::{Paths, Resolvers};
 pub use self::sha256::{Sha256 as Digest, to_sha256 as to_checksum};
+pub use self::signing::{Signature, SigningKey};
+pub use self::trust::Trust;
 pub use self::update::Update;
@@ -1,10 +1,11 @@
 //! ## Load objects through a local cache directory
 
-use super::Objects;
+use super::{gc_objects, not_found, Gc, Objects};
 use checksum::Checksum;
 use core::{Object, PathObject};
 use core::errors::*;
 use hex_slice::HexSlice;
+use std::collections::BTreeSet;
 use std::fs::{self, File};
 use std::io::{self, Read};
 use std::path::PathBuf;
@@ -79,6 +80,49 @@ impl<T: Objects> CachedObjects<T> {
 
         Ok((false, path))
     }
+
+    /// Remove all missing-markers which have expired.
+    fn gc_missing(&self) -> Result<Gc> {
+        let mut gc = Gc::default();
+        let missing = self.objects_cache.join("missing");
+
+        if !missing.is_dir() {
+            return Ok(gc);
+        }
+
+        let now = time::SystemTime::now();
+
+        for e in fs::read_dir(&missing)? {
+            let path = match not_found(e)? {
+                Some(e) => e.path(),
+                None => continue,
+            };
+
+            // markers might be removed concurrently by a lookup.
+            let m = match not_found(fs::metadata(&path))? {
+                Some(m) => m,
+                None => continue,
+            };
+
+            if !m.is_file() {
+                continue;
+            }
+
+            let age = now.duration_since(m.modified()?)
+                .unwrap_or_else(|_| Duration::new(0, 0));
+
+            if age >= self.missing_cache_time {
+                debug!("gc: removing missing entry: {}", path.display());
+
+                if not_found(fs::remove_file(&path))?.is_some() {
+                    gc.reclaimed += m.len();
+                    gc.missing += 1;
+                }
+            }
+        }
+
+        Ok(gc)
+    }
 }
 
 impl<T: Objects> Objects for CachedObjects<T> {
@@ -129,4 +173,11 @@ impl<T: Objects> Objects for CachedObjects<T> {
 
         return Ok(None);
     }
+
+    fn gc(&mut self, referenced: &BTreeSet<Checksum>) -> Result<Gc> {
+        let mut gc = gc_objects(&self.objects_cache, referenced)?;
+        gc.merge(self.gc_missing()?);
+        gc.merge(self.inner.gc(referenced)?);
+        Ok(gc)
+    }
 }
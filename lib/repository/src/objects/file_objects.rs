@@ -1,10 +1,11 @@
 //! ## Load objects through a local directory
 
-use super::Objects;
+use super::{gc_objects, Gc, Objects};
 use checksum::Checksum;
 use core::{Object, PathObject};
 use core::errors::*;
 use hex_slice::HexSlice;
+use std::collections::BTreeSet;
 use std::fs::{self, File};
 use std::io::{self, Read};
 use std::path::{Path, PathBuf};
@@ -61,4 +62,8 @@ impl Objects for FileObjects {
 
         Ok(None)
     }
+
+    fn gc(&mut self, referenced: &BTreeSet<Checksum>) -> Result<Gc> {
+        gc_objects(&self.path, referenced)
+    }
 }
@@ -0,0 +1,240 @@
+//! ## Garbage collection of objects stored in a local directory
+
+use checksum::Checksum;
+use core::errors::*;
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Files younger than this many seconds are never removed.
+///
+/// Objects are written before the index is updated, so a publish which is in progress has a
+/// temporary file, or an object which is not yet referenced.
+const GRACE_PERIOD: u64 = 3600;
+
+/// Summary of a garbage collection.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Gc {
+    /// Number of removed objects.
+    pub objects: u64,
+    /// Number of removed missing-markers.
+    pub missing: u64,
+    /// Number of reclaimed bytes.
+    pub reclaimed: u64,
+}
+
+impl Gc {
+    /// Add the result of another garbage collection to this one.
+    pub fn merge(&mut self, other: Gc) {
+        self.objects += other.objects;
+        self.missing += other.missing;
+        self.reclaimed += other.reclaimed;
+    }
+}
+
+/// Check if the given path is a directory named by a two-character hex prefix, as used by the
+/// object layout.
+fn is_prefix_dir(path: &Path) -> bool {
+    if !path.is_dir() {
+        return false;
+    }
+
+    match path.file_name().and_then(|n| n.to_str()) {
+        Some(name) => name.len() == 2 && name.chars().all(|c| c.is_digit(16)),
+        None => false,
+    }
+}
+
+/// Remove all objects under `root` which are not referenced.
+///
+/// Only files following the `<hex>/<hex>/<checksum>` layout are considered, anything else in the
+/// directory is left untouched. Left-over temporary files from interrupted writes are removed.
+/// Files modified within the grace period are kept, since they might belong to a publish which is
+/// in progress.
+pub fn gc_objects(root: &Path, referenced: &BTreeSet<Checksum>) -> Result<Gc> {
+    gc_objects_older_than(root, referenced, Duration::from_secs(GRACE_PERIOD))
+}
+
+/// Remove all objects under `root` which are not referenced, and older than `grace`.
+fn gc_objects_older_than(
+    root: &Path,
+    referenced: &BTreeSet<Checksum>,
+    grace: Duration,
+) -> Result<Gc> {
+    let mut gc = Gc::default();
+
+    if !root.is_dir() {
+        return Ok(gc);
+    }
+
+    let now = SystemTime::now();
+
+    for first in read_dir(root)? {
+        if !is_prefix_dir(&first) {
+            continue;
+        }
+
+        for second in read_dir(&first)? {
+            if !is_prefix_dir(&second) {
+                continue;
+            }
+
+            for object in read_dir(&second)? {
+                let remove = {
+                    let name = match object.file_name().and_then(|n| n.to_str()) {
+                        Some(name) => name,
+                        None => continue,
+                    };
+
+                    if name.ends_with(".tmp") {
+                        true
+                    } else {
+                        match Checksum::from_str(name) {
+                            Ok(checksum) => !referenced.contains(&checksum),
+                            Err(_) => false,
+                        }
+                    }
+                };
+
+                if !remove {
+                    continue;
+                }
+
+                let m = match not_found(fs::metadata(&object))? {
+                    Some(m) => m,
+                    None => continue,
+                };
+
+                if !m.is_file() || age(now, &m) < grace {
+                    continue;
+                }
+
+                debug!("gc: removing object: {}", object.display());
+
+                if not_found(fs::remove_file(&object))?.is_some() {
+                    gc.reclaimed += m.len();
+                    gc.objects += 1;
+                }
+            }
+
+            remove_if_empty(&second, now, grace)?;
+        }
+
+        remove_if_empty(&first, now, grace)?;
+    }
+
+    Ok(gc)
+}
+
+/// List the paths in the given directory, which is empty if the directory has been removed.
+fn read_dir(path: &Path) -> Result<Vec<PathBuf>> {
+    let mut out = Vec::new();
+
+    let entries = match not_found(fs::read_dir(path))? {
+        Some(entries) => entries,
+        None => return Ok(out),
+    };
+
+    for e in entries {
+        if let Some(e) = not_found(e)? {
+            out.push(e.path());
+        }
+    }
+
+    Ok(out)
+}
+
+/// Convert the result of a filesystem operation, treating a missing file as `None`.
+///
+/// Files might be removed concurrently, which should not abort the collection.
+pub fn not_found<T>(result: io::Result<T>) -> Result<Option<T>> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Time since the given file was modified, files modified in the future are treated as new.
+fn age(now: SystemTime, m: &fs::Metadata) -> Duration {
+    m.modified()
+        .ok()
+        .and_then(|modified| now.duration_since(modified).ok())
+        .unwrap_or_else(|| Duration::new(0, 0))
+}
+
+/// Remove the given directory if it is empty, and has not been modified within the grace period.
+///
+/// A publish which is in progress might just have created the directory.
+fn remove_if_empty(path: &Path, now: SystemTime, grace: Duration) -> Result<()> {
+    let m = match not_found(fs::metadata(path))? {
+        Some(m) => m,
+        None => return Ok(()),
+    };
+
+    if age(now, &m) < grace || !read_dir(path)?.is_empty() {
+        return Ok(());
+    }
+
+    if let Err(e) = fs::remove_dir(path) {
+        // a file might have been added since the directory was listed.
+        debug!("gc: not removing directory: {}: {}", path.display(), e);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::io::Write;
+    use tempdir::TempDir;
+
+    fn put(root: &Path, checksum: &Checksum, name: &str) -> PathBuf {
+        let hex = checksum.to_string();
+        let dir = root.join(&hex[0..2]).join(&hex[2..4]);
+        fs::create_dir_all(&dir).unwrap();
+
+        let path = dir.join(name);
+        File::create(&path).unwrap().write_all(b"hello").unwrap();
+        path
+    }
+
+    #[test]
+    fn test_gc_objects() {
+        let dir = TempDir::new("gc").unwrap();
+
+        let referenced = Checksum::new(vec![1u8; 32]);
+        let unreferenced = Checksum::new(vec![2u8; 32]);
+
+        let referenced_path = put(dir.path(), &referenced, &referenced.to_string());
+        let unreferenced_path = put(dir.path(), &unreferenced, &unreferenced.to_string());
+        let tmp_path = put(dir.path(), &unreferenced, "upload.tmp");
+
+        let mut set = BTreeSet::new();
+        set.insert(referenced.clone());
+
+        // everything is fresh, so nothing is removed.
+        let gc = gc_objects(dir.path(), &set).unwrap();
+        assert_eq!(0, gc.objects);
+        assert!(unreferenced_path.is_file());
+        assert!(tmp_path.is_file());
+
+        let gc = gc_objects_older_than(dir.path(), &set, Duration::new(0, 0)).unwrap();
+        assert_eq!(2, gc.objects);
+        assert_eq!(10, gc.reclaimed);
+        assert!(referenced_path.is_file());
+        assert!(!unreferenced_path.exists());
+        assert!(!tmp_path.exists());
+    }
+
+    #[test]
+    fn test_gc_missing_root() {
+        let dir = TempDir::new("gc").unwrap();
+        let gc = gc_objects(&dir.path().join("missing"), &BTreeSet::new()).unwrap();
+        assert_eq!(0, gc.objects);
+    }
+}
@@ -1,14 +1,18 @@
 mod file_objects;
 mod git_objects;
 mod cached_objects;
+mod gc;
 
 pub use self::cached_objects::CachedObjects;
 pub use self::file_objects::FileObjects;
+pub use self::gc::Gc;
+use self::gc::{gc_objects, not_found};
 pub use self::git_objects::GitObjects;
 use checksum::Checksum;
 use core::Object;
 use core::errors::*;
 use git;
+use std::collections::BTreeSet;
 use std::io::Read;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
@@ -39,6 +43,14 @@ pub trait Objects {
     fn update(&self) -> Result<Vec<Update>> {
         Ok(vec![])
     }
+
+    /// Remove all objects which are not part of `referenced`, together with any stale cache
+    /// entries.
+    ///
+    /// Backends which do not manage local storage leave everything untouched.
+    fn gc(&mut self, _referenced: &BTreeSet<Checksum>) -> Result<Gc> {
+        Ok(Gc::default())
+    }
 }
 
 pub struct NoObjects;
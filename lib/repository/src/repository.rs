@@ -1,14 +1,17 @@
-use super::Objects;
+use super::{Gc, Objects};
 use core::{self, Object, Resolved, ResolvedByPrefix, Resolver, RpPackage, RpRequiredPackage,
            Version};
 use core::errors::*;
-use index::{Deployment, Index};
+use index::{referenced_objects, Deployment, Index};
 use sha256::to_sha256;
+use signing::SigningKey;
+use trust::Trust;
 use update::Update;
 
 pub struct Repository {
     index: Box<Index>,
     objects: Box<Objects>,
+    trust: Trust,
 }
 
 impl Repository {
@@ -16,6 +19,15 @@ impl Repository {
         Repository {
             index: index,
             objects: objects,
+            trust: Trust::new(),
+        }
+    }
+
+    /// Only resolve deployments which are trusted according to the given configuration.
+    pub fn with_trust(self, trust: Trust) -> Repository {
+        Repository {
+            trust: trust,
+            ..self
         }
     }
 
@@ -31,6 +43,7 @@ impl Repository {
         object: O,
         package: &RpPackage,
         version: &Version,
+        signing_key: Option<&SigningKey>,
         force: bool,
     ) -> Result<()>
     where
@@ -47,18 +60,76 @@ impl Repository {
         let object = object.as_ref();
         let checksum = to_sha256(object.read()?)?;
 
+        let signature = signing_key.map(|key| key.sign(package, version, &checksum));
+
         self.objects
             .put_object(&checksum, &mut object.read()?, force)?;
-        self.index.put_version(&checksum, package, version, force)?;
+        self.index
+            .put_version(&checksum, package, version, signature, force)?;
 
         Ok(())
     }
 
+    /// Mark the given version of a package as yanked.
+    ///
+    /// Yanked versions are skipped when resolving, unless pinned to their exact version.
+    pub fn yank(
+        &mut self,
+        package: &RpPackage,
+        version: &Version,
+        deprecated: Option<&str>,
+    ) -> Result<()> {
+        self.index.set_yanked(package, version, true, deprecated)
+    }
+
+    /// Reverse the effect of `yank`.
+    pub fn unyank(&mut self, package: &RpPackage, version: &Version) -> Result<()> {
+        self.index.set_yanked(package, version, false, None)
+    }
+
     /// Get all deployments in this repository.
     pub fn all(&self, package: &RpPackage) -> Result<Vec<Deployment>> {
         self.index.all(package)
     }
 
+    /// Get all deployments matching the exact version of a package.
+    pub fn get_deployments(&self, package: &RpPackage, version: &Version) -> Result<Vec<Deployment>> {
+        self.index.get_deployments(package, version)
+    }
+
+    /// List all packages in this repository.
+    pub fn packages(&self) -> Result<Vec<RpPackage>> {
+        self.index.packages()
+    }
+
+    /// Copy the given deployment, and its object, into another repository.
+    ///
+    /// Any existing deployment with the same version in the target is replaced.
+    pub fn copy_deployment(
+        &mut self,
+        target: &mut Repository,
+        package: &RpPackage,
+        deployment: &Deployment,
+    ) -> Result<()> {
+        let object = self.get_object(deployment)?
+            .ok_or_else(|| format!("missing object: {}", deployment.object))?;
+
+        target
+            .objects
+            .put_object(&deployment.object, &mut object.read()?, false)?;
+        target
+            .index
+            .put_deployment(package, deployment.clone(), true)?;
+
+        Ok(())
+    }
+
+    /// Remove all objects which are no longer referenced by the index, and any stale caches.
+    pub fn gc(&mut self) -> Result<Gc> {
+        let referenced = referenced_objects(self.index.as_ref())?;
+        self.objects.gc(&referenced)
+    }
+
     /// Get the object for the specific deployment.
     pub fn get_object(&mut self, deployment: &Deployment) -> Result<Option<Box<Object>>> {
         self.objects.get_object(&deployment.object)
@@ -70,8 +141,42 @@ impl Resolver for Repository {
         let mut out = Vec::new();
 
         let deployments = self.index.resolve(&package.package, &package.range)?;
+        let mut last_untrusted = None;
+        let mut trusted = Vec::new();
 
+        // untrusted deployments are skipped, so that they only fail resolution if nothing else
+        // matches.
         for deployment in deployments {
+            if let Err(e) = self.trust.verify(&package.package, &deployment) {
+                warn!("skipping untrusted deployment: {}", e.message());
+                last_untrusted = Some(e);
+                continue;
+            }
+
+            trusted.push(deployment);
+        }
+
+        if trusted.is_empty() {
+            if let Some(e) = last_untrusted {
+                return Err(format!("{}: no trusted deployments: {}", package, e.message()).into());
+            }
+        }
+
+        for deployment in trusted {
+            if deployment.yanked {
+                warn!(
+                    "{}@{}: version has been yanked",
+                    package.package, deployment.version
+                );
+            }
+
+            if let Some(ref message) = deployment.deprecated {
+                warn!(
+                    "{}@{}: deprecated: {}",
+                    package.package, deployment.version, message
+                );
+            }
+
             if let Some(path) = self.get_object(&deployment)? {
                 out.push(Resolved {
                     version: Some(deployment.version),
@@ -89,3 +194,153 @@ impl Resolver for Repository {
         Err("repository does not support resolve by prefix".into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::{BytesObject, Range};
+    use index::{init_file_index, FileIndex};
+    use objects::FileObjects;
+    use signing::tests::signing_key;
+    use std::io::Read;
+    use std::path::Path;
+    use std::sync::Arc;
+    use tempdir::TempDir;
+
+    /// Open a repository with a file index and file objects in the given directory.
+    fn file_repository(path: &Path) -> Repository {
+        init_file_index(&path.join("index")).unwrap();
+
+        let index = FileIndex::new(&path.join("index")).unwrap();
+        let objects = FileObjects::new(&path.join("objects"));
+
+        Repository::new(Box::new(index), Box::new(objects))
+    }
+
+    fn publish(
+        repository: &mut Repository,
+        package: &str,
+        version: &str,
+        key: Option<&SigningKey>,
+    ) {
+        let object = BytesObject::new(
+            version.to_string(),
+            Arc::new(format!("// {} {}", package, version).into_bytes()),
+        );
+
+        let package = RpPackage::parse(package);
+        let version = Version::parse(version).unwrap();
+
+        repository
+            .publish(Box::new(object) as Box<Object>, &package, &version, key, false)
+            .unwrap();
+    }
+
+    fn resolve(repository: &mut Repository, package: &str, range: &str) -> Result<Vec<String>> {
+        let package = RpRequiredPackage::new(RpPackage::parse(package), Range::parse(range)?);
+
+        Ok(repository
+            .resolve(&package)?
+            .into_iter()
+            .map(|r| r.version.map(|v| v.to_string()).unwrap_or_default())
+            .collect())
+    }
+
+    #[test]
+    fn test_skip_untrusted() {
+        let dir = TempDir::new("repository").unwrap();
+
+        let key = signing_key();
+
+        let mut trust = Trust::new();
+        trust.protect(RpPackage::parse("foo"), vec![key.public_key()]);
+
+        let mut repository = file_repository(dir.path()).with_trust(trust);
+
+        publish(&mut repository, "foo.bar", "1.0.0", None);
+        publish(&mut repository, "foo.bar", "1.1.0", Some(&key));
+
+        assert_eq!(
+            vec!["1.1.0".to_string()],
+            resolve(&mut repository, "foo.bar", "^1.0.0").unwrap()
+        );
+        assert!(resolve(&mut repository, "foo.bar", "=1.0.0").is_err());
+    }
+
+    #[test]
+    fn test_mirror() {
+        let dir = TempDir::new("repository").unwrap();
+
+        let mut source = file_repository(&dir.path().join("source"));
+        let mut target = file_repository(&dir.path().join("target"));
+
+        publish(&mut source, "foo.bar", "1.0.0", None);
+        publish(&mut source, "foo.bar", "1.1.0", None);
+        publish(&mut source, "baz", "0.1.0", None);
+
+        for package in source.packages().unwrap() {
+            for deployment in source.all(&package).unwrap() {
+                source
+                    .copy_deployment(&mut target, &package, &deployment)
+                    .unwrap();
+            }
+        }
+
+        let mut packages = target
+            .packages()
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+
+        packages.sort();
+
+        assert_eq!(vec!["baz", "foo.bar"], packages);
+        assert_eq!(
+            vec!["1.0.0".to_string(), "1.1.0".to_string()],
+            resolve(&mut target, "foo.bar", "^1.0.0").unwrap()
+        );
+        assert_eq!(
+            vec!["0.1.0".to_string()],
+            resolve(&mut target, "baz", "^0.1.0").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_vendor() {
+        let dir = TempDir::new("repository").unwrap();
+
+        let mut source = file_repository(&dir.path().join("source"));
+        let mut vendor = file_repository(&dir.path().join("vendor"));
+
+        publish(&mut source, "foo.bar", "1.0.0", None);
+        publish(&mut source, "foo.bar", "1.1.0", None);
+
+        let package = RpPackage::parse("foo.bar");
+        let version = Version::parse("1.0.0").unwrap();
+
+        for deployment in source.get_deployments(&package, &version).unwrap() {
+            source
+                .copy_deployment(&mut vendor, &package, &deployment)
+                .unwrap();
+        }
+
+        // only the dependency which is used is vendored.
+        assert_eq!(
+            vec!["1.0.0".to_string()],
+            resolve(&mut vendor, "foo.bar", "^1.0.0").unwrap()
+        );
+
+        let deployments = vendor.get_deployments(&package, &version).unwrap();
+        assert_eq!(1, deployments.len());
+
+        let object = vendor
+            .get_object(&deployments[0])
+            .unwrap()
+            .expect("vendored object");
+
+        let mut content = String::new();
+        object.read().unwrap().read_to_string(&mut content).unwrap();
+        assert_eq!("// foo.bar 1.0.0", content);
+    }
+}
@@ -0,0 +1,159 @@
+//! Signing and verification of deployments using ed25519 keys.
+//!
+//! Keys are stored as hex-encoded PKCS#8 documents.
+
+use checksum::Checksum;
+use core::{RpPackage, Version};
+use core::errors::*;
+use hex::FromHex;
+use hex_slice::HexSlice;
+use ring::{rand, signature};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+use untrusted;
+
+/// Signature of a deployment, as stored in the index.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Signature {
+    /// Hex-encoded public key of the signer.
+    pub key: String,
+    /// Hex-encoded ed25519 signature.
+    pub signature: String,
+}
+
+impl Signature {
+    /// Verify that this signature is valid for the given deployment.
+    pub fn verify(&self, package: &RpPackage, version: &Version, checksum: &Checksum) -> Result<()> {
+        let key: Vec<u8> = FromHex::from_hex(self.key.as_str())?;
+        let sig: Vec<u8> = FromHex::from_hex(self.signature.as_str())?;
+        let message = message(package, version, checksum);
+
+        signature::verify(
+            &signature::ED25519,
+            untrusted::Input::from(&key),
+            untrusted::Input::from(message.as_bytes()),
+            untrusted::Input::from(&sig),
+        ).map_err(|_| format!("{}@{}: bad signature", package, version))?;
+
+        Ok(())
+    }
+}
+
+/// Build the message that is signed for a given deployment.
+///
+/// Includes the package and version to prevent a signed object from being re-published under a
+/// different name.
+fn message(package: &RpPackage, version: &Version, checksum: &Checksum) -> String {
+    format!("{}@{}:{}", package, version, checksum)
+}
+
+/// A local key used to sign deployments.
+pub struct SigningKey {
+    key_pair: signature::Ed25519KeyPair,
+}
+
+impl SigningKey {
+    /// Generate a new key, and write it to the given path.
+    pub fn generate<P: AsRef<Path>>(path: P) -> Result<SigningKey> {
+        let path = path.as_ref();
+
+        if path.exists() {
+            return Err(format!("{}: already exists", path.display()).into());
+        }
+
+        let rng = rand::SystemRandom::new();
+
+        let pkcs8 = signature::Ed25519KeyPair::generate_pkcs8(&rng)
+            .map_err(|_| "failed to generate key")?;
+
+        let mut f = File::create(path)?;
+        writeln!(f, "{}", HexSlice::new(&pkcs8[..]))?;
+
+        Self::from_pkcs8(&pkcs8[..])
+    }
+
+    /// Load a key from the given path.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<SigningKey> {
+        let path = path.as_ref();
+
+        let mut f = File::open(path)
+            .map_err(|e| format!("failed to open {}: {}", path.display(), e))?;
+
+        let mut content = String::new();
+        f.read_to_string(&mut content)?;
+
+        let pkcs8: Vec<u8> = FromHex::from_hex(content.trim())
+            .map_err(|e| format!("{}: bad key: {}", path.display(), e))?;
+
+        Self::from_pkcs8(&pkcs8)
+    }
+
+    fn from_pkcs8(pkcs8: &[u8]) -> Result<SigningKey> {
+        let key_pair = signature::Ed25519KeyPair::from_pkcs8(untrusted::Input::from(pkcs8))
+            .map_err(|_| "not a valid ed25519 key")?;
+
+        Ok(SigningKey { key_pair: key_pair })
+    }
+
+    /// Hex-encoded public key, as used in trust configurations.
+    pub fn public_key(&self) -> String {
+        HexSlice::new(self.key_pair.public_key_bytes()).to_string()
+    }
+
+    /// Sign the given deployment.
+    pub fn sign(&self, package: &RpPackage, version: &Version, checksum: &Checksum) -> Signature {
+        let message = message(package, version, checksum);
+        let sig = self.key_pair.sign(message.as_bytes());
+
+        Signature {
+            key: self.public_key(),
+            signature: HexSlice::new(sig.as_ref()).to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    /// Generate a key without storing it.
+    pub fn signing_key() -> SigningKey {
+        let rng = rand::SystemRandom::new();
+        let pkcs8 = signature::Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+        SigningKey::from_pkcs8(&pkcs8[..]).unwrap()
+    }
+
+    #[test]
+    fn test_sign_verify() {
+        let key = signing_key();
+
+        let package = RpPackage::parse("foo.bar");
+        let version = Version::parse("1.0.0").unwrap();
+        let checksum = Checksum::new(vec![1u8; 32]);
+
+        let signature = key.sign(&package, &version, &checksum);
+        assert_eq!(key.public_key(), signature.key);
+        assert!(signature.verify(&package, &version, &checksum).is_ok());
+    }
+
+    #[test]
+    fn test_tampered() {
+        let key = signing_key();
+
+        let package = RpPackage::parse("foo.bar");
+        let version = Version::parse("1.0.0").unwrap();
+        let checksum = Checksum::new(vec![1u8; 32]);
+
+        let signature = key.sign(&package, &version, &checksum);
+
+        let tampered = Checksum::new(vec![2u8; 32]);
+        assert!(signature.verify(&package, &version, &tampered).is_err());
+
+        let other_version = Version::parse("1.0.1").unwrap();
+        assert!(signature.verify(&package, &other_version, &checksum).is_err());
+
+        let other_package = RpPackage::parse("foo.baz");
+        assert!(signature.verify(&other_package, &version, &checksum).is_err());
+    }
+}
@@ -0,0 +1,113 @@
+//! Trust configuration for deployments.
+
+use core::RpPackage;
+use core::errors::*;
+use index::Deployment;
+
+/// Keys which are trusted to sign deployments for a set of protected package prefixes.
+#[derive(Debug, Clone, Default)]
+pub struct Trust {
+    protected: Vec<(RpPackage, Vec<String>)>,
+}
+
+impl Trust {
+    pub fn new() -> Trust {
+        Trust {
+            protected: Vec::new(),
+        }
+    }
+
+    /// Protect the given package prefix, only permitting deployments signed by any of `keys`.
+    pub fn protect(&mut self, prefix: RpPackage, keys: Vec<String>) {
+        self.protected.push((prefix, keys));
+    }
+
+    /// Find the trusted keys for the most specific prefix protecting the given package.
+    fn keys_for(&self, package: &RpPackage) -> Option<&[String]> {
+        self.protected
+            .iter()
+            .filter(|p| package.starts_with(&p.0))
+            .max_by_key(|p| p.0.parts.len())
+            .map(|p| p.1.as_slice())
+    }
+
+    /// Verify that the deployment is signed by a trusted key, if its package is protected.
+    pub fn verify(&self, package: &RpPackage, deployment: &Deployment) -> Result<()> {
+        let keys = match self.keys_for(package) {
+            Some(keys) => keys,
+            None => return Ok(()),
+        };
+
+        let signature = deployment.signature.as_ref().ok_or_else(|| {
+            format!(
+                "{}@{}: deployment is not signed, but package is protected",
+                package, deployment.version
+            )
+        })?;
+
+        if !keys.iter().any(|k| k.eq_ignore_ascii_case(&signature.key)) {
+            return Err(format!(
+                "{}@{}: deployment signed by unknown key: {}",
+                package, deployment.version, signature.key
+            ).into());
+        }
+
+        signature.verify(package, &deployment.version, &deployment.object)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use checksum::Checksum;
+    use core::Version;
+    use signing::SigningKey;
+    use signing::tests::signing_key;
+
+    fn deployment(package: &RpPackage, key: Option<&SigningKey>) -> Deployment {
+        let version = Version::parse("1.0.0").unwrap();
+        let checksum = Checksum::new(vec![1u8; 32]);
+        let signature = key.map(|k| k.sign(package, &version, &checksum));
+        Deployment::new(version, checksum, signature)
+    }
+
+    #[test]
+    fn test_unprotected() {
+        let trust = Trust::new();
+        let package = RpPackage::parse("foo.bar");
+        assert!(trust.verify(&package, &deployment(&package, None)).is_ok());
+    }
+
+    #[test]
+    fn test_unsigned() {
+        let key = signing_key();
+
+        let mut trust = Trust::new();
+        trust.protect(RpPackage::parse("foo"), vec![key.public_key()]);
+
+        let package = RpPackage::parse("foo.bar");
+        assert!(trust.verify(&package, &deployment(&package, None)).is_err());
+        assert!(trust.verify(&package, &deployment(&package, Some(&key))).is_ok());
+    }
+
+    #[test]
+    fn test_longest_prefix() {
+        let outer = signing_key();
+        let inner = signing_key();
+
+        let mut trust = Trust::new();
+        trust.protect(RpPackage::parse("foo.bar"), vec![inner.public_key()]);
+        trust.protect(RpPackage::parse("foo"), vec![outer.public_key()]);
+
+        let nested = RpPackage::parse("foo.bar.baz");
+        assert!(trust.verify(&nested, &deployment(&nested, Some(&inner))).is_ok());
+        assert!(trust.verify(&nested, &deployment(&nested, Some(&outer))).is_err());
+
+        let sibling = RpPackage::parse("foo.other");
+        assert!(trust.verify(&sibling, &deployment(&sibling, Some(&outer))).is_ok());
+        assert!(trust.verify(&sibling, &deployment(&sibling, Some(&inner))).is_err());
+
+        let unrelated = RpPackage::parse("other");
+        assert!(trust.verify(&unrelated, &deployment(&unrelated, None)).is_ok());
+    }
+}
@@ -0,0 +1,411 @@
+//! Generator for sample JSON payloads.
+//!
+//! Produces random, but valid, instances of the declarations loaded in an `Environment`. The
+//! generator is seeded, so the same seed always produces the same sequence of payloads.
+
+extern crate reproto_core as core;
+extern crate reproto_trans as trans;
+extern crate serde_json;
+
+use core::{Loc, RpEnumBody, RpField, RpInterfaceBody, RpName, RpReg, RpSubType,
+           RpSubTypeStrategy, RpTupleBody, RpType};
+use core::errors::*;
+use serde_json::Value;
+use serde_json::map::Map;
+use std::time::{SystemTime, UNIX_EPOCH};
+use trans::Environment;
+
+/// Nesting depth after which optional fields are left out and collections are empty.
+const SHALLOW_DEPTH: usize = 8;
+/// Nesting depth at which sampling gives up, since the declaration can't be satisfied.
+const MAX_DEPTH: usize = 64;
+/// Characters used in generated strings.
+const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+/// Characters used when base64-encoding bytes.
+const BASE64: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// A small, seedable pseudo-random number generator (splitmix64).
+///
+/// Not suitable for anything but generating test data.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    /// Generate the next random number.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Generate a random number in the inclusive range `[lo, hi]`.
+    pub fn range(&mut self, lo: i64, hi: i64) -> i64 {
+        let span = (hi - lo) as u64 + 1;
+        lo + (self.next_u64() % span) as i64
+    }
+
+    /// Pick a random index in `[0, len)`.
+    pub fn index(&mut self, len: usize) -> usize {
+        (self.next_u64() % len as u64) as usize
+    }
+
+    /// Flip a coin.
+    pub fn coin(&mut self) -> bool {
+        self.next_u64() & 1 == 1
+    }
+}
+
+/// Generates sample values for declarations in an environment.
+pub struct Sampler<'a> {
+    env: &'a Environment,
+    rng: Rng,
+    depth: usize,
+}
+
+impl<'a> Sampler<'a> {
+    pub fn new(env: &'a Environment, seed: u64) -> Sampler<'a> {
+        Sampler {
+            env: env,
+            rng: Rng::new(seed),
+            depth: 0,
+        }
+    }
+
+    /// A seed based on the current time, for when no seed was asked for.
+    pub fn seed_from_time() -> Result<u64> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| format!("bad system time: {}", e))?;
+
+        Ok(now.as_secs() ^ now.subsec_nanos() as u64)
+    }
+
+    /// Generate a sample of the declaration with the given name.
+    pub fn sample(&mut self, name: &RpName) -> Result<Value> {
+        self.sample_type(&RpType::Name { name: name.clone() })
+    }
+
+    /// Generate a sample of the given type.
+    pub fn sample_type(&mut self, ty: &RpType) -> Result<Value> {
+        if self.depth >= MAX_DEPTH {
+            return Err(format!("{}: too deeply nested to sample", ty).into());
+        }
+
+        self.depth += 1;
+        let value = self.ty(ty);
+        self.depth -= 1;
+        value
+    }
+
+    /// Check if sampling is deep enough that it should avoid growing any further.
+    fn is_shallow(&self) -> bool {
+        self.depth < SHALLOW_DEPTH
+    }
+
+    fn ty(&mut self, ty: &RpType) -> Result<Value> {
+        use core::RpType::*;
+
+        // 64-bit numbers are kept within 32 bits, since not every language can represent the full
+        // range when decoding JSON.
+        let value = match *ty {
+            Any => match self.rng.index(3) {
+                0 => Value::from(self.rng.coin()),
+                1 => Value::from(self.rng.range(-1000, 1000)),
+                _ => Value::from(self.string()),
+            },
+            Double | Float => Value::from(self.rng.range(-4000, 4000) as f64 / 4f64),
+            Signed { .. } => {
+                let (lo, hi) = (i32::min_value() as i64, i32::max_value() as i64);
+                Value::from(self.rng.range(lo, hi))
+            }
+            Unsigned { .. } => Value::from(self.rng.range(0, u32::max_value() as i64) as u64),
+            Boolean => Value::from(self.rng.coin()),
+            String => Value::from(self.string()),
+            DateTime => Value::from(self.datetime()),
+            Bytes => Value::from(self.bytes()),
+            Array { ref inner } => {
+                let mut values = Vec::new();
+
+                for _ in 0..self.length() {
+                    values.push(self.sample_type(inner)?);
+                }
+
+                Value::Array(values)
+            }
+            Map { ref key, ref value } => {
+                let mut entries = Map::new();
+
+                for _ in 0..self.length() {
+                    let k = self.key(key)?;
+                    entries.insert(k, self.sample_type(value)?);
+                }
+
+                Value::Object(entries)
+            }
+            Name { ref name } => {
+                let reg = self.env.lookup(name)?;
+                self.reg(reg)?
+            }
+        };
+
+        Ok(value)
+    }
+
+    fn reg(&mut self, reg: &RpReg) -> Result<Value> {
+        use core::RpReg::*;
+
+        match *reg {
+            Type(ref body) => {
+                let mut object = Map::new();
+                self.fields(&mut object, &body.fields)?;
+                Ok(Value::Object(object))
+            }
+            Tuple(ref body) => self.tuple_body(body),
+            Interface(ref body) => {
+                if body.sub_types.is_empty() {
+                    return Err(format!("{}: interface has no sub-types", body.name).into());
+                }
+
+                let index = self.rng.index(body.sub_types.len());
+                self.interface_body(body, &body.sub_types[index])
+            }
+            SubType(ref body, ref sub_type) => self.interface_body(body, sub_type),
+            Enum(ref body) => self.enum_body(body),
+            EnumVariant(_, ref variant) => Ok(Value::from(variant.ordinal())),
+            Service(ref body) => Err(format!("{}: services can't be sampled", body.name).into()),
+        }
+    }
+
+    fn tuple_body(&mut self, body: &RpTupleBody) -> Result<Value> {
+        let mut values = Vec::new();
+
+        // tuples are positional, so optional fields which are left out are encoded as null.
+        for field in &body.fields {
+            if field.is_optional() && !self.include_optional() {
+                values.push(Value::Null);
+                continue;
+            }
+
+            values.push(self.sample_type(&field.ty)?);
+        }
+
+        Ok(Value::Array(values))
+    }
+
+    fn interface_body(
+        &mut self,
+        body: &RpInterfaceBody,
+        sub_type: &Loc<RpSubType>,
+    ) -> Result<Value> {
+        let tag = match body.sub_type_strategy {
+            RpSubTypeStrategy::Tagged { ref tag } => tag.as_str(),
+        };
+
+        let mut object = Map::new();
+        object.insert(tag.to_string(), Value::from(sub_type.name()));
+        self.fields(&mut object, &body.fields)?;
+        self.fields(&mut object, &sub_type.fields)?;
+        Ok(Value::Object(object))
+    }
+
+    fn enum_body(&mut self, body: &RpEnumBody) -> Result<Value> {
+        if body.variants.is_empty() {
+            return Err(format!("{}: enum has no variants", body.name).into());
+        }
+
+        let index = self.rng.index(body.variants.len());
+        Ok(Value::from(body.variants[index].ordinal()))
+    }
+
+    fn fields(
+        &mut self,
+        object: &mut Map<::std::string::String, Value>,
+        fields: &[Loc<RpField>],
+    ) -> Result<()> {
+        for field in fields {
+            if field.is_optional() && !self.include_optional() {
+                continue;
+            }
+
+            let value = self.sample_type(&field.ty)?;
+            object.insert(field.name().to_string(), value);
+        }
+
+        Ok(())
+    }
+
+    /// Generate a map key for the given type.
+    ///
+    /// Keys are always strings in JSON, numeric keys are encoded as strings.
+    fn key(&mut self, ty: &RpType) -> Result<String> {
+        match self.sample_type(ty)? {
+            Value::String(string) => Ok(string),
+            Value::Number(number) => Ok(number.to_string()),
+            Value::Bool(boolean) => Ok(boolean.to_string()),
+            _ => Err(format!("{}: can't be used as a map key", ty).into()),
+        }
+    }
+
+    /// Decide if an optional field should be included.
+    fn include_optional(&mut self) -> bool {
+        self.is_shallow() && self.rng.coin()
+    }
+
+    /// Length of a collection.
+    fn length(&mut self) -> usize {
+        if !self.is_shallow() {
+            return 0;
+        }
+
+        self.rng.index(4)
+    }
+
+    fn string(&mut self) -> String {
+        let len = self.rng.index(13);
+        (0..len)
+            .map(|_| ALPHABET[self.rng.index(ALPHABET.len())] as char)
+            .collect()
+    }
+
+    /// Generate an ISO-8601 datetime, in UTC.
+    fn datetime(&mut self) -> String {
+        format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+            self.rng.range(1970, 2099),
+            self.rng.range(1, 12),
+            self.rng.range(1, 28),
+            self.rng.range(0, 23),
+            self.rng.range(0, 59),
+            self.rng.range(0, 59),
+        )
+    }
+
+    /// Generate padded, base64-encoded bytes.
+    fn bytes(&mut self) -> String {
+        let len = self.rng.index(13);
+        let bytes: Vec<u8> = (0..len).map(|_| self.rng.next_u64() as u8).collect();
+
+        let mut out = String::new();
+
+        for chunk in bytes.chunks(3) {
+            let b = [
+                chunk[0],
+                chunk.get(1).cloned().unwrap_or(0),
+                chunk.get(2).cloned().unwrap_or(0),
+            ];
+
+            let indexes = [
+                b[0] >> 2,
+                ((b[0] & 0x03) << 4) | (b[1] >> 4),
+                ((b[1] & 0x0f) << 2) | (b[2] >> 6),
+                b[2] & 0x3f,
+            ];
+
+            for (i, index) in indexes.iter().enumerate() {
+                if i > chunk.len() {
+                    out.push('=');
+                } else {
+                    out.push(BASE64[*index as usize] as char);
+                }
+            }
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate reproto_validate as validate;
+
+    use self::validate::Validator;
+    use super::*;
+    use trans::testing::{environment, package};
+
+    const SPEC: &str = r#"
+    type Foo {
+      id: u64;
+      name?: string as "display_name";
+      created: datetime;
+      data?: bytes;
+      tags: {string: [Color]};
+      pair?: Pair;
+      shape: Shape;
+      next?: Foo;
+    }
+
+    tuple Pair {
+      a: i32;
+      b?: float;
+    }
+
+    enum Color as string {
+      Red as "red";
+      Green as "green";
+    }
+
+    #[type_info(strategy = "tagged", tag = "@type")]
+    interface Shape {
+      name: string;
+
+      Circle as "circle" {
+        radius: double;
+      }
+
+      Square {
+        side: u32;
+      }
+    }
+    "#;
+
+    fn name(name: &str) -> RpName {
+        RpName::new(None, package(), vec![name.to_string()])
+    }
+
+    #[test]
+    fn test_samples_are_valid() {
+        let env = environment(SPEC);
+        let validator = Validator::new(&env);
+
+        for decl in &["Foo", "Pair", "Color", "Shape"] {
+            let name = name(decl);
+
+            for seed in 0..100 {
+                let value = Sampler::new(&env, seed).sample(&name).expect("to sample");
+                let violations = validator.validate(&name, &value).expect("to validate");
+                assert!(violations.is_empty(), "{}: {:?}", value, violations);
+            }
+        }
+    }
+
+    #[test]
+    fn test_deterministic() {
+        let env = environment(SPEC);
+        let name = name("Foo");
+
+        let a = Sampler::new(&env, 42).sample(&name).expect("to sample");
+        let b = Sampler::new(&env, 42).sample(&name).expect("to sample");
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_interface_tag() {
+        let env = environment(SPEC);
+        let name = name("Shape");
+
+        for seed in 0..20 {
+            let value = Sampler::new(&env, seed).sample(&name).expect("to sample");
+            let tag = value.get("@type").and_then(|t| t.as_str());
+            assert!(tag == Some("circle") || tag == Some("Square"), "{}", value);
+        }
+    }
+}
@@ -0,0 +1,407 @@
+//! Structural differences between two versions of a specification.
+//!
+//! Unlike the semantic checks, a diff doesn't judge whether a change is permitted. It lists every
+//! declaration, field, variant, and endpoint which has been added, removed, or changed.
+
+use super::{decls_to_map, endpoints_to_map, enum_variants, fields, fields_to_map,
+            variants_to_map};
+use reproto_core::{Loc, RpChannel, RpEndpoint, RpField, RpFile, RpReg, RpVariant};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        use self::ChangeKind::*;
+
+        let kind = match *self {
+            Added => "added",
+            Removed => "removed",
+            Changed => "changed",
+        };
+
+        fmt.write_str(kind)
+    }
+}
+
+/// A single difference between two versions.
+#[derive(Debug, Clone, Serialize)]
+pub struct Change {
+    pub kind: ChangeKind,
+    /// What kind of item was changed, like `type`, `field`, or `endpoint`.
+    pub item: &'static str,
+    /// Path to the changed item, like `Foo::bar`.
+    pub path: String,
+    /// Description of what changed, only present for changed items.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<String>,
+}
+
+impl Change {
+    fn new(kind: ChangeKind, item: &'static str, path: String) -> Change {
+        Change {
+            kind: kind,
+            item: item,
+            path: path,
+            details: Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{} {} `{}`", self.kind, self.item, self.path)?;
+
+        if !self.details.is_empty() {
+            write!(fmt, ": {}", self.details.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Calculate all differences between two versions of a file.
+///
+/// Changes are sorted by the path of the item that changed.
+pub fn diff(from: &RpFile, to: &RpFile) -> Vec<Change> {
+    use self::ChangeKind::*;
+
+    let mut changes = Vec::new();
+
+    let from_storage = decls_to_map(&from.decls);
+    let mut to_storage = decls_to_map(&to.decls);
+
+    for (name, from_reg) in from_storage {
+        let path = name.join("::");
+
+        let to_reg = match to_storage.remove(&name) {
+            Some(to_reg) => to_reg,
+            None => {
+                changes.push(Change::new(Removed, kind(&from_reg), path));
+                continue;
+            }
+        };
+
+        if kind(&from_reg) != kind(&to_reg) {
+            let mut change = Change::new(Changed, kind(&to_reg), path.clone());
+            change
+                .details
+                .push(format!("changed from {} to {}", kind(&from_reg), kind(&to_reg)));
+            changes.push(change);
+        }
+
+        let from_fields = fields_to_map(fields(&from_reg));
+        let mut to_fields = fields_to_map(fields(&to_reg));
+
+        for (ident, from_field) in from_fields {
+            let path = format!("{}::{}", path, ident);
+
+            match to_fields.remove(&ident) {
+                Some(to_field) => diff_field(&mut changes, path, from_field, to_field),
+                None => changes.push(Change::new(Removed, "field", path)),
+            }
+        }
+
+        for (ident, _) in to_fields {
+            changes.push(Change::new(Added, "field", format!("{}::{}", path, ident)));
+        }
+
+        let from_variants = variants_to_map(enum_variants(&from_reg));
+        let mut to_variants = variants_to_map(enum_variants(&to_reg));
+
+        for (name, from_variant) in from_variants {
+            let path = name.join("::");
+
+            match to_variants.remove(&name) {
+                Some(to_variant) => diff_variant(&mut changes, path, from_variant, to_variant),
+                None => changes.push(Change::new(Removed, "variant", path)),
+            }
+        }
+
+        for (name, _) in to_variants {
+            changes.push(Change::new(Added, "variant", name.join("::")));
+        }
+
+        let from_endpoints = endpoints_to_map(&from_reg);
+        let mut to_endpoints = endpoints_to_map(&to_reg);
+
+        for (ident, from_endpoint) in from_endpoints {
+            let path = format!("{}::{}", path, ident);
+
+            match to_endpoints.remove(ident) {
+                Some(to_endpoint) => diff_endpoint(&mut changes, path, from_endpoint, to_endpoint),
+                None => changes.push(Change::new(Removed, "endpoint", path)),
+            }
+        }
+
+        for (ident, _) in to_endpoints {
+            changes.push(Change::new(Added, "endpoint", format!("{}::{}", path, ident)));
+        }
+    }
+
+    for (name, to_reg) in to_storage {
+        changes.push(Change::new(Added, kind(&to_reg), name.join("::")));
+    }
+
+    changes.sort_by(|a, b| (&a.path, a.kind).cmp(&(&b.path, b.kind)));
+    changes
+}
+
+/// Describe the kind of the given declaration.
+fn kind(reg: &RpReg) -> &'static str {
+    use reproto_core::RpReg::*;
+
+    match *reg {
+        Type(_) => "type",
+        Tuple(_) => "tuple",
+        Interface(_) => "interface",
+        SubType(_, _) => "sub-type",
+        Enum(_) => "enum",
+        EnumVariant(_, _) => "variant",
+        Service(_) => "service",
+    }
+}
+
+fn diff_field(
+    changes: &mut Vec<Change>,
+    path: String,
+    from_field: &Loc<RpField>,
+    to_field: &Loc<RpField>,
+) {
+    let mut details = Vec::new();
+
+    if from_field.ty.clone().localize() != to_field.ty.clone().localize() {
+        details.push(format!(
+            "type changed from `{}` to `{}`",
+            from_field.ty, to_field.ty
+        ));
+    }
+
+    if from_field.name() != to_field.name() {
+        details.push(format!(
+            "renamed from `{}` to `{}`",
+            from_field.name(),
+            to_field.name()
+        ));
+    }
+
+    if from_field.is_optional() && to_field.is_required() {
+        details.push("made required".to_string());
+    }
+
+    if from_field.is_required() && to_field.is_optional() {
+        details.push("made optional".to_string());
+    }
+
+    push_changed(changes, "field", path, details);
+}
+
+fn diff_variant(
+    changes: &mut Vec<Change>,
+    path: String,
+    from_variant: &Loc<RpVariant>,
+    to_variant: &Loc<RpVariant>,
+) {
+    let mut details = Vec::new();
+
+    if from_variant.ordinal() != to_variant.ordinal() {
+        details.push(format!(
+            "ordinal changed from {:?} to {:?}",
+            from_variant.ordinal(),
+            to_variant.ordinal()
+        ));
+    }
+
+    push_changed(changes, "variant", path, details);
+}
+
+fn diff_endpoint(
+    changes: &mut Vec<Change>,
+    path: String,
+    from_endpoint: &Loc<RpEndpoint>,
+    to_endpoint: &Loc<RpEndpoint>,
+) {
+    let mut details = Vec::new();
+
+    for (ident, &(_, ref from_channel)) in &from_endpoint.arguments {
+        match to_endpoint.arguments.get(ident) {
+            Some(&(_, ref to_channel)) => {
+                if !same_channel(Some(&**from_channel), Some(&**to_channel)) {
+                    details.push(format!(
+                        "argument `{}` changed from `{}` to `{}`",
+                        ident, **from_channel, **to_channel
+                    ));
+                }
+            }
+            None => details.push(format!("argument `{}` removed", ident)),
+        }
+    }
+
+    for ident in to_endpoint.arguments.keys() {
+        if !from_endpoint.arguments.contains_key(ident) {
+            details.push(format!("argument `{}` added", ident));
+        }
+    }
+
+    let from_response = from_endpoint.response.as_ref().map(|r| &**r);
+    let to_response = to_endpoint.response.as_ref().map(|r| &**r);
+
+    if !same_channel(from_response, to_response) {
+        details.push(format!(
+            "response changed from `{}` to `{}`",
+            display_channel(from_response),
+            display_channel(to_response)
+        ));
+    }
+
+    let from_method = from_endpoint.http.method.as_ref().map(|m| m.as_str());
+    let to_method = to_endpoint.http.method.as_ref().map(|m| m.as_str());
+
+    if from_method != to_method {
+        details.push(format!(
+            "HTTP method changed from {} to {}",
+            from_method.unwrap_or("(none)"),
+            to_method.unwrap_or("(none)")
+        ));
+    }
+
+    if from_endpoint.http.path != to_endpoint.http.path {
+        details.push(format!(
+            "HTTP path changed from {} to {}",
+            display_option(from_endpoint.http.path.as_ref()),
+            display_option(to_endpoint.http.path.as_ref())
+        ));
+    }
+
+    push_changed(changes, "endpoint", path, details);
+}
+
+/// Check if two channels are the same, ignoring the version of the types in them.
+fn same_channel(from: Option<&RpChannel>, to: Option<&RpChannel>) -> bool {
+    let from = from.map(|c| (c.is_streaming(), c.ty().clone().localize()));
+    let to = to.map(|c| (c.is_streaming(), c.ty().clone().localize()));
+    from == to
+}
+
+fn display_channel(channel: Option<&RpChannel>) -> String {
+    channel
+        .map(ToString::to_string)
+        .unwrap_or_else(|| "()".to_string())
+}
+
+fn display_option<T: fmt::Display>(value: Option<T>) -> String {
+    value
+        .map(|v| format!("`{}`", v))
+        .unwrap_or_else(|| "(none)".to_string())
+}
+
+fn push_changed(changes: &mut Vec<Change>, item: &'static str, path: String, details: Vec<String>) {
+    if details.is_empty() {
+        return;
+    }
+
+    let mut change = Change::new(ChangeKind::Changed, item, path);
+    change.details = details;
+    changes.push(change);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use trans::testing::file;
+
+    fn changes(from: &str, to: &str) -> Vec<String> {
+        diff(&file(from), &file(to))
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_diff() {
+        let from = r#"
+        type Foo {
+          id: u32;
+          name?: string;
+          old: string;
+        }
+
+        type Gone {
+        }
+
+        enum Color as string {
+          Red as "red";
+          Green as "green";
+        }
+
+        service Svc {
+          #[http(method = "GET", path = "/foo")]
+          get(id: string) -> string;
+
+          remove() -> string;
+        }
+        "#;
+
+        let to = r#"
+        type Foo {
+          id: u64;
+          name: string as "display_name";
+          added?: string;
+        }
+
+        type New {
+        }
+
+        enum Color as string {
+          Red as "rouge";
+          Blue as "blue";
+        }
+
+        service Svc {
+          #[http(method = "POST", path = "/bar")]
+          get(id: u32, extra: string) -> stream string;
+
+          create() -> string;
+        }
+        "#;
+
+        let expected = vec![
+            "added variant `Color::Blue`",
+            "removed variant `Color::Green`",
+            "changed variant `Color::Red`: ordinal changed from \"red\" to \"rouge\"",
+            "added field `Foo::added`",
+            "changed field `Foo::id`: type changed from `u32` to `u64`",
+            "changed field `Foo::name`: renamed from `name` to `display_name`, made required",
+            "removed field `Foo::old`",
+            "removed type `Gone`",
+            "added type `New`",
+            "added endpoint `Svc::create`",
+            "changed endpoint `Svc::get`: argument `id` changed from `string` to `u32`, \
+             argument `extra` added, response changed from `string` to `stream string`, \
+             HTTP method changed from GET to POST, HTTP path changed from `/foo` to `/bar`",
+            "removed endpoint `Svc::remove`",
+        ];
+
+        assert_eq!(expected, changes(from, to));
+    }
+
+    #[test]
+    fn test_diff_kind() {
+        assert_eq!(
+            vec!["changed tuple `Foo`: changed from type to tuple"],
+            changes("type Foo {}", "tuple Foo {}")
+        );
+    }
+
+    #[test]
+    fn test_diff_unchanged() {
+        let spec = "type Foo { id: u32; }";
+        assert!(changes(spec, spec).is_empty());
+    }
+}
@@ -1,16 +1,28 @@
 extern crate reproto_core;
+#[cfg(test)]
+extern crate reproto_trans as trans;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+
+pub mod diff;
+pub mod migration;
+pub mod reserved;
 
 use self::Component::*;
 use self::Violation::*;
-use reproto_core::{ErrorPos, Loc, RpChannel, RpDecl, RpEndpoint, RpField, RpFile, RpName, RpReg,
-                   RpType, RpVariant, Version};
+use reproto_core::{ErrorPos, Loc, RpChannel, RpDecl, RpEndpoint, RpField, RpFile, RpName,
+                   RpPathPart, RpPathSpec, RpReg, RpSubTypeStrategy, RpType, RpVariant, Version};
 use reproto_core::errors::*;
 use std::collections::HashMap;
+use std::fmt;
 
 #[derive(Debug, Clone)]
 pub enum Component {
     Minor,
     Patch,
+    /// Names which may never be reused, regardless of version.
+    Reserved,
 }
 
 impl Component {
@@ -19,6 +31,129 @@ impl Component {
         match *self {
             Minor => "minor change violation",
             Patch => "patch change violation",
+            Reserved => "reserved name violation",
+        }
+    }
+}
+
+/// Which kind of compatibility to check for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// Only what affects serialized values, like names on the wire, tags, ordinals, optionality,
+    /// and types.
+    Wire,
+    /// Only what affects generated code, like the identifiers of fields, variants, and endpoints.
+    Source,
+    /// Both wire and source compatibility.
+    Both,
+}
+
+impl Mode {
+    /// If changes to serialized values should be checked.
+    pub fn is_wire(&self) -> bool {
+        match *self {
+            Mode::Wire | Mode::Both => true,
+            Mode::Source => false,
+        }
+    }
+
+    /// Key to match fields across versions with.
+    fn field_key(&self, field: &RpField) -> String {
+        match *self {
+            Mode::Wire => field.name().to_string(),
+            _ => field.ident().to_string(),
+        }
+    }
+
+    /// If the members of the given declaration are matched by their position instead of by a key.
+    ///
+    /// Tuples are encoded by position, so the names of their members are not visible on the wire.
+    fn is_positional(&self, reg: &RpReg) -> bool {
+        match (*self, reg) {
+            (Mode::Wire, &RpReg::Tuple(_)) => true,
+            _ => false,
+        }
+    }
+
+    /// Key to match variants across versions with.
+    fn variant_key(&self, variant: &RpVariant) -> String {
+        match *self {
+            Mode::Wire => variant.ordinal().to_string(),
+            _ => variant.ident.as_str().to_string(),
+        }
+    }
+
+    /// Key to match endpoints across versions with.
+    fn endpoint_key(&self, endpoint: &RpEndpoint) -> String {
+        match *self {
+            Mode::Wire => endpoint.name().to_string(),
+            _ => endpoint.ident().to_string(),
+        }
+    }
+
+    /// Key to match the arguments of an endpoint across versions with.
+    ///
+    /// On the wire, arguments are identified by where they are sent, like the body of the request
+    /// or a variable in the path.
+    fn argument_key(&self, endpoint: &RpEndpoint, ident: &str) -> String {
+        if *self != Mode::Wire {
+            return ident.to_string();
+        }
+
+        if endpoint.http.body.as_ref().map(|b| b.as_str()) == Some(ident) {
+            return "<body>".to_string();
+        }
+
+        let var = endpoint
+            .http
+            .path
+            .as_ref()
+            .and_then(|path| path.vars().position(|var| var == ident));
+
+        match var {
+            Some(index) => format!("<path #{}>", index),
+            None => ident.to_string(),
+        }
+    }
+}
+
+/// A kind of version bump.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Bump {
+    /// No changes to the API.
+    Patch,
+    /// Backwards compatible additions to the API.
+    Minor,
+    /// Breaking changes to the API.
+    Major,
+}
+
+impl Bump {
+    /// Apply the bump to the given version.
+    ///
+    /// Before 1.0, breaking changes bump the minor version, and any other changes bump the patch
+    /// version.
+    pub fn apply(&self, version: &Version) -> Version {
+        let mut version = version.clone();
+
+        match (*self, version.major) {
+            (Bump::Major, 0) => version.increment_minor(),
+            (_, 0) => version.increment_patch(),
+            (Bump::Major, _) => version.increment_major(),
+            (Bump::Minor, _) => version.increment_minor(),
+            (Bump::Patch, _) => version.increment_patch(),
+        }
+
+        version
+    }
+}
+
+impl fmt::Display for Bump {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Bump::Patch => fmt.write_str("patch"),
+            Bump::Minor => fmt.write_str("minor"),
+            Bump::Major => fmt.write_str("major"),
         }
     }
 }
@@ -29,14 +164,16 @@ pub enum Violation {
     DeclRemoved(Component, ErrorPos),
     /// An entire declaration has been added.
     DeclAdded(Component, ErrorPos),
-    /// Field was removed.
-    RemoveField(Component, ErrorPos),
-    /// Variant was removed.
-    RemoveVariant(Component, ErrorPos),
-    /// Field added.
-    AddField(Component, ErrorPos),
-    /// Variant added.
-    AddVariant(Component, ErrorPos),
+    /// Field was removed, and the declaration it was removed from.
+    RemoveField(Component, ErrorPos, ErrorPos),
+    /// Variant was removed, and the enum it was removed from.
+    RemoveVariant(Component, ErrorPos, ErrorPos),
+    /// Sub-type was removed, and the interface it was removed from.
+    RemoveSubType(Component, ErrorPos, ErrorPos),
+    /// The declaration a field was added to, and the added field.
+    AddField(Component, ErrorPos, ErrorPos),
+    /// The enum a variant was added to, and the added variant.
+    AddVariant(Component, ErrorPos, ErrorPos),
     /// Field type was changed from one to another.
     FieldTypeChange(Component, RpType, ErrorPos, RpType, ErrorPos),
     /// Field name was changed from one to another.
@@ -45,14 +182,14 @@ pub enum Violation {
     VariantOrdinalChange(Component, String, ErrorPos, String, ErrorPos),
     /// Field made required.
     FieldRequiredChange(Component, ErrorPos, ErrorPos),
-    /// Required field added.
-    AddRequiredField(Component, ErrorPos),
+    /// The declaration a required field was added to, and the added field.
+    AddRequiredField(Component, ErrorPos, ErrorPos),
     /// Field modifier changed.
     FieldModifierChange(Component, ErrorPos, ErrorPos),
-    /// Endpoint added.
-    AddEndpoint(Component, ErrorPos),
-    /// Endpoint removed.
-    RemoveEndpoint(Component, ErrorPos),
+    /// The service an endpoint was added to, and the added endpoint.
+    AddEndpoint(Component, ErrorPos, ErrorPos),
+    /// Endpoint was removed, and the service it was removed from.
+    RemoveEndpoint(Component, ErrorPos, ErrorPos),
     /// Endpoint request type changed.
     EndpointRequestChange(
         Component,
@@ -69,6 +206,185 @@ pub enum Violation {
         Option<RpChannel>,
         ErrorPos,
     ),
+    /// Name of a sub-type was changed from one to another.
+    SubTypeNameChange(Component, String, ErrorPos, String, ErrorPos),
+    /// Strategy used to tell sub-types of an interface apart was changed from one to another.
+    SubTypeStrategyChange(
+        Component,
+        RpSubTypeStrategy,
+        ErrorPos,
+        RpSubTypeStrategy,
+        ErrorPos,
+    ),
+    /// Member of a tuple was moved from one position to another.
+    TupleMemberMoved(Component, usize, ErrorPos, usize, ErrorPos),
+    /// Endpoint name was changed from one to another.
+    EndpointNameChange(Component, String, ErrorPos, String, ErrorPos),
+    /// HTTP path of an endpoint changed.
+    EndpointHttpPathChange(Component, Option<String>, ErrorPos, Option<String>, ErrorPos),
+    /// HTTP method of an endpoint changed.
+    EndpointHttpMethodChange(Component, String, ErrorPos, String, ErrorPos),
+    /// Argument used as the HTTP body of an endpoint changed.
+    EndpointHttpBodyChange(Component, Option<String>, ErrorPos, Option<String>, ErrorPos),
+    /// A name removed in an earlier version or reserved, and the member which reuses it.
+    NameReused(Component, String, ErrorPos, ErrorPos),
+}
+
+impl Violation {
+    /// The component that was violated.
+    pub fn component(&self) -> &Component {
+        match *self {
+            DeclRemoved(ref c, ..)
+            | DeclAdded(ref c, ..)
+            | RemoveField(ref c, ..)
+            | RemoveVariant(ref c, ..)
+            | RemoveSubType(ref c, ..)
+            | AddField(ref c, ..)
+            | AddVariant(ref c, ..)
+            | FieldTypeChange(ref c, ..)
+            | FieldNameChange(ref c, ..)
+            | VariantOrdinalChange(ref c, ..)
+            | FieldRequiredChange(ref c, ..)
+            | AddRequiredField(ref c, ..)
+            | FieldModifierChange(ref c, ..)
+            | AddEndpoint(ref c, ..)
+            | RemoveEndpoint(ref c, ..)
+            | EndpointRequestChange(ref c, ..)
+            | EndpointResponseChange(ref c, ..)
+            | SubTypeNameChange(ref c, ..)
+            | SubTypeStrategyChange(ref c, ..)
+            | TupleMemberMoved(ref c, ..)
+            | EndpointNameChange(ref c, ..)
+            | EndpointHttpPathChange(ref c, ..)
+            | EndpointHttpMethodChange(ref c, ..)
+            | EndpointHttpBodyChange(ref c, ..)
+            | NameReused(ref c, ..) => c,
+        }
+    }
+
+    /// Describe what changed, without the component that was violated.
+    pub fn message(&self) -> String {
+        match *self {
+            DeclRemoved(..) => "declaration removed".to_string(),
+            DeclAdded(..) => "declaration added".to_string(),
+            RemoveField(..) => "field removed".to_string(),
+            RemoveVariant(..) => "variant removed".to_string(),
+            RemoveSubType(..) => "sub-type removed".to_string(),
+            AddField(..) => "field added".to_string(),
+            AddVariant(..) => "variant added".to_string(),
+            FieldTypeChange(_, ref from, _, ref to, _) => {
+                format!("field type changed from `{}` to `{}`", from, to)
+            }
+            FieldNameChange(_, ref from, _, ref to, _) => {
+                format!("field name changed from `{}` to `{}`", from, to)
+            }
+            VariantOrdinalChange(_, ref from, _, ref to, _) => {
+                format!("variant ordinal changed from `{}` to `{}`", from, to)
+            }
+            FieldRequiredChange(..) => "field changed to be required".to_string(),
+            AddRequiredField(..) => "required field added".to_string(),
+            FieldModifierChange(..) => "field modifier changed".to_string(),
+            AddEndpoint(..) => "endpoint added".to_string(),
+            RemoveEndpoint(..) => "endpoint removed".to_string(),
+            EndpointRequestChange(_, ref from, _, ref to, _) => format!(
+                "endpoint request changed from `{}` to `{}`",
+                FmtOption(from.as_ref()),
+                FmtOption(to.as_ref())
+            ),
+            EndpointResponseChange(_, ref from, _, ref to, _) => format!(
+                "endpoint response changed from `{}` to `{}`",
+                FmtOption(from.as_ref()),
+                FmtOption(to.as_ref())
+            ),
+            SubTypeNameChange(_, ref from, _, ref to, _) => {
+                format!("sub-type name changed from `{}` to `{}`", from, to)
+            }
+            SubTypeStrategyChange(_, ref from, _, ref to, _) => format!(
+                "sub-type strategy changed from {} to {}",
+                FmtStrategy(from),
+                FmtStrategy(to)
+            ),
+            TupleMemberMoved(_, from, _, to, _) => {
+                format!("tuple member moved from #{} to #{}", from, to)
+            }
+            EndpointNameChange(_, ref from, _, ref to, _) => {
+                format!("endpoint name changed from `{}` to `{}`", from, to)
+            }
+            EndpointHttpPathChange(_, ref from, _, ref to, _) => format!(
+                "HTTP path changed from `{}` to `{}`",
+                FmtOption(from.as_ref()),
+                FmtOption(to.as_ref())
+            ),
+            EndpointHttpMethodChange(_, ref from, _, ref to, _) => {
+                format!("HTTP method changed from `{}` to `{}`", from, to)
+            }
+            EndpointHttpBodyChange(_, ref from, _, ref to, _) => format!(
+                "HTTP body changed from `{}` to `{}`",
+                FmtOption(from.as_ref()),
+                FmtOption(to.as_ref())
+            ),
+            NameReused(_, ref name, _, _) => {
+                format!("`{}` was removed or reserved earlier, and can't be reused", name)
+            }
+        }
+    }
+
+    /// Positions of the violation in the previous and in the new version, where present.
+    pub fn positions(&self) -> (Option<&ErrorPos>, Option<&ErrorPos>) {
+        match *self {
+            DeclRemoved(_, ref from) => (Some(from), None),
+            DeclAdded(_, ref to) => (None, Some(to)),
+            RemoveField(_, ref from, ref to)
+            | RemoveVariant(_, ref from, ref to)
+            | RemoveSubType(_, ref from, ref to)
+            | AddField(_, ref from, ref to)
+            | AddVariant(_, ref from, ref to)
+            | FieldRequiredChange(_, ref from, ref to)
+            | AddRequiredField(_, ref from, ref to)
+            | FieldModifierChange(_, ref from, ref to)
+            | AddEndpoint(_, ref from, ref to)
+            | RemoveEndpoint(_, ref from, ref to)
+            | NameReused(_, _, ref from, ref to) => (Some(from), Some(to)),
+            FieldTypeChange(_, _, ref from, _, ref to)
+            | FieldNameChange(_, _, ref from, _, ref to)
+            | VariantOrdinalChange(_, _, ref from, _, ref to)
+            | EndpointRequestChange(_, _, ref from, _, ref to)
+            | EndpointResponseChange(_, _, ref from, _, ref to)
+            | SubTypeNameChange(_, _, ref from, _, ref to)
+            | SubTypeStrategyChange(_, _, ref from, _, ref to)
+            | TupleMemberMoved(_, _, ref from, _, ref to)
+            | EndpointNameChange(_, _, ref from, _, ref to)
+            | EndpointHttpPathChange(_, _, ref from, _, ref to)
+            | EndpointHttpMethodChange(_, _, ref from, _, ref to)
+            | EndpointHttpBodyChange(_, _, ref from, _, ref to) => (Some(from), Some(to)),
+        }
+    }
+}
+
+/// Helper struct to display optional values.
+struct FmtOption<'a, T: 'a>(Option<&'a T>);
+
+impl<'a, T: 'a> fmt::Display for FmtOption<'a, T>
+where
+    T: fmt::Display,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            None => fmt.write_str("*empty*"),
+            Some(value) => write!(fmt, "{}", value),
+        }
+    }
+}
+
+/// Helper struct to display sub-type strategies.
+struct FmtStrategy<'a>(&'a RpSubTypeStrategy);
+
+impl<'a> fmt::Display for FmtStrategy<'a> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self.0 {
+            RpSubTypeStrategy::Tagged { ref tag } => write!(fmt, "tagged by `{}`", tag),
+        }
+    }
 }
 
 fn fields(reg: &RpReg) -> Vec<&Loc<RpField>> {
@@ -92,6 +408,15 @@ fn enum_variants(reg: &RpReg) -> Vec<&Loc<RpVariant>> {
     }
 }
 
+fn endpoints(reg: &RpReg) -> Vec<&Loc<RpEndpoint>> {
+    use self::RpReg::*;
+
+    match *reg {
+        Service(ref target) => target.endpoints.values().collect(),
+        _ => vec![],
+    }
+}
+
 fn endpoints_to_map(reg: &RpReg) -> HashMap<&str, &Loc<RpEndpoint>> {
     use self::RpReg::*;
 
@@ -105,6 +430,37 @@ fn endpoints_to_map(reg: &RpReg) -> HashMap<&str, &Loc<RpEndpoint>> {
     }
 }
 
+/// Collect items into a map, using the given key.
+fn keyed<'a, T: 'a, I, F>(items: I, key: F) -> HashMap<String, &'a T>
+where
+    I: IntoIterator<Item = &'a T>,
+    F: Fn(&T) -> String,
+{
+    items.into_iter().map(|item| (key(item), item)).collect()
+}
+
+/// Collect the arguments of an endpoint, keyed by what identifies them in the given mode.
+fn keyed_arguments(mode: Mode, endpoint: &RpEndpoint) -> HashMap<String, &Loc<RpChannel>> {
+    endpoint
+        .arguments
+        .iter()
+        .map(|(ident, &(_, ref channel))| (mode.argument_key(endpoint, ident), channel))
+        .collect()
+}
+
+/// Collect the fields of a declaration, keyed by what identifies them in the given mode.
+fn keyed_fields(mode: Mode, reg: &RpReg) -> HashMap<String, &Loc<RpField>> {
+    if mode.is_positional(reg) {
+        return fields(reg)
+            .into_iter()
+            .enumerate()
+            .map(|(index, field)| (index.to_string(), field))
+            .collect();
+    }
+
+    keyed(fields(reg), |f| mode.field_key(f))
+}
+
 fn decls_to_map<'a, I: 'a>(decls: I) -> HashMap<RpName, RpReg>
 where
     I: IntoIterator<Item = &'a RpDecl>,
@@ -125,6 +481,34 @@ where
     storage
 }
 
+/// Collect the declarations of a file, keyed by what identifies them in the given mode.
+///
+/// On the wire, sub-types are identified by their name within the interface.
+fn keyed_decls(mode: Mode, decls: &[RpDecl]) -> HashMap<RpName, RpReg> {
+    let storage = decls_to_map(decls);
+
+    if mode != Mode::Wire {
+        return storage;
+    }
+
+    storage
+        .into_iter()
+        .map(|(name, reg)| {
+            let name = match reg {
+                RpReg::SubType(ref interface, ref sub_type) => {
+                    let mut name = interface.name.clone().localize();
+                    // quoted, so that it is never mistaken for a declaration in the interface.
+                    name.parts.push(format!("{:?}", sub_type.name()));
+                    name
+                }
+                _ => name,
+            };
+
+            (name, reg)
+        })
+        .collect()
+}
+
 fn variants_to_map<'a, I: 'a>(variants: I) -> HashMap<RpName, &'a Loc<RpVariant>>
 where
     I: IntoIterator<Item = &'a Loc<RpVariant>>,
@@ -151,49 +535,39 @@ where
     storage
 }
 
-/// Perform checks on an endpoint channel.
-fn check_endpoint_channel<F, E>(
+/// Perform checks on a channel, which might be absent on either side.
+///
+/// If a channel is absent, the position of the endpoint is used instead.
+fn check_channel<E>(
     component: Component,
     violations: &mut Vec<Violation>,
-    from_endpoint: &Loc<RpEndpoint>,
-    to_endpoint: &Loc<RpEndpoint>,
-    accessor: F,
+    from: (Option<&Loc<RpChannel>>, &Loc<RpEndpoint>),
+    to: (Option<&Loc<RpChannel>>, &Loc<RpEndpoint>),
     error: E,
 ) -> Result<()>
 where
-    F: Fn(&RpEndpoint) -> &Option<Loc<RpChannel>>,
     E: Fn(Component, Option<RpChannel>, ErrorPos, Option<RpChannel>, ErrorPos) -> Violation,
 {
-    let from_ty = accessor(from_endpoint)
-        .as_ref()
-        .map(|r| (r.is_streaming(), r.ty().clone().localize()));
+    let (from_channel, from_endpoint) = from;
+    let (to_channel, to_endpoint) = to;
 
-    let to_ty = accessor(to_endpoint)
-        .as_ref()
-        .map(|r| (r.is_streaming(), r.ty().clone().localize()));
+    let from_ty = from_channel.map(|r| (r.is_streaming(), r.ty().clone().localize()));
+    let to_ty = to_channel.map(|r| (r.is_streaming(), r.ty().clone().localize()));
 
     if from_ty != to_ty {
-        let from_pos = accessor(from_endpoint)
-            .as_ref()
+        let from_pos = from_channel
             .map(|r| Loc::pos(r))
             .unwrap_or(Loc::pos(from_endpoint));
 
-        let to_pos = accessor(to_endpoint)
-            .as_ref()
+        let to_pos = to_channel
             .map(|r| Loc::pos(r))
             .unwrap_or(Loc::pos(to_endpoint));
 
         violations.push(error(
             component,
-            accessor(from_endpoint)
-                .as_ref()
-                .map(Loc::value)
-                .map(Clone::clone),
+            from_channel.map(Loc::value).map(Clone::clone),
             from_pos.into(),
-            accessor(to_endpoint)
-                .as_ref()
-                .map(Loc::value)
-                .map(Clone::clone),
+            to_channel.map(Loc::value).map(Clone::clone),
             to_pos.into(),
         ));
     }
@@ -202,40 +576,242 @@ where
 }
 
 fn check_endpoint_type(
+    mode: Mode,
     component: Component,
     violations: &mut Vec<Violation>,
     from_endpoint: &Loc<RpEndpoint>,
     to_endpoint: &Loc<RpEndpoint>,
 ) -> Result<()> {
-    // TODO: check arguments.
-    /*check_endpoint_channel(
-        component.clone(),
-        violations,
-        from_endpoint,
-        to_endpoint,
-        |e| &e.request,
-        EndpointRequestChange,
-    )?;*/
+    let from_arguments = keyed_arguments(mode, from_endpoint);
+    let to_arguments = keyed_arguments(mode, to_endpoint);
+
+    for (key, from_channel) in &from_arguments {
+        let to_channel = to_arguments.get(key).map(|c| *c);
+
+        check_channel(
+            component.clone(),
+            violations,
+            (Some(*from_channel), from_endpoint),
+            (to_channel, to_endpoint),
+            EndpointRequestChange,
+        )?;
+    }
+
+    for (key, to_channel) in &to_arguments {
+        if from_arguments.contains_key(key) {
+            continue;
+        }
+
+        check_channel(
+            component.clone(),
+            violations,
+            (None, from_endpoint),
+            (Some(*to_channel), to_endpoint),
+            EndpointRequestChange,
+        )?;
+    }
 
-    check_endpoint_channel(
+    check_channel(
         component.clone(),
         violations,
-        from_endpoint,
-        to_endpoint,
-        |e| &e.response,
+        (from_endpoint.response.as_ref(), from_endpoint),
+        (to_endpoint.response.as_ref(), to_endpoint),
         EndpointResponseChange,
     )?;
 
     Ok(())
 }
 
+fn check_endpoint_http(
+    mode: Mode,
+    component: Component,
+    violations: &mut Vec<Violation>,
+    from_endpoint: &Loc<RpEndpoint>,
+    to_endpoint: &Loc<RpEndpoint>,
+) -> Result<()> {
+    let (from, to) = (&from_endpoint.http, &to_endpoint.http);
+
+    // names of variables are not visible in the request, so they are permitted to change.
+    if from.path.as_ref().map(path_shape) != to.path.as_ref().map(path_shape) {
+        violations.push(EndpointHttpPathChange(
+            component.clone(),
+            from.path.as_ref().map(ToString::to_string),
+            Loc::pos(from_endpoint).into(),
+            to.path.as_ref().map(ToString::to_string),
+            Loc::pos(to_endpoint).into(),
+        ));
+    }
+
+    // endpoints without an explicit method use GET.
+    let from_method = from.method.as_ref().map(|m| m.as_str()).unwrap_or("GET");
+    let to_method = to.method.as_ref().map(|m| m.as_str()).unwrap_or("GET");
+
+    if from_method != to_method {
+        violations.push(EndpointHttpMethodChange(
+            component.clone(),
+            from_method.to_string(),
+            Loc::pos(from_endpoint).into(),
+            to_method.to_string(),
+            Loc::pos(to_endpoint).into(),
+        ));
+    }
+
+    // on the wire, the argument used as the body is only visible through its type.
+    let body_changed = match mode {
+        Mode::Wire => from.body.is_some() != to.body.is_some(),
+        _ => from.body != to.body,
+    };
+
+    if body_changed {
+        violations.push(EndpointHttpBodyChange(
+            component.clone(),
+            from.body.clone(),
+            Loc::pos(from_endpoint).into(),
+            to.body.clone(),
+            Loc::pos(to_endpoint).into(),
+        ));
+    }
+
+    return Ok(());
+
+    /// The segments of the path, with variables left anonymous.
+    fn path_shape(spec: &RpPathSpec) -> Vec<Vec<Option<&str>>> {
+        spec.steps
+            .iter()
+            .map(|step| {
+                step.parts
+                    .iter()
+                    .map(|part| match *part {
+                        RpPathPart::Variable(_) => None,
+                        RpPathPart::Segment(ref segment) => Some(segment.as_str()),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+fn common_check_endpoint(
+    mode: Mode,
+    component: Component,
+    violations: &mut Vec<Violation>,
+    from_endpoint: &Loc<RpEndpoint>,
+    to_endpoint: &Loc<RpEndpoint>,
+) -> Result<()> {
+    check_endpoint_type(mode, component.clone(), violations, from_endpoint, to_endpoint)?;
+
+    if !mode.is_wire() {
+        return Ok(());
+    }
+
+    check_endpoint_http(mode, component.clone(), violations, from_endpoint, to_endpoint)?;
+
+    if from_endpoint.name() != to_endpoint.name() {
+        violations.push(EndpointNameChange(
+            component.clone(),
+            from_endpoint.name().to_string(),
+            Loc::pos(from_endpoint).into(),
+            to_endpoint.name().to_string(),
+            Loc::pos(to_endpoint).into(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Checks for the parts of a declaration which are not fields, variants, or endpoints.
+fn common_check_reg(
+    mode: Mode,
+    component: Component,
+    violations: &mut Vec<Violation>,
+    from_reg: &RpReg,
+    to_reg: &RpReg,
+) -> Result<()> {
+    use self::RpReg::*;
+
+    match (from_reg, to_reg) {
+        (&SubType(_, ref from), &SubType(_, ref to)) => {
+            if mode.is_wire() && from.name() != to.name() {
+                violations.push(SubTypeNameChange(
+                    component.clone(),
+                    from.name().to_string(),
+                    Loc::pos(from).into(),
+                    to.name().to_string(),
+                    Loc::pos(to).into(),
+                ));
+            }
+        }
+        (&Interface(ref from), &Interface(ref to)) => {
+            if mode.is_wire() && from.sub_type_strategy != to.sub_type_strategy {
+                violations.push(SubTypeStrategyChange(
+                    component.clone(),
+                    from.sub_type_strategy.clone(),
+                    Loc::pos(from).into(),
+                    to.sub_type_strategy.clone(),
+                    Loc::pos(to).into(),
+                ));
+            }
+        }
+        // tuples are encoded by position, so members are not permitted to move.
+        //
+        // on the wire, members are already matched by their position.
+        (&Tuple(ref from), &Tuple(ref to)) if !mode.is_positional(from_reg) => {
+            for (from_index, from_field) in from.fields.iter().enumerate() {
+                let key = mode.field_key(from_field);
+
+                let to_index = to.fields
+                    .iter()
+                    .position(|f| mode.field_key(f) == key);
+
+                if let Some(to_index) = to_index {
+                    if from_index != to_index {
+                        violations.push(TupleMemberMoved(
+                            component.clone(),
+                            from_index,
+                            Loc::pos(from_field).into(),
+                            to_index,
+                            Loc::pos(&to.fields[to_index]).into(),
+                        ));
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Report a declaration which is missing in the new version.
+fn removed_reg(
+    component: Component,
+    violations: &mut Vec<Violation>,
+    from_reg: &RpReg,
+    to_storage: &HashMap<RpName, RpReg>,
+) {
+    // sub-types are reported together with the interface, unless it is also removed.
+    if let RpReg::SubType(ref interface, _) = *from_reg {
+        if let Some(to_reg) = to_storage.get(&interface.name.clone().localize()) {
+            violations.push(RemoveSubType(
+                component,
+                from_reg.pos().into(),
+                to_reg.pos().into(),
+            ));
+            return;
+        }
+    }
+
+    violations.push(DeclRemoved(component, from_reg.pos().into()));
+}
+
 fn common_check_variant(
+    mode: Mode,
     component: Component,
     violations: &mut Vec<Violation>,
     from_variant: &Loc<RpVariant>,
     to_variant: &Loc<RpVariant>,
 ) -> Result<()> {
-    if from_variant.ordinal() != to_variant.ordinal() {
+    if mode.is_wire() && from_variant.ordinal() != to_variant.ordinal() {
         violations.push(VariantOrdinalChange(
             component.clone(),
             from_variant.ordinal().to_string(),
@@ -248,9 +824,15 @@ fn common_check_variant(
     Ok(())
 }
 
+/// Checks for fields which are present in both versions.
+///
+/// `positional` indicates that the fields are matched by position, in which case their names
+/// are not visible on the wire.
 fn common_check_field(
+    mode: Mode,
     component: Component,
     violations: &mut Vec<Violation>,
+    positional: bool,
     from_field: &Loc<RpField>,
     to_field: &Loc<RpField>,
 ) -> Result<()> {
@@ -264,8 +846,8 @@ fn common_check_field(
         ));
     }
 
-    // not permitted to rename fields.
-    if to_field.name() != from_field.name() {
+    // not permitted to rename fields on the wire.
+    if mode.is_wire() && !positional && to_field.name() != from_field.name() {
         violations.push(FieldNameChange(
             component.clone(),
             from_field.name().to_string(),
@@ -279,66 +861,90 @@ fn common_check_field(
 }
 
 /// Performs checks for minor version violations.
-fn check_minor(from: &RpFile, to: &RpFile) -> Result<Vec<Violation>> {
+fn check_minor(mode: Mode, from: &RpFile, to: &RpFile) -> Result<Vec<Violation>> {
     let mut violations = Vec::new();
 
-    let from_storage = decls_to_map(&from.decls);
-    let mut to_storage = decls_to_map(&to.decls);
+    let from_storage = keyed_decls(mode, &from.decls);
+    let all_to_storage = keyed_decls(mode, &to.decls);
+    let mut to_storage = keyed_decls(mode, &to.decls);
 
     for (name, from_reg) in from_storage {
         if let Some(to_reg) = to_storage.remove(&name) {
-            let from_fields = fields_to_map(fields(&from_reg));
-            let mut to_fields = fields_to_map(fields(&to_reg));
+            let (from_pos, to_pos) = (from_reg.pos(), to_reg.pos());
+
+            let positional = mode.is_positional(&from_reg);
+            let from_fields = keyed_fields(mode, &from_reg);
+            let mut to_fields = keyed_fields(mode, &to_reg);
 
             for (name, from_field) in from_fields.into_iter() {
                 if let Some(to_field) = to_fields.remove(&name) {
-                    check_field(&mut violations, from_field, to_field)?;
+                    check_field(mode, &mut violations, positional, from_field, to_field)?;
                 } else {
-                    violations.push(RemoveField(Minor, Loc::pos(from_field).into()));
+                    violations.push(RemoveField(
+                        Minor,
+                        Loc::pos(from_field).into(),
+                        to_pos.into(),
+                    ));
                 }
             }
 
             // check that added fields are not required.
             for (_, to_field) in to_fields.into_iter() {
                 if to_field.is_required() {
-                    violations.push(AddRequiredField(Minor, Loc::pos(to_field).into()));
+                    violations.push(AddRequiredField(
+                        Minor,
+                        from_pos.into(),
+                        Loc::pos(to_field).into(),
+                    ));
                 }
             }
 
-            let from_variants = variants_to_map(enum_variants(&from_reg));
-            let mut to_variants = variants_to_map(enum_variants(&to_reg));
+            let from_variants = keyed(enum_variants(&from_reg), |v| mode.variant_key(v));
+            let mut to_variants = keyed(enum_variants(&to_reg), |v| mode.variant_key(v));
 
             for (name, from_variant) in from_variants.into_iter() {
                 if let Some(to_variant) = to_variants.remove(&name) {
-                    check_variant(&mut violations, from_variant, to_variant)?;
+                    check_variant(mode, &mut violations, from_variant, to_variant)?;
                 } else {
-                    violations.push(RemoveVariant(Minor, Loc::pos(from_variant).into()));
+                    violations.push(RemoveVariant(
+                        Minor,
+                        Loc::pos(from_variant).into(),
+                        to_pos.into(),
+                    ));
                 }
             }
 
-            let from_endpoints = endpoints_to_map(&from_reg);
-            let mut to_endpoints = endpoints_to_map(&to_reg);
+            let from_endpoints = keyed(endpoints(&from_reg), |e| mode.endpoint_key(e));
+            let mut to_endpoints = keyed(endpoints(&to_reg), |e| mode.endpoint_key(e));
 
             for (name, from_endpoint) in from_endpoints.into_iter() {
                 if let Some(to_endpoint) = to_endpoints.remove(&name) {
-                    check_endpoint(&mut violations, from_endpoint, to_endpoint)?;
+                    check_endpoint(mode, &mut violations, from_endpoint, to_endpoint)?;
                 } else {
-                    violations.push(RemoveEndpoint(Minor, Loc::pos(from_endpoint).into()));
+                    violations.push(RemoveEndpoint(
+                        Minor,
+                        Loc::pos(from_endpoint).into(),
+                        to_pos.into(),
+                    ));
                 }
             }
+
+            common_check_reg(mode, Minor, &mut violations, &from_reg, &to_reg)?;
         } else {
-            violations.push(DeclRemoved(Minor, from_reg.pos().into()));
+            removed_reg(Minor, &mut violations, &from_reg, &all_to_storage);
         }
     }
 
     return Ok(violations);
 
     fn check_field(
+        mode: Mode,
         violations: &mut Vec<Violation>,
+        positional: bool,
         from_field: &Loc<RpField>,
         to_field: &Loc<RpField>,
     ) -> Result<()> {
-        common_check_field(Minor, violations, from_field, to_field)?;
+        common_check_field(mode, Minor, violations, positional, from_field, to_field)?;
 
         // Minor patch may make fields optional, but not required.
         if from_field.is_optional() && to_field.is_required() {
@@ -353,81 +959,109 @@ fn check_minor(from: &RpFile, to: &RpFile) -> Result<Vec<Violation>> {
     }
 
     fn check_variant(
+        mode: Mode,
         violations: &mut Vec<Violation>,
         from_variant: &Loc<RpVariant>,
         to_variant: &Loc<RpVariant>,
     ) -> Result<()> {
-        common_check_variant(Minor, violations, from_variant, to_variant)?;
+        common_check_variant(mode, Minor, violations, from_variant, to_variant)?;
         Ok(())
     }
 
     fn check_endpoint(
+        mode: Mode,
         violations: &mut Vec<Violation>,
         from_endpoint: &Loc<RpEndpoint>,
         to_endpoint: &Loc<RpEndpoint>,
     ) -> Result<()> {
-        check_endpoint_type(Minor, violations, from_endpoint, to_endpoint)?;
+        common_check_endpoint(mode, Minor, violations, from_endpoint, to_endpoint)?;
         Ok(())
     }
 }
 
-fn check_patch(from: &RpFile, to: &RpFile) -> Result<Vec<Violation>> {
+fn check_patch(mode: Mode, from: &RpFile, to: &RpFile) -> Result<Vec<Violation>> {
     let mut violations = Vec::new();
 
-    let from_storage = decls_to_map(&from.decls);
-    let mut to_storage = decls_to_map(&to.decls);
+    let from_storage = keyed_decls(mode, &from.decls);
+    let all_to_storage = keyed_decls(mode, &to.decls);
+    let mut to_storage = keyed_decls(mode, &to.decls);
 
     for (name, from_reg) in from_storage {
         if let Some(to_reg) = to_storage.remove(&name) {
-            let from_fields = fields_to_map(fields(&from_reg));
-            let mut to_fields = fields_to_map(fields(&to_reg));
+            let (from_pos, to_pos) = (from_reg.pos(), to_reg.pos());
+
+            let positional = mode.is_positional(&from_reg);
+            let from_fields = keyed_fields(mode, &from_reg);
+            let mut to_fields = keyed_fields(mode, &to_reg);
 
             for (name, from_field) in from_fields.into_iter() {
                 if let Some(to_field) = to_fields.remove(&name) {
-                    check_field(&mut violations, from_field, to_field)?;
+                    check_field(mode, &mut violations, positional, from_field, to_field)?;
                 } else {
-                    violations.push(RemoveField(Patch, Loc::pos(from_field).into()));
+                    violations.push(RemoveField(
+                        Patch,
+                        Loc::pos(from_field).into(),
+                        to_pos.into(),
+                    ));
                 }
             }
 
             // added fields are not permitted
             for (_, to_field) in to_fields.into_iter() {
-                violations.push(AddField(Patch, Loc::pos(to_field).into()));
+                violations.push(AddField(Patch, from_pos.into(), Loc::pos(to_field).into()));
             }
 
-            let from_variants = variants_to_map(enum_variants(&from_reg));
-            let mut to_variants = variants_to_map(enum_variants(&to_reg));
+            let from_variants = keyed(enum_variants(&from_reg), |v| mode.variant_key(v));
+            let mut to_variants = keyed(enum_variants(&to_reg), |v| mode.variant_key(v));
 
             for (name, from_variant) in from_variants.into_iter() {
                 if let Some(to_variant) = to_variants.remove(&name) {
-                    check_variant(&mut violations, from_variant, to_variant)?;
+                    check_variant(mode, &mut violations, from_variant, to_variant)?;
                 } else {
-                    violations.push(RemoveVariant(Patch, Loc::pos(from_variant).into()));
+                    violations.push(RemoveVariant(
+                        Patch,
+                        Loc::pos(from_variant).into(),
+                        to_pos.into(),
+                    ));
                 }
             }
 
             // added variants are not permitted
             for (_, to_variant) in to_variants.into_iter() {
-                violations.push(AddVariant(Patch, Loc::pos(to_variant).into()));
+                violations.push(AddVariant(
+                    Patch,
+                    from_pos.into(),
+                    Loc::pos(to_variant).into(),
+                ));
             }
 
-            let from_endpoints = endpoints_to_map(&from_reg);
-            let mut to_endpoints = endpoints_to_map(&to_reg);
+            let from_endpoints = keyed(endpoints(&from_reg), |e| mode.endpoint_key(e));
+            let mut to_endpoints = keyed(endpoints(&to_reg), |e| mode.endpoint_key(e));
 
             for (name, from_endpoint) in from_endpoints.into_iter() {
                 if let Some(to_endpoint) = to_endpoints.remove(&name) {
-                    check_endpoint(&mut violations, from_endpoint, to_endpoint)?;
+                    check_endpoint(mode, &mut violations, from_endpoint, to_endpoint)?;
                 } else {
-                    violations.push(RemoveEndpoint(Patch, Loc::pos(from_endpoint).into()));
+                    violations.push(RemoveEndpoint(
+                        Patch,
+                        Loc::pos(from_endpoint).into(),
+                        to_pos.into(),
+                    ));
                 }
             }
 
             // added endpoints are not permitted
             for (_, to_endpoint) in to_endpoints.into_iter() {
-                violations.push(AddEndpoint(Patch, Loc::pos(to_endpoint).into()));
+                violations.push(AddEndpoint(
+                    Patch,
+                    from_pos.into(),
+                    Loc::pos(to_endpoint).into(),
+                ));
             }
+
+            common_check_reg(mode, Patch, &mut violations, &from_reg, &to_reg)?;
         } else {
-            violations.push(DeclRemoved(Patch, from_reg.pos().into()));
+            removed_reg(Patch, &mut violations, &from_reg, &all_to_storage);
         }
     }
 
@@ -438,11 +1072,13 @@ fn check_patch(from: &RpFile, to: &RpFile) -> Result<Vec<Violation>> {
     return Ok(violations);
 
     fn check_field(
+        mode: Mode,
         violations: &mut Vec<Violation>,
+        positional: bool,
         from_field: &Loc<RpField>,
         to_field: &Loc<RpField>,
     ) -> Result<()> {
-        common_check_field(Patch, violations, from_field, to_field)?;
+        common_check_field(mode, Patch, violations, positional, from_field, to_field)?;
 
         if to_field.modifier != from_field.modifier {
             violations.push(FieldModifierChange(
@@ -456,37 +1092,487 @@ fn check_patch(from: &RpFile, to: &RpFile) -> Result<Vec<Violation>> {
     }
 
     fn check_variant(
+        mode: Mode,
         violations: &mut Vec<Violation>,
         from_variant: &Loc<RpVariant>,
         to_variant: &Loc<RpVariant>,
     ) -> Result<()> {
-        common_check_variant(Patch, violations, from_variant, to_variant)?;
+        common_check_variant(mode, Patch, violations, from_variant, to_variant)?;
         Ok(())
     }
 
     fn check_endpoint(
+        mode: Mode,
         violations: &mut Vec<Violation>,
         from_endpoint: &Loc<RpEndpoint>,
         to_endpoint: &Loc<RpEndpoint>,
     ) -> Result<()> {
-        check_endpoint_type(Patch, violations, from_endpoint, to_endpoint)?;
+        common_check_endpoint(mode, Patch, violations, from_endpoint, to_endpoint)?;
         Ok(())
     }
 }
 
-pub fn check(from: (&Version, &RpFile), to: (&Version, &RpFile)) -> Result<Vec<Violation>> {
+pub fn check(
+    mode: Mode,
+    from: (&Version, &RpFile),
+    to: (&Version, &RpFile),
+) -> Result<Vec<Violation>> {
     let (from_version, from_file) = from;
     let (to_version, to_file) = to;
 
+    // before 1.0, a minor bump is breaking, and a patch bump may add to the API.
+    if from_version.major == 0 && to_version.major == 0 {
+        if from_version.minor == to_version.minor && from_version.patch < to_version.patch {
+            return check_minor(mode, from_file, to_file);
+        }
+
+        return Ok(vec![]);
+    }
+
     if from_version.major == to_version.major {
         if from_version.minor < to_version.minor {
-            return check_minor(from_file, to_file);
+            return check_minor(mode, from_file, to_file);
         }
 
         if from_version.patch < to_version.patch {
-            return check_patch(from_file, to_file);
+            return check_patch(mode, from_file, to_file);
         }
     }
 
     Ok(vec![])
 }
+
+/// Find the smallest version bump which permits every change between two versions.
+pub fn suggest(mode: Mode, from: &RpFile, to: &RpFile) -> Result<Bump> {
+    if check_patch(mode, from, to)?.is_empty() {
+        return Ok(Bump::Patch);
+    }
+
+    if check_minor(mode, from, to)?.is_empty() {
+        return Ok(Bump::Minor);
+    }
+
+    Ok(Bump::Major)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use trans::testing::file;
+
+    /// Check the given specifications, released as `1.0.0` and after the given bump, and describe
+    /// every violation.
+    fn violations(
+        mode: Mode,
+        bump: Bump,
+        from: &str,
+        to: &str,
+    ) -> Vec<(String, Option<String>, Option<String>)> {
+        violations_since(Version::new(1, 0, 0), mode, bump, from, to)
+    }
+
+    /// Check the given specifications, released as `from_version` and after the given bump, and
+    /// describe every violation.
+    ///
+    /// Each violation is described by its message, and the identifiers at its positions in the
+    /// previous and in the new version.
+    fn violations_since(
+        from_version: Version,
+        mode: Mode,
+        bump: Bump,
+        from: &str,
+        to: &str,
+    ) -> Vec<(String, Option<String>, Option<String>)> {
+        let to_version = bump.apply(&from_version);
+
+        let violations = check(
+            mode,
+            (&from_version, &file(from)),
+            (&to_version, &file(to)),
+        ).expect("to check");
+
+        let mut out = violations
+            .iter()
+            .map(|v| {
+                let (from_pos, to_pos) = v.positions();
+                (v.message(), at(from, from_pos), at(to, to_pos))
+            })
+            .collect::<Vec<_>>();
+
+        out.sort();
+        return out;
+
+        /// The identifier at the given position.
+        fn at(spec: &str, pos: Option<&ErrorPos>) -> Option<String> {
+            pos.and_then(|pos| {
+                spec[pos.start..]
+                    .split(|c: char| !c.is_alphanumeric() && c != '_')
+                    .next()
+                    .map(ToString::to_string)
+            })
+        }
+    }
+
+    fn violation(message: &str, from: &str, to: &str) -> (String, Option<String>, Option<String>) {
+        (
+            message.to_string(),
+            Some(from.to_string()),
+            Some(to.to_string()),
+        )
+    }
+
+    #[test]
+    fn test_bump_apply() {
+        let version = Version::new(1, 2, 3);
+        assert_eq!(Version::new(2, 0, 0), Bump::Major.apply(&version));
+        assert_eq!(Version::new(1, 3, 0), Bump::Minor.apply(&version));
+        assert_eq!(Version::new(1, 2, 4), Bump::Patch.apply(&version));
+
+        // before 1.0, breaking changes bump the minor version.
+        let version = Version::new(0, 2, 3);
+        assert_eq!(Version::new(0, 3, 0), Bump::Major.apply(&version));
+        assert_eq!(Version::new(0, 2, 4), Bump::Minor.apply(&version));
+        assert_eq!(Version::new(0, 2, 4), Bump::Patch.apply(&version));
+    }
+
+    #[test]
+    fn test_suggest() {
+        let bump = |from: &str, to: &str| {
+            suggest(Mode::Both, &file(from), &file(to)).expect("to suggest")
+        };
+
+        let from = "type Foo { a: string; }";
+
+        assert_eq!(Bump::Patch, bump(from, "type Foo { a: string; }"));
+        assert_eq!(Bump::Minor, bump(from, "type Foo { a: string; b?: string; }"));
+        assert_eq!(Bump::Major, bump(from, "type Foo { a: string; b: string; }"));
+        assert_eq!(Bump::Major, bump(from, "type Foo {}"));
+    }
+
+    #[test]
+    fn test_check_pre_1_0() {
+        let version = Version::new(0, 1, 0);
+        let from = "type Foo { a: string; b: string; }";
+        let to = "type Foo { a: string; c?: string; }";
+
+        // a minor bump before 1.0 is a patch release, which may only add to the API.
+        assert_eq!(
+            vec![violation("field removed", "b", "type")],
+            violations_since(version.clone(), Mode::Both, Bump::Minor, from, to)
+        );
+
+        assert!(
+            violations_since(
+                version.clone(),
+                Mode::Both,
+                Bump::Minor,
+                from,
+                "type Foo { a: string; b: string; c?: string; }",
+            ).is_empty()
+        );
+
+        // a major bump before 1.0 is a minor release, which may break the API.
+        assert!(violations_since(version, Mode::Both, Bump::Major, from, to).is_empty());
+    }
+
+    #[test]
+    fn test_remove_sub_type() {
+        let from = "interface Shape { Circle {} Square {} }";
+        let to = "interface Shape { Circle {} }";
+
+        assert_eq!(
+            vec![violation("sub-type removed", "Square", "interface")],
+            violations(Mode::Both, Bump::Minor, from, to)
+        );
+    }
+
+    #[test]
+    fn test_sub_type_name_change() {
+        let from = r#"interface Shape { Circle as "circle" {} }"#;
+        let to = r#"interface Shape { Circle as "round" {} }"#;
+
+        assert_eq!(
+            vec![
+                violation(
+                    "sub-type name changed from `circle` to `round`",
+                    "Circle",
+                    "Circle",
+                ),
+            ],
+            violations(Mode::Both, Bump::Minor, from, to)
+        );
+
+        // on the wire, sub-types are matched by their name.
+        assert_eq!(
+            vec![violation("sub-type removed", "Circle", "interface")],
+            violations(Mode::Wire, Bump::Minor, from, to)
+        );
+
+        // the name is only visible on the wire.
+        assert!(violations(Mode::Source, Bump::Minor, from, to).is_empty());
+    }
+
+    #[test]
+    fn test_sub_type_ident_change() {
+        let from = r#"interface Shape { Circle as "circle" {} }"#;
+        let to = r#"interface Shape { Round as "circle" {} }"#;
+
+        assert!(violations(Mode::Wire, Bump::Minor, from, to).is_empty());
+
+        assert_eq!(
+            vec![violation("sub-type removed", "Circle", "interface")],
+            violations(Mode::Source, Bump::Minor, from, to)
+        );
+    }
+
+    #[test]
+    fn test_endpoint_argument_ident_change() {
+        let from = r#"
+        service Foo {
+          #[http(path = "/foo/{id}", method = "POST", body = "foo")]
+          set(id: u32, foo: string);
+        }
+        "#;
+
+        let to = r#"
+        service Foo {
+          #[http(path = "/foo/{key}", method = "POST", body = "value")]
+          set(key: u32, value: string);
+        }
+        "#;
+
+        // arguments are matched by where they are sent.
+        assert!(violations(Mode::Wire, Bump::Minor, from, to).is_empty());
+
+        assert!(!violations(Mode::Source, Bump::Minor, from, to).is_empty());
+    }
+
+    #[test]
+    fn test_sub_type_strategy_change() {
+        let from = r#"
+        #[type_info(strategy = "tagged", tag = "type")]
+        interface Shape { Circle {} }
+        "#;
+
+        let to = r#"
+        #[type_info(strategy = "tagged", tag = "kind")]
+        interface Shape { Circle {} }
+        "#;
+
+        assert_eq!(
+            vec![
+                violation(
+                    "sub-type strategy changed from tagged by `type` to tagged by `kind`",
+                    "interface",
+                    "interface",
+                ),
+            ],
+            violations(Mode::Wire, Bump::Minor, from, to)
+        );
+
+        assert!(violations(Mode::Source, Bump::Minor, from, to).is_empty());
+    }
+
+    #[test]
+    fn test_tuple_member_moved() {
+        let from = "tuple Pair { a: u32; b: u32; }";
+        let to = "tuple Pair { b: u32; a: u32; }";
+
+        assert_eq!(
+            vec![
+                violation("tuple member moved from #0 to #1", "a", "a"),
+                violation("tuple member moved from #1 to #0", "b", "b"),
+            ],
+            violations(Mode::Both, Bump::Minor, from, to)
+        );
+
+        // on the wire, only the position and type of each member is visible.
+        assert!(violations(Mode::Wire, Bump::Minor, from, to).is_empty());
+    }
+
+    #[test]
+    fn test_tuple_wire() {
+        let from = "tuple Pair { a: u32; b: string; }";
+
+        // renaming members doesn't affect the wire.
+        let renamed = r#"tuple Pair { c: u32; d: string as "e"; }"#;
+        assert!(violations(Mode::Wire, Bump::Minor, from, renamed).is_empty());
+
+        let mut expected = vec![
+            violation("field removed", "a", "tuple"),
+            violation("field removed", "b", "tuple"),
+            violation("required field added", "tuple", "c"),
+            violation("required field added", "tuple", "d"),
+        ];
+
+        expected.sort();
+        assert_eq!(expected, violations(Mode::Both, Bump::Minor, from, renamed));
+
+        // swapping members changes the type at each position.
+        let swapped = "tuple Pair { b: string; a: u32; }";
+
+        assert_eq!(
+            vec![
+                violation("field type changed from `string` to `u32`", "b", "a"),
+                violation("field type changed from `u32` to `string`", "a", "b"),
+            ],
+            violations(Mode::Wire, Bump::Minor, from, swapped)
+        );
+    }
+
+    #[test]
+    fn test_endpoint_name_change() {
+        let from = "service Svc { get() -> string; }";
+        let to = r#"service Svc { get() -> string as "fetch"; }"#;
+
+        assert_eq!(
+            vec![violation("endpoint name changed from `get` to `fetch`", "get", "get")],
+            violations(Mode::Both, Bump::Minor, from, to)
+        );
+
+        assert!(violations(Mode::Source, Bump::Minor, from, to).is_empty());
+    }
+
+    #[test]
+    fn test_endpoint_http_path() {
+        let from = r#"service Svc { #[http(path = "/foo/{id}")] get(id: string) -> string; }"#;
+        let to = r#"service Svc { #[http(path = "/bar/{id}")] get(id: string) -> string; }"#;
+
+        assert_eq!(
+            vec![
+                violation(
+                    "HTTP path changed from `/foo/{id}` to `/bar/{id}`",
+                    "get",
+                    "get",
+                ),
+            ],
+            violations(Mode::Wire, Bump::Minor, from, to)
+        );
+
+        // names of variables are not visible in the request.
+        let renamed = r#"service Svc { #[http(path = "/foo/{key}")] get(key: string) -> string; }"#;
+        let renamed = violations(Mode::Wire, Bump::Minor, from, renamed);
+        assert!(renamed.iter().all(|v| !v.0.starts_with("HTTP path")));
+    }
+
+    #[test]
+    fn test_endpoint_http_method() {
+        let from = r#"service Svc { #[http(method = "GET")] get() -> string; }"#;
+        let to = r#"service Svc { #[http(method = "POST")] get() -> string; }"#;
+
+        assert_eq!(
+            vec![violation("HTTP method changed from `GET` to `POST`", "get", "get")],
+            violations(Mode::Wire, Bump::Minor, from, to)
+        );
+
+        // endpoints without an explicit method use GET.
+        let implicit = "service Svc { get() -> string; }";
+        assert!(violations(Mode::Wire, Bump::Minor, from, implicit).is_empty());
+    }
+
+    #[test]
+    fn test_endpoint_http_body() {
+        let from = r#"
+        service Svc {
+          #[http(path = "/{a}", body = "b")]
+          put(a: string, b: string);
+        }
+        "#;
+
+        let to = r#"
+        service Svc {
+          #[http(path = "/{b}", body = "a")]
+          put(a: string, b: string);
+        }
+        "#;
+
+        assert_eq!(
+            vec![violation("HTTP body changed from `b` to `a`", "put", "put")],
+            violations(Mode::Both, Bump::Minor, from, to)
+        );
+
+        // both arguments have the same type, so the request is the same on the wire.
+        assert!(violations(Mode::Wire, Bump::Minor, from, to).is_empty());
+    }
+
+    #[test]
+    fn test_field_ident_changed() {
+        let from = r#"type Foo { foo: string as "wire"; }"#;
+        let to = r#"type Foo { bar: string as "wire"; }"#;
+
+        assert!(violations(Mode::Wire, Bump::Minor, from, to).is_empty());
+
+        assert_eq!(
+            vec![
+                violation("field removed", "foo", "type"),
+                violation("required field added", "type", "bar"),
+            ],
+            violations(Mode::Source, Bump::Minor, from, to)
+        );
+    }
+
+    #[test]
+    fn test_field_name_changed() {
+        let from = r#"type Foo { foo: string as "wire"; }"#;
+        let to = r#"type Foo { foo: string as "other"; }"#;
+
+        assert!(violations(Mode::Source, Bump::Minor, from, to).is_empty());
+
+        assert_eq!(
+            vec![
+                violation("field removed", "foo", "type"),
+                violation("required field added", "type", "foo"),
+            ],
+            violations(Mode::Wire, Bump::Minor, from, to)
+        );
+
+        assert_eq!(
+            vec![violation("field name changed from `wire` to `other`", "foo", "foo")],
+            violations(Mode::Both, Bump::Minor, from, to)
+        );
+    }
+
+    #[test]
+    fn test_variant_ident_changed() {
+        let from = r#"enum Foo as string { A as "a"; }"#;
+        let to = r#"enum Foo as string { B as "a"; }"#;
+
+        assert!(violations(Mode::Wire, Bump::Minor, from, to).is_empty());
+
+        assert_eq!(
+            vec![violation("variant removed", "A", "enum")],
+            violations(Mode::Source, Bump::Minor, from, to)
+        );
+    }
+
+    #[test]
+    fn test_variant_ordinal_changed() {
+        let from = r#"enum Foo as string { A as "a"; }"#;
+        let to = r#"enum Foo as string { A as "b"; }"#;
+
+        assert!(violations(Mode::Source, Bump::Minor, from, to).is_empty());
+
+        assert_eq!(
+            vec![violation("variant removed", "A", "enum")],
+            violations(Mode::Wire, Bump::Minor, from, to)
+        );
+
+        assert_eq!(
+            vec![violation("variant ordinal changed from `a` to `b`", "A", "A")],
+            violations(Mode::Both, Bump::Minor, from, to)
+        );
+    }
+
+    #[test]
+    fn test_endpoint_ident_changed() {
+        let from = r#"service Svc { get() -> string as "fetch"; }"#;
+        let to = r#"service Svc { load() -> string as "fetch"; }"#;
+
+        assert!(violations(Mode::Wire, Bump::Minor, from, to).is_empty());
+
+        assert_eq!(
+            vec![violation("endpoint removed", "get", "service")],
+            violations(Mode::Source, Bump::Minor, from, to)
+        );
+    }
+}
@@ -0,0 +1,268 @@
+//! Migration reports between major versions.
+//!
+//! A major version is permitted to break compatibility, so the regular checks don't report
+//! anything. A migration report instead lists every change which would have been a violation, to
+//! help consumers move to the new version.
+
+use super::{check_minor, decls_to_map, fields, Mode, Violation};
+use reproto_core::{ErrorPos, Loc, RpFile, RpType};
+use reproto_core::errors::*;
+use reproto_core::utils::find_line;
+use std::fmt::Write;
+
+/// An alias which would have kept a field compatible on the wire.
+#[derive(Debug)]
+pub struct Alias {
+    /// Declaration the field belongs to, like `Foo::Bar`.
+    pub decl: String,
+    /// Identifier of the field in the new version.
+    pub field: String,
+    /// If the field is optional in the new version.
+    pub optional: bool,
+    /// Type of the field in the new version.
+    pub ty: RpType,
+    /// Name of the field on the wire in the previous version.
+    pub name: String,
+    /// Position of the field in the new version.
+    pub pos: ErrorPos,
+}
+
+/// Every breaking change between two versions.
+#[derive(Debug)]
+pub struct Migration {
+    pub breaking: Vec<Violation>,
+    pub aliases: Vec<Alias>,
+}
+
+/// Build a migration report between two versions, regardless of what the versions are.
+pub fn migration(mode: Mode, from: &RpFile, to: &RpFile) -> Result<Migration> {
+    let breaking = check_minor(mode, from, to)?;
+
+    let mut aliases = Vec::new();
+
+    let from_storage = decls_to_map(&from.decls);
+    let to_storage = decls_to_map(&to.decls);
+
+    for (name, from_reg) in &from_storage {
+        let to_reg = match to_storage.get(name) {
+            Some(to_reg) => to_reg,
+            None => continue,
+        };
+
+        let from_fields = fields(from_reg);
+        let to_fields = fields(to_reg);
+
+        // indexes of fields in the new version which are new on the wire, and could take an old
+        // name.
+        let mut candidates = to_fields
+            .iter()
+            .enumerate()
+            .filter(|&(_, t)| !from_fields.iter().any(|f| f.name() == t.name()))
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+
+        for (index, from_field) in from_fields.iter().enumerate() {
+            if to_fields.iter().any(|t| t.name() == from_field.name()) {
+                continue;
+            }
+
+            let ty = from_field.ty.clone().localize();
+
+            let matching = candidates
+                .iter()
+                .cloned()
+                .filter(|&i| to_fields[i].ty.clone().localize() == ty)
+                .collect::<Vec<_>>();
+
+            // only suggest unambiguous aliases, for the field at the same position, or for the
+            // only field with the same type.
+            let to_index = if matching.contains(&index) {
+                index
+            } else if matching.len() == 1 {
+                matching[0]
+            } else {
+                continue;
+            };
+
+            candidates.retain(|&i| i != to_index);
+            let to_field = to_fields[to_index];
+
+            aliases.push(Alias {
+                decl: name.join("::"),
+                field: to_field.ident().to_string(),
+                optional: to_field.is_optional(),
+                ty: to_field.ty.clone(),
+                name: from_field.name().to_string(),
+                pos: Loc::pos(to_field).into(),
+            });
+        }
+    }
+
+    aliases.sort_by(|a, b| (&a.decl, &a.field).cmp(&(&b.decl, &b.field)));
+
+    Ok(Migration {
+        breaking: breaking,
+        aliases: aliases,
+    })
+}
+
+impl Migration {
+    /// Render the report as markdown, suitable for release notes.
+    pub fn to_markdown(&self, title: &str) -> Result<String> {
+        let mut out = String::new();
+
+        writeln!(out, "# {}", title)?;
+
+        if self.breaking.is_empty() {
+            writeln!(out)?;
+            writeln!(out, "No breaking changes.")?;
+            return Ok(out);
+        }
+
+        writeln!(out)?;
+        writeln!(out, "## Breaking changes")?;
+        writeln!(out)?;
+
+        for violation in &self.breaking {
+            writeln!(out, "* {}", violation.message())?;
+
+            let (from, to) = violation.positions();
+
+            if let Some(from) = from {
+                writeln!(out, "  * before: {}", location(from)?)?;
+            }
+
+            if let Some(to) = to {
+                writeln!(out, "  * after: {}", location(to)?)?;
+            }
+        }
+
+        if self.aliases.is_empty() {
+            return Ok(out);
+        }
+
+        writeln!(out)?;
+        writeln!(out, "## Suggested aliases")?;
+        writeln!(out)?;
+        writeln!(
+            out,
+            "The following fields would have stayed compatible on the wire with an alias:"
+        )?;
+        writeln!(out)?;
+
+        for alias in &self.aliases {
+            writeln!(
+                out,
+                "* `{}::{}`: use `{}{}: {} as \"{}\";` ({})",
+                alias.decl,
+                alias.field,
+                alias.field,
+                if alias.optional { "?" } else { "" },
+                alias.ty,
+                alias.name,
+                location(&alias.pos)?
+            )?;
+        }
+
+        return Ok(out);
+
+        /// Describe where a position is, like `foo.reproto:12:3`.
+        fn location(pos: &ErrorPos) -> Result<String> {
+            let (_, line, (s, _)) = find_line(pos.object.read()?, (pos.start, pos.end))?;
+            Ok(format!("{}:{}:{}", pos.object, line + 1, s + 1))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use trans::testing::file;
+
+    #[test]
+    fn test_migration() {
+        let from = file(
+            r#"
+            type Foo {
+              id: u32;
+              name: string;
+              count?: u32;
+            }
+            "#,
+        );
+
+        let to = file(
+            r#"
+            type Foo {
+              id: u32;
+              label: string;
+              total?: u32;
+            }
+            "#,
+        );
+
+        let report = migration(Mode::Wire, &from, &to).expect("to build migration");
+
+        let mut breaking = report
+            .breaking
+            .iter()
+            .map(Violation::message)
+            .collect::<Vec<_>>();
+
+        breaking.sort();
+
+        assert_eq!(
+            vec!["field removed", "field removed", "required field added"],
+            breaking
+        );
+
+        let aliases = report
+            .aliases
+            .iter()
+            .map(|a| (a.decl.as_str(), a.field.as_str(), a.name.as_str()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec![("Foo", "label", "name"), ("Foo", "total", "count")], aliases);
+    }
+
+    #[test]
+    fn test_ambiguous_alias() {
+        let from = file("type Foo { a: string; id: u32; }");
+        let to = file("type Foo { id: u32; b: string; c: string; }");
+
+        let report = migration(Mode::Wire, &from, &to).expect("to build migration");
+        assert!(report.aliases.is_empty());
+
+        // the field at the same position is unambiguous.
+        let to = file("type Foo { b: string; c: string; id: u32; }");
+
+        let report = migration(Mode::Wire, &from, &to).expect("to build migration");
+        assert_eq!(1, report.aliases.len());
+        assert_eq!("b", report.aliases[0].field);
+    }
+
+    #[test]
+    fn test_to_markdown() {
+        let from = file("type Foo {\n  name: string;\n  count?: u32;\n}\n");
+        let to = file("type Foo {\n  label: string;\n  total?: u32;\n}\n");
+
+        let report = migration(Mode::Wire, &from, &to).expect("to build migration");
+        let markdown = report.to_markdown("Foo 2.0.0").expect("to render markdown");
+
+        assert!(markdown.starts_with("# Foo 2.0.0\n\n## Breaking changes\n\n"));
+        assert!(markdown.contains("* required field added\n  * before: test:1:1\n"));
+        assert!(markdown.contains("* `Foo::label`: use `label: string as \"name\";` (test:2:3)\n"));
+        assert!(markdown.contains("* `Foo::total`: use `total?: u32 as \"count\";` (test:3:3)\n"));
+    }
+
+    #[test]
+    fn test_to_markdown_no_changes() {
+        let from = file("type Foo { id: u32; }");
+        let report = migration(Mode::Wire, &from, &from).expect("to build migration");
+
+        assert_eq!(
+            "# Foo 2.0.0\n\nNo breaking changes.\n",
+            report.to_markdown("Foo 2.0.0").expect("to render markdown")
+        );
+    }
+}
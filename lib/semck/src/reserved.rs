@@ -0,0 +1,359 @@
+//! Checks that names which were removed or reserved are never reused.
+//!
+//! Clients built against an earlier version would interpret a new member with a reused name as
+//! the old one, regardless of how many versions ago it was removed.
+
+use super::{decls_to_map, endpoints, enum_variants, fields, Component, Mode, Violation};
+use reproto_core::{ErrorPos, Loc, Pos, RpFile, RpReg};
+use reproto_core::errors::*;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::iter;
+
+/// The kind of a member, the mode its key is for, and the key it is matched with across versions.
+type Member = (&'static str, Mode, String);
+
+/// Names which should be added to the reserved list of a type.
+#[derive(Debug)]
+pub struct Reservation {
+    /// Declaration to reserve the names in, like `Foo::Bar`.
+    pub decl: String,
+    /// Position of the declaration in the new version.
+    pub pos: ErrorPos,
+    /// Names to reserve, in order.
+    pub names: Vec<String>,
+}
+
+/// Collect every field, variant, and endpoint of a declaration, with their keys, identifiers,
+/// and positions.
+///
+/// In `Mode::Both`, every member has one key for its identifier and one for its name on the wire.
+///
+/// Reserved words are matched against the identifier, since the key of a member might not be a
+/// name at all, like the ordinal of a variant on the wire.
+fn members<'a>(mode: Mode, reg: &'a RpReg) -> Vec<(Vec<Member>, &'a str, &'a Pos)> {
+    let modes = match mode {
+        Mode::Both => vec![Mode::Source, Mode::Wire],
+        mode => vec![mode],
+    };
+
+    let mut out = Vec::new();
+
+    for field in fields(reg) {
+        let keys = modes
+            .iter()
+            .map(|m| ("field", *m, m.field_key(field)))
+            .collect();
+
+        out.push((keys, field.ident(), Loc::pos(field)));
+    }
+
+    for variant in enum_variants(reg) {
+        let keys = modes
+            .iter()
+            .map(|m| ("variant", *m, m.variant_key(variant)))
+            .collect();
+
+        out.push((keys, variant.ident.as_str(), Loc::pos(variant)));
+    }
+
+    for endpoint in endpoints(reg) {
+        let keys = modes
+            .iter()
+            .map(|m| ("endpoint", *m, m.endpoint_key(endpoint)))
+            .collect();
+
+        out.push((keys, endpoint.ident(), Loc::pos(endpoint)));
+    }
+
+    out
+}
+
+/// Names listed in `#[reserved(...)]` of a declaration.
+fn reserved(reg: &RpReg) -> Vec<&Loc<String>> {
+    match *reg {
+        RpReg::Type(ref body) => body.reserved.iter().collect(),
+        _ => vec![],
+    }
+}
+
+/// Check that no member added in the new version reuses a name which was removed in any
+/// previously published version, or which is reserved.
+///
+/// `history` contains every previously published version, from the oldest to the newest.
+pub fn check(mode: Mode, history: &[&RpFile], to: &RpFile) -> Result<Vec<Violation>> {
+    let mut violations = Vec::new();
+
+    let history = history
+        .iter()
+        .map(|file| decls_to_map(&file.decls))
+        .collect::<Vec<_>>();
+
+    let to_storage = decls_to_map(&to.decls);
+
+    for (name, to_reg) in &to_storage {
+        // members of the newest published version are still in use, and not retired.
+        let current = history
+            .last()
+            .and_then(|storage| storage.get(name))
+            .map(|reg| {
+                members(mode, reg)
+                    .into_iter()
+                    .flat_map(|(keys, _, _)| keys)
+                    .collect::<HashSet<_>>()
+            })
+            .unwrap_or_default();
+
+        let mut retired: HashMap<Member, &Pos> = HashMap::new();
+
+        for reg in history.iter().filter_map(|storage| storage.get(name)) {
+            for (keys, _, pos) in members(mode, reg) {
+                for key in keys {
+                    if !current.contains(&key) {
+                        retired.insert(key, pos);
+                    }
+                }
+            }
+        }
+
+        let all_regs = history
+            .iter()
+            .filter_map(|storage| storage.get(name))
+            .chain(iter::once(to_reg));
+
+        let mut words: HashMap<&str, &Pos> = HashMap::new();
+
+        for reg in all_regs {
+            for word in reserved(reg) {
+                words.insert(word.as_str(), Loc::pos(word));
+            }
+        }
+
+        for (keys, ident, pos) in members(mode, to_reg) {
+            if keys.iter().all(|key| current.contains(key)) {
+                continue;
+            }
+
+            let previous = keys.into_iter()
+                .filter(|key| !current.contains(key))
+                .filter_map(|key| retired.get(&key).map(|previous| (key.2, *previous)))
+                .next()
+                .or_else(|| words.get(ident).map(|previous| (ident.to_string(), *previous)));
+
+            if let Some((name, previous)) = previous {
+                violations.push(Violation::NameReused(
+                    Component::Reserved,
+                    name,
+                    previous.into(),
+                    pos.into(),
+                ));
+            }
+        }
+    }
+
+    Ok(violations)
+}
+
+/// Suggest names to add to the reserved list of types in the new version.
+///
+/// Every field which was present in an earlier version of a type, but which is neither present
+/// nor reserved in the new version, is suggested.
+pub fn suggest(history: &[&RpFile], to: &RpFile) -> Vec<Reservation> {
+    let history = history
+        .iter()
+        .map(|file| decls_to_map(&file.decls))
+        .collect::<Vec<_>>();
+
+    let to_storage = decls_to_map(&to.decls);
+
+    let mut out = Vec::new();
+
+    for (name, to_reg) in &to_storage {
+        let body = match *to_reg {
+            RpReg::Type(ref body) => body,
+            _ => continue,
+        };
+
+        let mut names = BTreeSet::new();
+
+        for reg in history.iter().filter_map(|storage| storage.get(name)) {
+            for field in fields(reg) {
+                names.insert(field.ident().to_string());
+            }
+        }
+
+        for field in body.fields() {
+            names.remove(field.ident());
+        }
+
+        for word in &body.reserved {
+            names.remove(word.as_str());
+        }
+
+        if names.is_empty() {
+            continue;
+        }
+
+        out.push(Reservation {
+            decl: name.join("::"),
+            pos: to_reg.pos().into(),
+            names: names.into_iter().collect(),
+        });
+    }
+
+    out.sort_by(|a, b| a.decl.cmp(&b.decl));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use trans::testing::file;
+
+    /// Check the given history and new version, and list the names which are reused.
+    fn reused(mode: Mode, history: &[&str], to: &str) -> Vec<String> {
+        let history = history.iter().map(|spec| file(spec)).collect::<Vec<_>>();
+        let history = history.iter().collect::<Vec<_>>();
+
+        let mut out = check(mode, &history, &file(to))
+            .expect("to check")
+            .iter()
+            .map(|v| match *v {
+                Violation::NameReused(_, ref name, _, _) => name.to_string(),
+                ref other => panic!("unexpected violation: {:?}", other),
+            })
+            .collect::<Vec<_>>();
+
+        out.sort();
+        out
+    }
+
+    #[test]
+    fn test_field_removed_earlier() {
+        let history = [
+            "type Foo { a: string; b: string; }",
+            "type Foo { a: string; }",
+            "type Foo { a: string; c: string; }",
+        ];
+
+        assert_eq!(
+            vec!["b"],
+            reused(Mode::Both, &history, "type Foo { a: string; b?: string; }")
+        );
+
+        // fields in the latest version are not retired.
+        assert!(reused(Mode::Both, &history, "type Foo { a: string; c: string; }").is_empty());
+    }
+
+    #[test]
+    fn test_field_removed_earlier_both() {
+        let history = [
+            r#"type Foo { a: string; b: string as "x"; }"#,
+            "type Foo { a: string; }",
+        ];
+
+        // the name on the wire is reused, under a new identifier.
+        let to = r#"type Foo { a: string; c?: string as "x"; }"#;
+        assert_eq!(vec!["x"], reused(Mode::Both, &history, to));
+        assert_eq!(vec!["x"], reused(Mode::Wire, &history, to));
+        assert!(reused(Mode::Source, &history, to).is_empty());
+
+        // the identifier is reused, under a new name on the wire.
+        let to = r#"type Foo { a: string; b?: string as "y"; }"#;
+        assert_eq!(vec!["b"], reused(Mode::Both, &history, to));
+        assert_eq!(vec!["b"], reused(Mode::Source, &history, to));
+        assert!(reused(Mode::Wire, &history, to).is_empty());
+    }
+
+    #[test]
+    fn test_reserved_field() {
+        let history = ["#[reserved(b)] type Foo { a: string; }"];
+
+        assert_eq!(
+            vec!["b"],
+            reused(Mode::Both, &history, "type Foo { a: string; b?: string; }")
+        );
+
+        assert_eq!(
+            vec!["c"],
+            reused(Mode::Both, &[], "#[reserved(c)] type Foo { a: string; c?: string; }")
+        );
+    }
+
+    #[test]
+    fn test_variant_removed_earlier() {
+        let history = [
+            r#"enum Color as string { Red as "red"; Green as "green"; Blue as "blue"; }"#,
+            r#"enum Color as string { Red as "red"; Blue as "blue"; }"#,
+        ];
+
+        // variants are matched by their ordinal on the wire.
+        let to = r#"enum Color as string { Red as "red"; Blue as "blue"; Lime as "green"; }"#;
+        assert_eq!(vec!["green"], reused(Mode::Wire, &history, to));
+        assert!(reused(Mode::Source, &history, to).is_empty());
+
+        let to = r#"enum Color as string { Red as "red"; Blue as "blue"; Green as "lime"; }"#;
+        assert_eq!(vec!["Green"], reused(Mode::Source, &history, to));
+        assert!(reused(Mode::Wire, &history, to).is_empty());
+    }
+
+    #[test]
+    fn test_variant_removed_earlier_both() {
+        let history = [
+            r#"enum Color as string { Red as "red"; Green as "green"; Blue as "blue"; }"#,
+            r#"enum Color as string { Red as "red"; Blue as "blue"; }"#,
+        ];
+
+        let to = r#"enum Color as string { Red as "red"; Blue as "blue"; Lime as "green"; }"#;
+        assert_eq!(vec!["green"], reused(Mode::Both, &history, to));
+
+        let to = r#"enum Color as string { Red as "red"; Blue as "blue"; Green as "lime"; }"#;
+        assert_eq!(vec!["Green"], reused(Mode::Both, &history, to));
+    }
+
+    #[test]
+    fn test_endpoint_removed_earlier() {
+        let history = [
+            "service Foo { get() -> string; list() -> [string]; }",
+            "service Foo { get() -> string; }",
+        ];
+
+        let to = "service Foo { get() -> string; list() -> [u32]; }";
+
+        assert_eq!(vec!["list"], reused(Mode::Wire, &history, to));
+        assert_eq!(vec!["list"], reused(Mode::Source, &history, to));
+    }
+
+    #[test]
+    fn test_endpoint_removed_earlier_both() {
+        let history = [
+            r#"service Foo { get() -> string; list() -> [string] as "items"; }"#,
+            "service Foo { get() -> string; }",
+        ];
+
+        let to = r#"service Foo { get() -> string; all() -> [u32] as "items"; }"#;
+        assert_eq!(vec!["items"], reused(Mode::Both, &history, to));
+
+        let to = r#"service Foo { get() -> string; list() -> [u32] as "all"; }"#;
+        assert_eq!(vec!["list"], reused(Mode::Both, &history, to));
+    }
+
+    #[test]
+    fn test_suggest() {
+        let history = [
+            "type Foo { a: string; b: string; c: string; }",
+            "type Foo { a: string; c: string; }",
+            "type Foo { a: string; }",
+        ];
+
+        let history = history.iter().map(|spec| file(spec)).collect::<Vec<_>>();
+        let history = history.iter().collect::<Vec<_>>();
+
+        let reservations = suggest(&history, &file("type Foo { a: string; }"));
+        assert_eq!(1, reservations.len());
+        assert_eq!("Foo", reservations[0].decl);
+        assert_eq!(vec!["b", "c"], reservations[0].names);
+
+        let reservations = suggest(&history, &file("#[reserved(b, c)] type Foo { a: string; }"));
+        assert!(reservations.is_empty());
+    }
+}
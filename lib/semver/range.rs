@@ -196,6 +196,22 @@ impl Range {
     pub fn matches_any(&self) -> bool {
         self.predicates.is_empty()
     }
+
+    /// Check if range pins a single, exact version.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use reproto_semver::Range;
+    ///
+    /// assert!(Range::parse("=1.2.3").unwrap().is_exact());
+    /// assert!(!Range::parse("^1.2.3").unwrap().is_exact());
+    /// ```
+    pub fn is_exact(&self) -> bool {
+        self.predicates
+            .iter()
+            .any(|p| p.op == Ex && p.minor.is_some() && p.patch.is_some())
+    }
 }
 
 impl Predicate {
@@ -750,6 +766,14 @@ mod test {
         assert_match(&r, &["2.1.1-really.0"]);
     }
 
+    #[test]
+    pub fn test_is_exact() {
+        assert!(range("=1.0.0").is_exact());
+        assert!(!range("=1.0").is_exact());
+        assert!(!range("1.0.0").is_exact());
+        assert!(!Range::any().is_exact());
+    }
+
     #[test]
     pub fn test_from_str() {
         assert_eq!(
@@ -1,6 +1,7 @@
 use ast::{self, UseDecl};
-use core::{Context, Loc, Object, PathObject, Range, Resolved, Resolver, RpDecl, RpFile, RpName,
-           RpPackage, RpReg, RpRequiredPackage, RpVersionedPackage, WithPos};
+use core::{codes, Context, ErrorPos, Loc, Object, PathObject, Range, Resolved, ResolvedByPrefix,
+           Resolver, RpDecl, RpFile, RpName, RpPackage, RpReg, RpRequiredPackage, RpValue,
+           RpVersionedPackage, WithPos};
 use core::errors::{Error, Result};
 use into_model::IntoModel;
 use linked_hash_map::LinkedHashMap;
@@ -66,6 +67,8 @@ pub struct Environment {
     resolver: Box<Resolver>,
     /// Store required packages, to avoid unnecessary lookups.
     visited: HashMap<RpRequiredPackage, Option<RpVersionedPackage>>,
+    /// Packages which are being imported, in the order they were imported in.
+    importing: Vec<RpPackage>,
     /// Registered types.
     types: LinkedHashMap<RpName, RpReg>,
     /// Files and associated declarations.
@@ -93,6 +96,7 @@ impl Environment {
             package_prefix: package_prefix,
             resolver: resolver,
             visited: HashMap::new(),
+            importing: Vec::new(),
             types: LinkedHashMap::new(),
             files: BTreeMap::new(),
             keywords: Rc::new(HashMap::new()),
@@ -166,14 +170,32 @@ impl Environment {
         let required = RpRequiredPackage::new(package.package.clone(), Range::any());
 
         if !self.visited.contains_key(&required) {
-            let file = self.load_object(object, &package)?;
-            self.process_file(package.clone(), file)?;
+            self.track_import(&package.package, |env| {
+                let file = env.load_object(object, &package)?;
+                env.process_file(Some(object), package.clone(), file)
+            })?;
+
             self.visited.insert(required, Some(package.clone()));
         }
 
         Ok(package)
     }
 
+    /// Import another version of a package, next to any version which is already imported.
+    ///
+    /// Unlike `import_object`, this doesn't mark the package as visited, so it doesn't affect
+    /// which version other packages resolve when importing it.
+    pub fn import_version(&mut self, object: &Object, package: RpVersionedPackage) -> Result<()> {
+        if self.files
+            .contains_key(&package.clone().with_replacements(&self.keywords))
+        {
+            return Ok(());
+        }
+
+        let file = self.load_object(object, &package)?;
+        self.process_file(Some(object), package, file)
+    }
+
     /// Import a single, structured file object.
     pub fn import_file(
         &mut self,
@@ -184,8 +206,11 @@ impl Environment {
         let required = RpRequiredPackage::new(package.package.clone(), Range::any());
 
         if !self.visited.contains_key(&required) {
-            let file = self.load_file(file, &package)?;
-            self.process_file(package.clone(), file)?;
+            self.track_import(&package.package, |env| {
+                let file = env.load_file(file, &package)?;
+                env.process_file(None, package.clone(), file)
+            })?;
+
             self.visited.insert(required, Some(package.clone()));
         }
 
@@ -201,6 +226,51 @@ impl Environment {
             return Ok(existing.as_ref().cloned());
         }
 
+        let result = self.track_import(&required.package, |env| env.resolve_and_load(required))?;
+
+        self.visited.insert(required.clone(), result.clone());
+        Ok(result)
+    }
+
+    /// Import the newest version of every package which the given resolver can find, like every
+    /// package in a set of source paths.
+    pub fn import_all(&mut self, resolver: &mut Resolver) -> Result<()> {
+        for ResolvedByPrefix { package, .. } in resolver.resolve_by_prefix(&RpPackage::empty())? {
+            self.import(&RpRequiredPackage::new(package, Range::any()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Run the given import, recording the package as being imported while it runs.
+    ///
+    /// Packages are only marked as visited once they are completely imported, so a package
+    /// which is imported again while it is being imported is part of an import cycle.
+    fn track_import<F, T>(&mut self, package: &RpPackage, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut Self) -> Result<T>,
+    {
+        if let Some(index) = self.importing.iter().position(|p| p == package) {
+            let mut cycle = self.importing[index..]
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+
+            cycle.push(package.to_string());
+            return Err(format!("import cycle: {}", cycle.join(" -> ")).into());
+        }
+
+        self.importing.push(package.clone());
+        let result = f(self);
+        self.importing.pop();
+        result
+    }
+
+    /// Resolve the newest version of a package matching the given criteria, and load it.
+    fn resolve_and_load(
+        &mut self,
+        required: &RpRequiredPackage,
+    ) -> Result<Option<RpVersionedPackage>> {
         let mut candidates = BTreeMap::new();
 
         // find all matching objects from the resolver.
@@ -215,14 +285,14 @@ impl Environment {
             candidates
                 .entry(package)
                 .or_insert_with(Vec::new)
-                .push(file);
+                .push((object, file));
         }
 
         let result = if let Some((versioned, files)) = candidates.into_iter().last() {
             debug!("found: {} ({})", versioned, required);
 
-            for file in files.into_iter() {
-                self.process_file(versioned.clone(), file)?;
+            for (object, file) in files.into_iter() {
+                self.process_file(Some(object.as_ref()), versioned.clone(), file)?;
             }
 
             Some(versioned)
@@ -230,7 +300,6 @@ impl Environment {
             None
         };
 
-        self.visited.insert(required.clone(), result.clone());
         Ok(result)
     }
 
@@ -268,13 +337,20 @@ impl Environment {
     /// Parse a naming option.
     ///
     /// Since lower_camel is default, do nothing on that case.
-    fn parse_naming(&self, naming: &str) -> Result<Option<Box<Naming>>> {
-        let result: Option<Box<Naming>> = match naming {
+    fn parse_naming(&self, naming: Loc<RpValue>) -> Result<Option<Box<Naming>>> {
+        let (naming, pos) = Loc::take_pair(naming);
+
+        let result: Option<Box<Naming>> = match naming.as_identifier().with_pos(&pos)? {
             "upper_camel" => Some(Box::new(naming::to_upper_camel())),
             "lower_camel" => Some(Box::new(naming::to_lower_camel())),
             "upper_snake" => Some(Box::new(naming::to_upper_snake())),
             "lower_snake" => None,
-            _ => return Err("illegal value".into()),
+            naming => {
+                return Err(self.ctx
+                    .report()
+                    .err(codes::ILLEGAL_NAMING, pos, format!("illegal naming: {}", naming))
+                    .into());
+            }
         };
 
         Ok(result)
@@ -321,8 +397,8 @@ impl Environment {
                 root.endpoint_naming = endpoint_naming
                     .take_word()
                     .ok_or_else(|| Error::from("expected argument"))
-                    .and_then(|n| n.as_identifier().and_then(|n| self.parse_naming(n)))
-                    .with_pos(&pos)?;
+                    .with_pos(&pos)
+                    .and_then(|n| self.parse_naming(n))?;
 
                 check_selection!(&self.ctx, endpoint_naming);
             }
@@ -333,8 +409,8 @@ impl Environment {
                 root.field_naming = field_naming
                     .take_word()
                     .ok_or_else(|| Error::from("expected argument"))
-                    .and_then(|n| n.as_identifier().and_then(|n| self.parse_naming(n)))
-                    .with_pos(&pos)?;
+                    .with_pos(&pos)
+                    .and_then(|n| self.parse_naming(n))?;
 
                 check_selection!(&self.ctx, field_naming);
             }
@@ -382,7 +458,7 @@ impl Environment {
 
             let required = RpRequiredPackage::new(package, range);
 
-            let use_package = self.import(&required)?;
+            let use_package = self.import(&required).with_pos(Loc::pos(use_decl))?;
 
             if let Some(use_package) = use_package {
                 let use_package = self.package_prefix(&use_package);
@@ -393,7 +469,20 @@ impl Environment {
                     match prefixes.entry(alias.to_owned()) {
                         Entry::Vacant(entry) => entry.insert(use_package.clone()),
                         Entry::Occupied(_) => {
-                            return Err(format!("alias {} already in use", alias).into())
+                            let pos = use_decl
+                                .alias
+                                .as_ref()
+                                .map(Loc::pos)
+                                .unwrap_or_else(|| Loc::pos(use_decl));
+
+                            return Err(self.ctx
+                                .report()
+                                .err(
+                                    codes::ALIAS_IN_USE,
+                                    pos,
+                                    format!("alias {} already in use", alias),
+                                )
+                                .into());
                         }
                     };
                 }
@@ -401,14 +490,28 @@ impl Environment {
                 continue;
             }
 
-            return Err(Error::new(format!("no package found: {}", required)).with_pos(Loc::pos(use_decl)));
+            return Err(self.ctx
+                .report()
+                .err(
+                    codes::NO_PACKAGE_FOUND,
+                    Loc::pos(use_decl),
+                    format!("no package found: {}", required),
+                )
+                .into());
         }
 
         Ok(prefixes)
     }
 
     /// Process a single file, populating the environment.
-    fn process_file(&mut self, package: RpVersionedPackage, file: RpFile) -> Result<()> {
+    ///
+    /// `object` is the object the file was loaded from, if available.
+    fn process_file(
+        &mut self,
+        object: Option<&Object>,
+        package: RpVersionedPackage,
+        file: RpFile,
+    ) -> Result<()> {
         use linked_hash_map::Entry::*;
 
         let new_package = package.clone().with_replacements(&self.keywords);
@@ -416,7 +519,23 @@ impl Environment {
         let file = match self.files.entry(new_package) {
             btree_map::Entry::Vacant(entry) => entry.insert(file),
             btree_map::Entry::Occupied(_) => {
-                return Err(format!("package already registered: {}", package).into());
+                let message = format!("package already registered: {}", package);
+
+                let object = match object {
+                    Some(object) => object,
+                    None => return Err(message.into()),
+                };
+
+                let pos = ErrorPos {
+                    object: object.clone_object(),
+                    start: 0,
+                    end: 0,
+                };
+
+                return Err(self.ctx
+                    .report()
+                    .err(codes::PACKAGE_ALREADY_REGISTERED, pos, message)
+                    .into());
             }
         };
 
@@ -430,7 +549,11 @@ impl Environment {
                 Occupied(entry) => {
                     return Err(self.ctx
                         .report()
-                        .err(t.pos(), "conflicting declaration")
+                        .err(
+                            codes::CONFLICTING_DECLARATION,
+                            t.pos(),
+                            "conflicting declaration",
+                        )
                         .info(entry.get().pos(), "last declaration here")
                         .into());
                 }
@@ -440,3 +563,234 @@ impl Environment {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::{BytesObject, CapturingFilesystem, Version};
+    use std::sync::Arc;
+    use testing;
+
+    /// Resolves packages to the given specifications, all with the same version.
+    struct TestResolver {
+        version: Version,
+        specs: HashMap<&'static str, &'static str>,
+    }
+
+    impl Resolver for TestResolver {
+        fn resolve(&mut self, package: &RpRequiredPackage) -> Result<Vec<Resolved>> {
+            let name = package.package.to_string();
+
+            let spec = match self.specs.get(name.as_str()) {
+                Some(spec) => spec,
+                None => return Ok(vec![]),
+            };
+
+            Ok(vec![
+                Resolved {
+                    version: Some(self.version.clone()),
+                    object: Box::new(object(&name, spec)),
+                },
+            ])
+        }
+
+        fn resolve_by_prefix(&mut self, prefix: &RpPackage) -> Result<Vec<ResolvedByPrefix>> {
+            let mut out = Vec::new();
+
+            for (name, spec) in &self.specs {
+                let package = RpPackage::parse(name);
+
+                if package.starts_with(prefix) {
+                    out.push(ResolvedByPrefix {
+                        package: package,
+                        object: Box::new(object(name, spec)),
+                    });
+                }
+            }
+
+            Ok(out)
+        }
+    }
+
+    fn object(name: &str, spec: &str) -> BytesObject {
+        BytesObject::new(name.to_string(), Arc::new(spec.as_bytes().to_vec()))
+    }
+
+    fn resolver(version: Version, specs: &[(&'static str, &'static str)]) -> TestResolver {
+        TestResolver {
+            version: version,
+            specs: specs.iter().cloned().collect(),
+        }
+    }
+
+    fn environment(version: Version, specs: &[(&'static str, &'static str)]) -> Environment {
+        let ctx = Rc::new(Context::new(Box::new(CapturingFilesystem::new())));
+        Environment::new(ctx, None, Box::new(resolver(version, specs)))
+    }
+
+    #[test]
+    fn test_references() {
+        let spec = r#"
+        type Foo {
+          bar: Bar;
+          bars: [Bar];
+          by_name?: {string: Bar};
+          id: string;
+        }
+
+        type Bar {
+        }
+
+        tuple Pair {
+          a: u32;
+          b: Foo;
+        }
+
+        interface Shape {
+          Circle {
+            inner: Foo;
+          }
+        }
+
+        service Svc {
+          get(foo: Foo) -> stream Bar;
+        }
+        "#;
+
+        let file = testing::file(spec);
+
+        let mut references = file.references()
+            .into_iter()
+            .map(|r| {
+                let nesting = r.nesting.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+                format!(
+                    "{} {}, {} [{}] -> {}",
+                    r.owner_kind,
+                    r.owner.join("::"),
+                    r.site,
+                    nesting.join(", "),
+                    r.name.join("::")
+                )
+            })
+            .collect::<Vec<_>>();
+
+        references.sort();
+
+        let expected = vec![
+            "service Svc, argument `foo` of endpoint `get` [] -> Foo",
+            "service Svc, response of endpoint `get` [] -> Bar",
+            "sub-type Shape::Circle, field `inner` [] -> Foo",
+            "tuple Pair, tuple member #1 `b` [] -> Foo",
+            "type Foo, field `bar` [] -> Bar",
+            "type Foo, field `bars` [array element] -> Bar",
+            "type Foo, field `by_name` [map value] -> Bar",
+        ];
+
+        assert_eq!(expected, references);
+    }
+
+    #[test]
+    fn test_import_version() {
+        let mut env = environment(Version::new(2, 0, 0), &[("dep", "type Foo {}")]);
+
+        let old = RpVersionedPackage::new(RpPackage::parse("dep"), Some(Version::new(1, 0, 0)));
+
+        env.import_version(&object("dep", "type Bar {}"), old.clone())
+            .expect("to import version");
+
+        // other packages still resolve the version which the resolver picks.
+        let required = RpRequiredPackage::new(RpPackage::parse("dep"), Range::any());
+        let new = env.import(&required).expect("to import");
+
+        assert_eq!(
+            Some(RpVersionedPackage::new(RpPackage::parse("dep"), Some(Version::new(2, 0, 0)))),
+            new
+        );
+
+        let packages = env.for_each_file()
+            .map(|(package, _)| package.clone())
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec![old, new.unwrap()], packages);
+    }
+
+    #[test]
+    fn test_import_cycle() {
+        let specs = [
+            ("a", "use b; type A {}"),
+            ("b", "use c; type B {}"),
+            ("c", "use a; type C {}"),
+        ];
+
+        let mut env = environment(Version::new(1, 0, 0), &specs);
+        let required = RpRequiredPackage::new(RpPackage::parse("a"), Range::any());
+
+        let e = env.import(&required).expect_err("import cycle");
+        assert_eq!("import cycle: a -> b -> c -> a", e.message());
+
+        // the root package is part of the cycle as well.
+        let mut env = environment(Version::new(1, 0, 0), &specs);
+        let package = RpVersionedPackage::new(RpPackage::parse("b"), None);
+
+        let e = env.import_object(&object("b", "use c;"), Some(package))
+            .expect_err("import cycle");
+
+        assert_eq!("import cycle: b -> c -> a -> b", e.message());
+    }
+
+    #[test]
+    fn test_import_shared() {
+        let specs = [
+            ("a", "use b; use c; type A {}"),
+            ("b", "use c; type B {}"),
+            ("c", "type C {}"),
+        ];
+
+        let mut env = environment(Version::new(1, 0, 0), &specs);
+        let required = RpRequiredPackage::new(RpPackage::parse("a"), Range::any());
+
+        // a package used twice, but not in a cycle.
+        assert!(env.import(&required).expect("to import").is_some());
+        assert_eq!(3, env.for_each_file().count());
+    }
+
+    #[test]
+    fn test_import_all() {
+        let specs = [
+            ("a", "type Foo {}"),
+            ("b", "use a; type Bar { foo: a::Foo; }"),
+            ("c", "type Baz {}"),
+        ];
+
+        let mut env = environment(Version::new(1, 0, 0), &specs);
+        let required = RpRequiredPackage::new(RpPackage::parse("a"), Range::any());
+        env.import(&required).expect("to import");
+
+        let mut resolver = resolver(Version::new(1, 0, 0), &specs);
+        env.import_all(&mut resolver).expect("to import all");
+
+        let mut packages = env.for_each_file()
+            .map(|(package, _)| package.package.to_string())
+            .collect::<Vec<_>>();
+
+        packages.sort();
+        assert_eq!(vec!["a", "b", "c"], packages);
+
+        // packages which depend on an imported package are found.
+        let references = env.for_each_file()
+            .flat_map(|(_, file)| file.references())
+            .map(|r| {
+                format!(
+                    "{}::{} -> {}::{}",
+                    r.owner.package.package,
+                    r.owner.join("::"),
+                    r.name.package.package,
+                    r.name.join("::")
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec!["b::Bar -> a::Foo"], references);
+    }
+}
@@ -6,7 +6,7 @@ use naming::Naming;
 use path_parser;
 use scope::Scope;
 use std::borrow::Cow;
-use std::collections::{hash_map, HashMap, HashSet};
+use std::collections::{hash_map, BTreeSet, HashMap, HashSet};
 use std::option;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
@@ -15,13 +15,35 @@ macro_rules! check_defined {
     ($ctx:expr, $names:expr, $item:expr, $accessor:expr, $what:expr) => {
         if let Some(other) = $names.insert($accessor.to_string(), Loc::pos(&$item).clone()) {
             return Err($ctx.report()
-              .err(Loc::pos(&$item), format!(concat!($what, " `{}` is already defined"), $accessor))
+              .err(
+                  codes::DUPLICATE_DEFINITION,
+                  Loc::pos(&$item),
+                  format!(concat!($what, " `{}` is already defined"), $accessor),
+              )
               .info(other, "previously defined here")
               .into());
         }
     }
 }
 
+/// Take the lint rules which are allowed through the `#[allow(...)]` attribute.
+fn take_allow(scope: &Scope, attributes: &mut Attributes) -> Result<BTreeSet<String>> {
+    let mut allow = BTreeSet::new();
+
+    if let Some(selection) = attributes.take_selection("allow") {
+        let (mut selection, _pos) = Loc::take_pair(selection);
+
+        for word in selection.take_words() {
+            let word = Loc::and_then(word, |w| w.as_identifier().map(|id| id.to_string()))?;
+            allow.insert(Loc::take(word));
+        }
+
+        check_selection!(scope.ctx(), selection);
+    }
+
+    Ok(allow)
+}
+
 /// Adds a method for all types that supports conversion into core types.
 pub trait IntoModel {
     type Output;
@@ -223,7 +245,8 @@ impl<'input> IntoModel for Item<'input, EnumBody<'input>> {
                 variants.push(Rc::new(variant));
             }
 
-            let attributes = attributes.into_model(scope)?;
+            let mut attributes = attributes.into_model(scope)?;
+            let allow = take_allow(scope, &mut attributes)?;
             check_attributes!(scope.ctx(), attributes);
 
             Ok(RpEnumBody {
@@ -231,6 +254,7 @@ impl<'input> IntoModel for Item<'input, EnumBody<'input>> {
                 ident: item.name.to_string(),
                 comment: Comment(&comment).into_model(scope)?,
                 decls: vec![],
+                allow: allow,
                 variant_type: variant_type,
                 variants: variants,
                 codes: codes,
@@ -326,7 +350,8 @@ impl<'input> IntoModel for Item<'input, Field<'input>> {
                 scope.field_ident_naming(),
             );
 
-            let attributes = attributes.into_model(scope)?;
+            let mut attributes = attributes.into_model(scope)?;
+            let allow = take_allow(scope, &mut attributes)?;
             check_attributes!(scope.ctx(), attributes);
 
             Ok(RpField {
@@ -336,6 +361,7 @@ impl<'input> IntoModel for Item<'input, Field<'input>> {
                 comment: Comment(&comment).into_model(scope)?,
                 ty: item.ty.into_model(scope)?,
                 field_as: field_as,
+                allow: allow,
             })
         })
     }
@@ -350,6 +376,7 @@ impl<'input> IntoModel for File<'input> {
         Ok(RpFile {
             comment: Comment(&self.comment).into_model(scope)?,
             decls: decls,
+            uses: scope.uses(),
         })
     }
 }
@@ -376,6 +403,7 @@ impl<'input> IntoModel for Item<'input, InterfaceBody<'input>> {
             }
 
             let mut attributes = attributes.into_model(scope)?;
+            let allow = take_allow(scope, &mut attributes)?;
 
             let mut sub_type_strategy = RpSubTypeStrategy::default();
 
@@ -391,6 +419,7 @@ impl<'input> IntoModel for Item<'input, InterfaceBody<'input>> {
                 ident: item.name.to_string(),
                 comment: Comment(&comment).into_model(scope)?,
                 decls: decls,
+                allow: allow,
                 fields: fields,
                 codes: codes,
                 sub_types: sub_types,
@@ -417,7 +446,7 @@ impl<'input> IntoModel for Item<'input, InterfaceBody<'input>> {
                         }
                         _ => {
                             return Err(ctx.report()
-                                .err(Loc::pos(&strategy), "bad strategy")
+                                .err(codes::BAD_STRATEGY, Loc::pos(&strategy), "bad strategy")
                                 .into());
                         }
                     }
@@ -429,20 +458,30 @@ impl<'input> IntoModel for Item<'input, InterfaceBody<'input>> {
     }
 }
 
-impl IntoModel for Name {
+impl IntoModel for Loc<Name> {
     type Output = RpName;
 
     fn into_model(self, scope: &Scope) -> Result<Self::Output> {
         use self::Name::*;
 
-        let out = match self {
+        let (name, pos) = Loc::take_pair(self);
+
+        let out = match name {
             Relative { parts } => scope.as_name().extend(parts),
             Absolute { prefix, parts } => {
                 let package = if let Some(ref prefix) = prefix {
                     if let Some(package) = scope.lookup_prefix(prefix) {
                         package.clone()
                     } else {
-                        return Err(Error::new(format!("Missing prefix: {}", prefix.clone())));
+                        return Err(scope
+                            .ctx()
+                            .report()
+                            .err(
+                                codes::MISSING_PREFIX,
+                                pos,
+                                format!("missing prefix: {}", prefix),
+                            )
+                            .into());
                     }
                 } else {
                     scope.package()
@@ -493,6 +532,7 @@ impl<'input> IntoModel for Item<'input, ServiceBody<'input>> {
             }
 
             let mut attributes = attributes.into_model(scope)?;
+            let allow = take_allow(scope, &mut attributes)?;
 
             let mut http = RpServiceBodyHttp::default();
 
@@ -509,6 +549,7 @@ impl<'input> IntoModel for Item<'input, ServiceBody<'input>> {
                 ident: item.name.to_string(),
                 comment: Comment(&comment).into_model(scope)?,
                 decls: decls,
+                allow: allow,
                 http: http,
                 endpoints: endpoints,
             })
@@ -547,7 +588,11 @@ impl<'input> IntoModel for Item<'input, ServiceBody<'input>> {
                 Vacant(entry) => entry.insert(endpoint),
                 Occupied(entry) => {
                     return Err(ctx.report()
-                        .err(Loc::pos(&endpoint), "conflicting id of endpoint")
+                        .err(
+                            codes::CONFLICTING_ENDPOINT_ID,
+                            Loc::pos(&endpoint),
+                            "conflicting id of endpoint",
+                        )
                         .info(Loc::pos(entry.get()), "previous id here")
                         .into());
                 }
@@ -588,7 +633,11 @@ impl<'input> IntoModel for Item<'input, Endpoint<'input>> {
                     }
                     linked_hash_map::Entry::Occupied(entry) => {
                         return Err(ctx.report()
-                            .err(Loc::pos(&name), "argument already present")
+                            .err(
+                                codes::DUPLICATE_ARGUMENT,
+                                Loc::pos(&name),
+                                "argument already present",
+                            )
                             .info(Loc::pos(&entry.get().0), "argument present here")
                             .into());
                     }
@@ -598,6 +647,7 @@ impl<'input> IntoModel for Item<'input, Endpoint<'input>> {
             let response = item.response.into_model(scope)?;
 
             let mut attributes = attributes.into_model(scope)?;
+            let allow = take_allow(scope, &mut attributes)?;
 
             let mut http = RpEndpointHttp::default();
 
@@ -627,6 +677,7 @@ impl<'input> IntoModel for Item<'input, Endpoint<'input>> {
                 arguments: arguments,
                 response: response,
                 http: http,
+                allow: allow,
             })
         });
 
@@ -647,7 +698,7 @@ impl<'input> IntoModel for Item<'input, Endpoint<'input>> {
 
             if let Some(path) = selection.take("path") {
                 let (path, pos) = Loc::take_pair(path);
-                http.path = Some(parse_path(scope, path, &mut unused_args).with_pos(pos)?);
+                http.path = Some(parse_path(scope, path, &pos, &mut unused_args)?);
             }
 
             if let Some(body) = selection.take("body") {
@@ -655,7 +706,13 @@ impl<'input> IntoModel for Item<'input, Endpoint<'input>> {
                 let body = body.as_identifier().with_pos(&pos)?;
 
                 if unused_args.remove(body).is_none() {
-                    return Err(format!("no such argument: {}", body).into()).with_pos(&pos);
+                    return Err(ctx.report()
+                        .err(
+                            codes::NO_SUCH_ARGUMENT,
+                            pos,
+                            format!("no such argument: {}", body),
+                        )
+                        .into());
                 }
 
                 http.body = Some(body.to_string());
@@ -663,17 +720,27 @@ impl<'input> IntoModel for Item<'input, Endpoint<'input>> {
 
             if let Some(method) = selection.take("method") {
                 let (method, pos) = Loc::take_pair(method);
-                http.method = Some(parse_method(method).with_pos(pos)?);
+                http.method = Some(parse_method(ctx, method, &pos)?);
             }
 
             if let Some(accept) = selection.take("accept") {
-                let accept = Loc::and_then(accept, |a| {
-                    a.as_string().and_then(|a| match a {
-                        "application/json" => Ok(RpAccept::Json),
-                        "text/plain" => Ok(RpAccept::Text),
-                        _ => Err("unsupported media type".into()),
-                    })
-                })?;
+                let (accept, pos) = Loc::take_pair(accept);
+
+                let accept = match accept.as_string().with_pos(&pos)? {
+                    "application/json" => RpAccept::Json,
+                    "text/plain" => RpAccept::Text,
+                    accept => {
+                        return Err(ctx.report()
+                            .err(
+                                codes::UNSUPPORTED_MEDIA_TYPE,
+                                pos,
+                                format!("unsupported media type: {}", accept),
+                            )
+                            .into());
+                    }
+                };
+
+                let accept = Loc::new(accept, pos);
 
                 http_verify_accept(ctx, &accept, response)?;
                 http.accept = Loc::take(accept);
@@ -684,8 +751,11 @@ impl<'input> IntoModel for Item<'input, Endpoint<'input>> {
                 let mut report = ctx.report();
 
                 for arg in unused_args.values() {
-                    report =
-                        report.err(Loc::pos(arg), "Argument not used in #[http(...)] attribute");
+                    report = report.err(
+                        codes::UNUSED_HTTP_ARGUMENT,
+                        Loc::pos(arg),
+                        "Argument not used in #[http(...)] attribute",
+                    );
                 }
 
                 return Err(report.into());
@@ -698,16 +768,26 @@ impl<'input> IntoModel for Item<'input, Endpoint<'input>> {
         fn parse_path(
             scope: &Scope,
             path: RpValue,
+            pos: &Pos,
             unused_args: &mut HashMap<&str, &Loc<String>>,
         ) -> Result<RpPathSpec> {
-            let path = path.as_string()?;
+            let path = path.as_string().with_pos(pos)?;
             let path = path_parser::parse(path)
-                .map_err(|e| format!("Bad path: {}: {}", path, e.display()))?;
-            let path = path.into_model(scope)?;
+                .map_err(|e| Error::from(format!("Bad path: {}: {}", path, e.display())))
+                .with_pos(pos)?;
+            let path = path.into_model(scope).with_pos(pos)?;
 
             for var in path.vars() {
                 if unused_args.remove(var).is_none() {
-                    return Err(format!("no such argument: {}", var).into());
+                    return Err(scope
+                        .ctx()
+                        .report()
+                        .err(
+                            codes::NO_SUCH_ARGUMENT,
+                            pos,
+                            format!("no such argument: {}", var),
+                        )
+                        .into());
                 }
             }
 
@@ -715,10 +795,10 @@ impl<'input> IntoModel for Item<'input, Endpoint<'input>> {
         }
 
         /// Parse a method.
-        fn parse_method(method: RpValue) -> Result<RpHttpMethod> {
+        fn parse_method(ctx: &Context, method: RpValue, pos: &Pos) -> Result<RpHttpMethod> {
             use self::RpHttpMethod::*;
 
-            let m = match method.as_string()? {
+            let m = match method.as_string().with_pos(pos)? {
                 "GET" => GET,
                 "POST" => POST,
                 "PUT" => PUT,
@@ -726,7 +806,15 @@ impl<'input> IntoModel for Item<'input, Endpoint<'input>> {
                 "DELETE" => DELETE,
                 "PATCH" => PATCH,
                 "HEAD" => HEAD,
-                method => return Err(format!("no such method: {}", method).into()),
+                method => {
+                    return Err(ctx.report()
+                        .err(
+                            codes::NO_SUCH_METHOD,
+                            pos,
+                            format!("no such method: {}", method),
+                        )
+                        .into());
+                }
             };
 
             Ok(m)
@@ -755,6 +843,7 @@ impl<'input> IntoModel for Item<'input, Endpoint<'input>> {
 
                     return Err(ctx.report()
                         .err(
+                            codes::UNSUPPORTED_RESPONSE,
                             Loc::pos(response),
                             "Only `string` responses are supported for the given `accept`",
                         )
@@ -820,7 +909,8 @@ impl<'input> IntoModel for Item<'input, SubType<'input>> {
 
             let sub_type_name = sub_type_name(item.alias, scope)?;
 
-            let attributes = attributes.into_model(scope)?;
+            let mut attributes = attributes.into_model(scope)?;
+            let allow = take_allow(scope, &mut attributes)?;
             check_attributes!(scope.ctx(), attributes);
 
             Ok(RpSubType {
@@ -828,6 +918,7 @@ impl<'input> IntoModel for Item<'input, SubType<'input>> {
                 ident: item.name.to_string(),
                 comment: Comment(&comment).into_model(scope)?,
                 decls: decls,
+                allow: allow,
                 fields: fields,
                 codes: codes,
                 sub_type_name: sub_type_name,
@@ -840,7 +931,11 @@ impl<'input> IntoModel for Item<'input, SubType<'input>> {
 
             match alias {
                 RpValue::String(string) => Ok(Loc::new(string, pos)),
-                _ => Err("expected string".into()).with_pos(pos),
+                _ => Err(scope
+                    .ctx()
+                    .report()
+                    .err(codes::EXPECTED_STRING, pos, "expected string")
+                    .into()),
             }
         }
 
@@ -864,7 +959,8 @@ impl<'input> IntoModel for Item<'input, TupleBody<'input>> {
         self.map(|comment, attributes, item| {
             let (fields, codes, decls) = item.members.into_model(scope)?;
 
-            let attributes = attributes.into_model(scope)?;
+            let mut attributes = attributes.into_model(scope)?;
+            let allow = take_allow(scope, &mut attributes)?;
             check_attributes!(scope.ctx(), attributes);
 
             Ok(RpTupleBody {
@@ -872,6 +968,7 @@ impl<'input> IntoModel for Item<'input, TupleBody<'input>> {
                 ident: item.name.to_string(),
                 comment: Comment(&comment).into_model(scope)?,
                 decls: decls,
+                allow: allow,
                 fields: fields,
                 codes: codes,
             })
@@ -888,6 +985,7 @@ impl<'input> IntoModel for Item<'input, TypeBody<'input>> {
 
             let mut reserved: HashSet<Loc<String>> = HashSet::new();
             let mut attributes = attributes.into_model(scope)?;
+            let allow = take_allow(scope, &mut attributes)?;
 
             if let Some(selection) = attributes.take_selection("reserved") {
                 let (mut selection, _pos) = Loc::take_pair(selection);
@@ -908,6 +1006,7 @@ impl<'input> IntoModel for Item<'input, TypeBody<'input>> {
                 ident: item.name.to_string(),
                 comment: Comment(&comment).into_model(scope)?,
                 decls: decls,
+                allow: allow,
                 fields: fields,
                 codes: codes,
                 reserved: reserved,
@@ -938,7 +1037,7 @@ impl<'input> IntoModel for Vec<TypeMember<'input>> {
                         .find(|f| f.name() == field.name() || f.ident() == field.ident())
                     {
                         return Err(ctx.report()
-                            .err(Loc::pos(&field), "conflict in field")
+                            .err(codes::CONFLICTING_FIELD, Loc::pos(&field), "conflict in field")
                             .info(Loc::pos(other), "previous declaration here")
                             .into());
                     }
@@ -1002,7 +1101,7 @@ impl<'input> IntoModel for Vec<Loc<Attribute<'input>>> {
 
                     if let Some(old) = words.insert(word, pos.clone()) {
                         return Err(ctx.report()
-                            .err(pos, "word already present")
+                            .err(codes::DUPLICATE_ATTRIBUTE_WORD, pos, "word already present")
                             .info(old, "old attribute here")
                             .into());
                     }
@@ -1033,7 +1132,11 @@ impl<'input> IntoModel for Vec<Loc<Attribute<'input>>> {
                         }
                         hash_map::Entry::Occupied(entry) => {
                             return Err(ctx.report()
-                                .err(attr_pos, "attribute already present")
+                                .err(
+                                    codes::DUPLICATE_ATTRIBUTE,
+                                    attr_pos,
+                                    "attribute already present",
+                                )
                                 .info(Loc::pos(entry.get()), "attribute here")
                                 .into());
                         }
@@ -3,7 +3,7 @@
 use core::{Context, RpName, RpPackage, RpVersionedPackage};
 use core::errors::{Error, Result};
 use naming::Naming;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::rc::Rc;
 
 /// Root of the scope.
@@ -125,6 +125,18 @@ impl Scope {
         root.prefixes.get(prefix).map(|p| root.package(p.clone()))
     }
 
+    /// Get every package imported through `use` declarations, sorted.
+    pub fn uses(&self) -> Vec<RpVersionedPackage> {
+        let root = self.root();
+
+        let uses = root.prefixes
+            .values()
+            .map(|p| root.package(p.clone()))
+            .collect::<BTreeSet<_>>();
+
+        uses.into_iter().collect()
+    }
+
     /// Get the package that this scope belongs to.
     pub fn package(&self) -> RpVersionedPackage {
         let root = self.root();
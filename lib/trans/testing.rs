@@ -0,0 +1,35 @@
+//! Helpers to load specifications in tests, without a filesystem or a resolver.
+
+use core::{BytesObject, CapturingFilesystem, Context, EmptyResolver, RpFile, RpPackage,
+           RpVersionedPackage};
+use environment::Environment;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// The package that specifications are loaded into.
+pub fn package() -> RpVersionedPackage {
+    RpVersionedPackage::new(RpPackage::parse("test"), None)
+}
+
+/// Build an environment with the given specification imported as the `test` package.
+pub fn environment(spec: &str) -> Environment {
+    let mut env = Environment::new(context(), None, Box::new(EmptyResolver));
+    env.import_object(&object(spec), Some(package()))
+        .expect("to import specification");
+    env
+}
+
+/// Load the given specification into a file, without registering it in an environment.
+pub fn file(spec: &str) -> RpFile {
+    let mut env = Environment::new(context(), None, Box::new(EmptyResolver));
+    env.load_object(&object(spec), &package())
+        .expect("to load specification")
+}
+
+fn context() -> Rc<Context> {
+    Rc::new(Context::new(Box::new(CapturingFilesystem::new())))
+}
+
+fn object(spec: &str) -> BytesObject {
+    BytesObject::new("test".to_string(), Arc::new(spec.as_bytes().to_vec()))
+}
@@ -0,0 +1,576 @@
+//! Validator for JSON payloads.
+//!
+//! Walks a JSON value against the declarations loaded in an `Environment`, and collects every
+//! place where the payload doesn't match the specification. Each violation is identified by a
+//! [JSON pointer] into the payload.
+//!
+//! [JSON pointer]: https://tools.ietf.org/html/rfc6901
+
+extern crate reproto_core as core;
+extern crate reproto_trans as trans;
+extern crate serde_json;
+
+use core::{Loc, RpEnumBody, RpField, RpInterfaceBody, RpName, RpReg, RpSubType,
+           RpSubTypeStrategy, RpTupleBody, RpType, RpTypeBody};
+use core::errors::*;
+use serde_json::Value;
+use serde_json::map::Map;
+use std::fmt;
+use trans::Environment;
+
+/// A single place where the payload does not match the specification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// JSON pointer to the offending value.
+    pub pointer: String,
+    /// Description of what's wrong.
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        if self.pointer.is_empty() {
+            write!(fmt, "(root): {}", self.message)
+        } else {
+            write!(fmt, "{}: {}", self.pointer, self.message)
+        }
+    }
+}
+
+/// Validates JSON values against declarations in an environment.
+pub struct Validator<'a> {
+    env: &'a Environment,
+}
+
+impl<'a> Validator<'a> {
+    pub fn new(env: &'a Environment) -> Validator<'a> {
+        Validator { env: env }
+    }
+
+    /// Validate the given value against the declaration with the given name.
+    ///
+    /// Returns all violations found, which is empty if the value is valid.
+    pub fn validate(&self, name: &RpName, value: &Value) -> Result<Vec<Violation>> {
+        self.validate_type(&RpType::Name { name: name.clone() }, value)
+    }
+
+    /// Validate the given value against the given type.
+    pub fn validate_type(&self, ty: &RpType, value: &Value) -> Result<Vec<Violation>> {
+        let mut walk = Walk {
+            path: Vec::new(),
+            violations: Vec::new(),
+        };
+
+        self.ty(&mut walk, ty, value)?;
+        Ok(walk.violations)
+    }
+
+    fn ty(&self, walk: &mut Walk, ty: &RpType, value: &Value) -> Result<()> {
+        use core::RpType::*;
+
+        match (ty, value) {
+            (&Any, _) => {}
+            (&Double, &Value::Number(_)) | (&Float, &Value::Number(_)) => {}
+            (&Signed { size }, &Value::Number(ref n)) => match n.as_i64() {
+                Some(n) if size > 32 || n == n as i32 as i64 => {}
+                Some(n) => walk.violation(format!("{} is out of range for i{}", n, size)),
+                None => walk.violation(format!("expected i{}, but got {}", size, n)),
+            },
+            (&Unsigned { size }, &Value::Number(ref n)) => match n.as_u64() {
+                Some(n) if size > 32 || n == n as u32 as u64 => {}
+                Some(n) => walk.violation(format!("{} is out of range for u{}", n, size)),
+                None => walk.violation(format!("expected u{}, but got {}", size, n)),
+            },
+            (&Boolean, &Value::Bool(_)) => {}
+            (&String, &Value::String(_)) => {}
+            (&DateTime, &Value::String(ref s)) => {
+                if !is_datetime(s) {
+                    walk.violation(format!("expected ISO-8601 datetime, but got {:?}", s));
+                }
+            }
+            (&Bytes, &Value::String(ref s)) => {
+                if !is_base64(s) {
+                    walk.violation("expected base64-encoded bytes");
+                }
+            }
+            (&Array { ref inner }, &Value::Array(ref values)) => {
+                for (index, value) in values.iter().enumerate() {
+                    walk.path.push(index.to_string());
+                    self.ty(walk, inner, value)?;
+                    walk.path.pop();
+                }
+            }
+            (&Map { ref key, value: ref value_ty }, &Value::Object(ref entries)) => {
+                for (k, v) in entries {
+                    walk.path.push(k.to_string());
+
+                    if !is_valid_key(key, k) {
+                        walk.violation(format!("key {:?} is not a valid {}", k, key));
+                    }
+
+                    self.ty(walk, value_ty, v)?;
+                    walk.path.pop();
+                }
+            }
+            (&Name { ref name }, value) => {
+                let reg = self.env.lookup(name)?;
+                self.reg(walk, reg, value)?;
+            }
+            (ty, value) => walk.unexpected(ty, value),
+        }
+
+        Ok(())
+    }
+
+    fn reg(&self, walk: &mut Walk, reg: &RpReg, value: &Value) -> Result<()> {
+        use core::RpReg::*;
+
+        match *reg {
+            Type(ref body) => self.type_body(walk, body, value),
+            Tuple(ref body) => self.tuple_body(walk, body, value),
+            Interface(ref body) => self.interface_body(walk, body, None, value),
+            SubType(ref body, ref sub_type) => {
+                self.interface_body(walk, body, Some(&**sub_type), value)
+            }
+            Enum(ref body) => {
+                self.enum_body(walk, body, value);
+                Ok(())
+            }
+            EnumVariant(ref body, ref variant) => {
+                match value.as_str() {
+                    Some(ordinal) if ordinal == variant.ordinal() => {}
+                    _ => walk.violation(format!(
+                        "expected variant `{}` of {} ({:?})",
+                        variant.ident.as_str(),
+                        body.name,
+                        variant.ordinal()
+                    )),
+                }
+
+                Ok(())
+            }
+            Service(ref body) => Err(format!("{}: services can't be validated", body.name).into()),
+        }
+    }
+
+    fn type_body(&self, walk: &mut Walk, body: &RpTypeBody, value: &Value) -> Result<()> {
+        let object = match *value {
+            Value::Object(ref object) => object,
+            ref value => {
+                walk.unexpected(format!("object for {}", body.name), value);
+                return Ok(());
+            }
+        };
+
+        self.fields(walk, body.fields.iter(), object, None)
+    }
+
+    fn tuple_body(&self, walk: &mut Walk, body: &RpTupleBody, value: &Value) -> Result<()> {
+        let values = match *value {
+            Value::Array(ref values) => values,
+            ref value => {
+                walk.unexpected(format!("array for {}", body.name), value);
+                return Ok(());
+            }
+        };
+
+        if values.len() != body.fields.len() {
+            walk.violation(format!(
+                "expected {} element(s) for {}, but got {}",
+                body.fields.len(),
+                body.name,
+                values.len()
+            ));
+
+            return Ok(());
+        }
+
+        for (index, (field, value)) in body.fields.iter().zip(values.iter()).enumerate() {
+            walk.path.push(index.to_string());
+
+            match *value {
+                Value::Null if field.is_optional() => {}
+                Value::Null => walk.violation(format!("missing required field `{}`", field.name())),
+                ref value => self.ty(walk, &field.ty, value)?,
+            }
+
+            walk.path.pop();
+        }
+
+        Ok(())
+    }
+
+    fn interface_body(
+        &self,
+        walk: &mut Walk,
+        body: &RpInterfaceBody,
+        expected: Option<&Loc<RpSubType>>,
+        value: &Value,
+    ) -> Result<()> {
+        let object = match *value {
+            Value::Object(ref object) => object,
+            ref value => {
+                walk.unexpected(format!("object for {}", body.name), value);
+                return Ok(());
+            }
+        };
+
+        let tag = match body.sub_type_strategy {
+            RpSubTypeStrategy::Tagged { ref tag } => tag.as_str(),
+        };
+
+        let name = match object.get(tag) {
+            Some(&Value::String(ref name)) => name.as_str(),
+            Some(value) => {
+                walk.path.push(tag.to_string());
+                walk.unexpected("string tag", value);
+                walk.path.pop();
+                return Ok(());
+            }
+            None => {
+                walk.violation(format!("missing tag `{}` for {}", tag, body.name));
+                return Ok(());
+            }
+        };
+
+        let sub_type = match body.sub_types.iter().find(|s| s.name() == name) {
+            Some(sub_type) => sub_type,
+            None => {
+                let names: Vec<_> = body.sub_types
+                    .iter()
+                    .map(|s| format!("{:?}", s.name()))
+                    .collect();
+
+                walk.path.push(tag.to_string());
+                walk.violation(format!(
+                    "unknown sub-type {:?} for {}, expected one of: {}",
+                    name,
+                    body.name,
+                    names.join(", ")
+                ));
+                walk.path.pop();
+                return Ok(());
+            }
+        };
+
+        if let Some(expected) = expected {
+            if expected.name() != sub_type.name() {
+                walk.path.push(tag.to_string());
+                walk.violation(format!(
+                    "expected sub-type {:?}, but got {:?}",
+                    expected.name(),
+                    name
+                ));
+                walk.path.pop();
+                return Ok(());
+            }
+        }
+
+        let fields = body.fields.iter().chain(sub_type.fields.iter());
+        self.fields(walk, fields, object, Some(tag))
+    }
+
+    fn enum_body(&self, walk: &mut Walk, body: &RpEnumBody, value: &Value) {
+        let ordinal = match *value {
+            Value::String(ref ordinal) => ordinal.as_str(),
+            ref value => {
+                walk.unexpected(format!("string for {}", body.name), value);
+                return;
+            }
+        };
+
+        if body.variants.iter().any(|v| v.ordinal() == ordinal) {
+            return;
+        }
+
+        let ordinals: Vec<_> = body.variants
+            .iter()
+            .map(|v| format!("{:?}", v.ordinal()))
+            .collect();
+
+        walk.violation(format!(
+            "{:?} is not a variant of {}, expected one of: {}",
+            ordinal,
+            body.name,
+            ordinals.join(", ")
+        ));
+    }
+
+    /// Validate the fields of an object.
+    ///
+    /// `tag` is a key which is permitted in addition to the fields.
+    fn fields<'f, I>(
+        &self,
+        walk: &mut Walk,
+        fields: I,
+        object: &Map<::std::string::String, Value>,
+        tag: Option<&str>,
+    ) -> Result<()>
+    where
+        I: IntoIterator<Item = &'f Loc<RpField>>,
+    {
+        let fields: Vec<_> = fields.into_iter().collect();
+
+        for field in &fields {
+            match object.get(field.name()) {
+                None | Some(&Value::Null) if field.is_optional() => {}
+                None | Some(&Value::Null) => {
+                    walk.violation(format!("missing required field `{}`", field.name()))
+                }
+                Some(value) => {
+                    walk.path.push(field.name().to_string());
+                    self.ty(walk, &field.ty, value)?;
+                    walk.path.pop();
+                }
+            }
+        }
+
+        for key in object.keys() {
+            if Some(key.as_str()) == tag || fields.iter().any(|f| f.name() == key) {
+                continue;
+            }
+
+            walk.path.push(key.to_string());
+
+            // hint at the name used on the wire, when the identifier of a field is used instead.
+            match fields.iter().find(|f| f.ident() == key) {
+                Some(field) => walk.violation(format!(
+                    "unknown field `{}`, the field is named `{}`",
+                    key,
+                    field.name()
+                )),
+                None => walk.violation(format!("unknown field `{}`", key)),
+            }
+
+            walk.path.pop();
+        }
+
+        Ok(())
+    }
+}
+
+/// State of walking a value.
+struct Walk {
+    /// Current path into the value, in unescaped segments.
+    path: Vec<String>,
+    violations: Vec<Violation>,
+}
+
+impl Walk {
+    /// Record a violation at the current path.
+    fn violation<M: Into<String>>(&mut self, message: M) {
+        let mut pointer = String::new();
+
+        for segment in &self.path {
+            pointer.push('/');
+            pointer.push_str(&segment.replace("~", "~0").replace("/", "~1"));
+        }
+
+        self.violations.push(Violation {
+            pointer: pointer,
+            message: message.into(),
+        });
+    }
+
+    /// Record that a value of the wrong kind was encountered at the current path.
+    fn unexpected<E: fmt::Display>(&mut self, expected: E, value: &Value) {
+        self.violation(format!("expected {}, but got {}", expected, kind(value)));
+    }
+}
+
+/// Describe the kind of the given value.
+fn kind(value: &Value) -> &'static str {
+    match *value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Check if the given key is valid for a map with keys of the given type.
+///
+/// Keys are always strings in JSON, numeric keys are encoded as strings.
+fn is_valid_key(ty: &RpType, key: &str) -> bool {
+    use core::RpType::*;
+
+    match *ty {
+        Signed { .. } => key.parse::<i64>().is_ok(),
+        Unsigned { .. } => key.parse::<u64>().is_ok(),
+        Boolean => key == "true" || key == "false",
+        DateTime => is_datetime(key),
+        _ => true,
+    }
+}
+
+/// Check if the given string is an ISO-8601 datetime, like `2017-12-31T23:59:59.999Z`.
+fn is_datetime(input: &str) -> bool {
+    let mut it = input.chars().peekable();
+
+    return digits(&mut it, 4) && expect(&mut it, '-') && digits(&mut it, 2) &&
+        expect(&mut it, '-') && digits(&mut it, 2) && expect(&mut it, 'T') &&
+        digits(&mut it, 2) && expect(&mut it, ':') && digits(&mut it, 2) &&
+        expect(&mut it, ':') && digits(&mut it, 2) && fraction(&mut it) && offset(&mut it) &&
+        it.next().is_none();
+
+    fn digits<I: Iterator<Item = char>>(it: &mut I, count: usize) -> bool {
+        (0..count).all(|_| it.next().map(|c| c.is_digit(10)).unwrap_or(false))
+    }
+
+    fn expect<I: Iterator<Item = char>>(it: &mut I, expected: char) -> bool {
+        it.next() == Some(expected)
+    }
+
+    fn fraction<I: Iterator<Item = char>>(it: &mut ::std::iter::Peekable<I>) -> bool {
+        if it.peek() != Some(&'.') {
+            return true;
+        }
+
+        it.next();
+
+        let mut count = 0;
+
+        while it.peek().map(|c| c.is_digit(10)).unwrap_or(false) {
+            it.next();
+            count += 1;
+        }
+
+        count > 0
+    }
+
+    fn offset<I: Iterator<Item = char>>(it: &mut I) -> bool {
+        match it.next() {
+            Some('Z') => true,
+            Some('+') | Some('-') => digits(it, 2) && expect(it, ':') && digits(it, 2),
+            _ => false,
+        }
+    }
+}
+
+/// Check if the given string is valid, padded base64.
+fn is_base64(input: &str) -> bool {
+    if input.len() % 4 != 0 {
+        return false;
+    }
+
+    let data = input.trim_right_matches('=');
+
+    if input.len() - data.len() > 2 {
+        return false;
+    }
+
+    data.chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '/')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use trans::testing::{environment, package};
+
+    const SPEC: &str = r#"
+    type Foo {
+      id: u32;
+      name?: string as "display_name";
+      tags?: [string];
+    }
+
+    tuple Pair {
+      a: i32;
+      b: string;
+    }
+
+    enum Color as string {
+      Red as "red";
+      Green as "green";
+    }
+
+    interface Shape {
+      name: string;
+
+      Circle as "circle" {
+        radius: double;
+      }
+    }
+    "#;
+
+    fn validate(name: &str, value: Value) -> Vec<Violation> {
+        let env = environment(SPEC);
+        let name = RpName::new(None, package(), vec![name.to_string()]);
+
+        Validator::new(&env)
+            .validate(&name, &value)
+            .expect("to validate")
+    }
+
+    fn pointers(violations: Vec<Violation>) -> Vec<String> {
+        violations.into_iter().map(|v| v.pointer).collect()
+    }
+
+    #[test]
+    fn test_type() {
+        let value = serde_json::from_str(r#"{"id": 42, "display_name": "foo"}"#).unwrap();
+        assert_eq!(Vec::<Violation>::new(), validate("Foo", value));
+
+        let value = serde_json::from_str(r#"{"name": "foo", "tags": ["a", 1]}"#).unwrap();
+        assert_eq!(vec!["", "/tags/1", "/name"], pointers(validate("Foo", value)));
+
+        let value = serde_json::from_str(r#"{"id": 4294967296}"#).unwrap();
+        assert_eq!(vec!["/id"], pointers(validate("Foo", value)));
+    }
+
+    #[test]
+    fn test_tuple() {
+        let value = serde_json::from_str(r#"[1, "b"]"#).unwrap();
+        assert_eq!(Vec::<Violation>::new(), validate("Pair", value));
+
+        let value = serde_json::from_str(r#"[1]"#).unwrap();
+        assert_eq!(vec![""], pointers(validate("Pair", value)));
+
+        let value = serde_json::from_str(r#"["a", "b"]"#).unwrap();
+        assert_eq!(vec!["/0"], pointers(validate("Pair", value)));
+    }
+
+    #[test]
+    fn test_enum() {
+        let value = serde_json::from_str(r#""green""#).unwrap();
+        assert_eq!(Vec::<Violation>::new(), validate("Color", value));
+
+        let value = serde_json::from_str(r#""Green""#).unwrap();
+        assert_eq!(vec![""], pointers(validate("Color", value)));
+    }
+
+    #[test]
+    fn test_interface() {
+        let value = r#"{"type": "circle", "name": "c", "radius": 1.5}"#;
+        let value = serde_json::from_str(value).unwrap();
+        assert_eq!(Vec::<Violation>::new(), validate("Shape", value));
+
+        let value = serde_json::from_str(r#"{"type": "square", "name": "c"}"#).unwrap();
+        assert_eq!(vec!["/type"], pointers(validate("Shape", value)));
+
+        let value = serde_json::from_str(r#"{"name": "c"}"#).unwrap();
+        assert_eq!(vec![""], pointers(validate("Shape", value)));
+    }
+
+    #[test]
+    fn test_datetime() {
+        assert!(is_datetime("2017-12-31T23:59:59Z"));
+        assert!(is_datetime("2017-12-31T23:59:59.999+01:00"));
+        assert!(!is_datetime("2017-12-31"));
+        assert!(!is_datetime("2017-12-31T23:59:59"));
+    }
+
+    #[test]
+    fn test_pointer_escape() {
+        let mut walk = Walk {
+            path: vec!["a/b".to_string(), "c~d".to_string()],
+            violations: Vec::new(),
+        };
+
+        walk.violation("bad");
+        assert_eq!("/a~1b/c~0d", walk.violations[0].pointer);
+    }
+}
@@ -15,6 +15,12 @@ pub struct Config {
     /// Max file size permitted during upload.
     #[serde(default = "default_max_file_size")]
     pub max_file_size: u64,
+    /// Path to a local checkout of the index, used to determine which objects are referenced.
+    pub index: Option<PathBuf>,
+    /// Token required to perform administrative operations.
+    ///
+    /// Administrative operations are disabled if not set.
+    pub admin_token: Option<String>,
 }
 
 fn default_listen_address() -> String {
@@ -35,6 +41,8 @@ impl Default for Config {
             listen_address: default_listen_address(),
             objects: default_objects(),
             max_file_size: default_max_file_size(),
+            index: None,
+            admin_token: None,
         }
     }
 }
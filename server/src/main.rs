@@ -40,6 +40,8 @@ fn entry() -> Result<()> {
     let listen_address = config.listen_address.parse()?;
     let objects = config.objects;
     let max_file_size = config.max_file_size;
+    let index = config.index;
+    let admin_token = config.admin_token;
 
     let pool = Arc::new(CpuPool::new_num_cpus());
     let setup_pool = pool.clone();
@@ -50,6 +52,8 @@ fn entry() -> Result<()> {
             max_file_size: max_file_size,
             pool: setup_pool.clone(),
             objects: objects.clone(),
+            index: index.clone(),
+            admin_token: admin_token.clone(),
         })
     };
 
@@ -3,14 +3,17 @@ use flate2::FlateReadExt;
 use futures::future::{ok, Future};
 use futures_cpupool::CpuPool;
 use hyper::{self, Method, StatusCode};
-use hyper::header::{ContentEncoding, ContentLength, ContentType, Encoding, Headers};
+use hyper::header::{Authorization, Bearer, ContentEncoding, ContentLength, ContentType, Encoding,
+                    Headers};
 use hyper::mime;
 use hyper::server::{Request, Response, Service};
 use io;
-use reproto_repository::{to_checksum, Checksum, FileObjects, Objects};
+use reproto_repository::{index_from_path, referenced_objects, to_checksum, Checksum, FileObjects,
+                         Objects};
 use std::fs::File;
 use std::io::{Seek, SeekFrom};
 use std::io::Read;
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use tempfile;
 
@@ -28,6 +31,10 @@ pub struct ReprotoService {
     pub max_file_size: u64,
     pub pool: Arc<CpuPool>,
     pub objects: Arc<Mutex<FileObjects>>,
+    /// Path to the index, used to determine which objects are referenced.
+    pub index: Option<PathBuf>,
+    /// Token required for administrative operations.
+    pub admin_token: Option<String>,
 }
 
 type EncodingFn = fn(&File) -> Result<Box<Read>>;
@@ -173,6 +180,79 @@ impl ReprotoService {
         Ok(self.put_uploaded_object(body, checksum, encoding))
     }
 
+    /// Check that the request is authorized to perform administrative operations.
+    fn is_admin(&self, req: &Request) -> bool {
+        let token = match self.admin_token {
+            Some(ref token) => token,
+            None => return false,
+        };
+
+        req.headers()
+            .get::<Authorization<Bearer>>()
+            .map(|auth| auth.0.token == *token)
+            .unwrap_or(false)
+    }
+
+    /// Remove all objects which are no longer referenced by the index.
+    fn admin_gc(&self) -> Result<Box<Future<Item = Response, Error = Error>>> {
+        let index = match self.index {
+            Some(ref index) => index.clone(),
+            None => return Err(Error::BadRequest("no index configured")),
+        };
+
+        let objects = self.objects.clone();
+
+        Ok(Box::new(self.pool.spawn_fn(move || {
+            let index = index_from_path(&index)?;
+            let referenced = referenced_objects(&index)?;
+
+            let gc = objects
+                .lock()
+                .map_err(|_| "lock poisoned")?
+                .gc(&referenced)?;
+
+            info!(
+                "gc: removed {} object(s), reclaimed {} byte(s)",
+                gc.objects, gc.reclaimed
+            );
+
+            let body = format!(
+                "removed {} object(s), reclaimed {} byte(s)\n",
+                gc.objects, gc.reclaimed
+            );
+
+            Ok(Response::new()
+                .with_status(StatusCode::Ok)
+                .with_header(ContentLength(body.len() as u64))
+                .with_header(ContentType(mime::TEXT_PLAIN))
+                .with_body(body))
+        })))
+    }
+
+    fn admin<'a, I>(
+        &self,
+        req: Request,
+        path: I,
+    ) -> Result<Box<Future<Item = Response, Error = Error>>>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        if self.admin_token.is_none() {
+            return Ok(Box::new(ok(Self::not_found())));
+        }
+
+        if !self.is_admin(&req) {
+            return Ok(Box::new(ok(
+                Response::new().with_status(StatusCode::Unauthorized),
+            )));
+        }
+
+        match path.into_iter().next() {
+            Some("gc") => self.admin_gc(),
+            _ => Ok(Box::new(ok(Self::not_found()))),
+        }
+    }
+
     fn inner_call<'a, I>(
         &self,
         req: Request,
@@ -187,6 +267,7 @@ impl ReprotoService {
             match (req.method(), part) {
                 (&Method::Get, "objects") => return self.get_objects(it),
                 (&Method::Put, "objects") => return self.put_objects(req, it),
+                (&Method::Post, "admin") => return self.admin(req, it),
                 _ => return Ok(Box::new(ok(Self::not_found()))),
             }
         }
@@ -222,6 +222,51 @@ impl Reproto {
 
         Ok(())
     }
+
+    /// Generate sample payloads for the given declaration, one JSON document per line.
+    pub fn sample(&self, manifest: Manifest, name: &str, samples: &Samples) -> Result<Vec<String>> {
+        let mut cmd = Command::new(&self.binary);
+
+        cmd.arg("sample");
+        cmd.args(&["--type", name]);
+        cmd.args(&["--count", samples.count.to_string().as_str()]);
+        cmd.args(&["--seed", samples.seed.to_string().as_str()]);
+        // Disable using local repository.
+        cmd.arg("--no-repository");
+        // Path to resolve packages from.
+        cmd.args(&["--path", manifest.path.display().to_string().as_str()]);
+
+        let output = cmd.current_dir(manifest.current_dir)
+            .output()
+            .map_err(|e| format_err!("bad exit status: {}", e))?;
+
+        if !output.status.success() {
+            let stderr = str::from_utf8(&output.stderr)?;
+
+            bail!(
+                "failed to sample `{}` (seed: {}): {}: {}:\nstderr: {}",
+                name,
+                samples.seed,
+                manifest.current_dir.display(),
+                output.status,
+                stderr,
+            );
+        }
+
+        Ok(str::from_utf8(&output.stdout)?
+            .lines()
+            .map(|line| line.to_string())
+            .collect())
+    }
+}
+
+/// Randomly generated payloads to round-trip through projects.
+#[derive(Debug, Clone)]
+pub struct Samples {
+    /// Number of payloads to generate.
+    pub count: u64,
+    /// Seed to generate payloads from.
+    pub seed: u64,
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
@@ -367,6 +412,10 @@ pub struct ProjectRunner<'a> {
     packages: Vec<String>,
     /// Inputs to feed to the project.
     inputs: Vec<PathBuf>,
+    /// Declaration to generate sample payloads for.
+    sample: String,
+    /// Sample payloads to generate, if any.
+    samples: Option<Samples>,
     /// Source directory from where to build project.
     source_workdir: PathBuf,
     /// Target directory to build project.
@@ -431,6 +480,13 @@ impl<'a> ProjectRunner<'a> {
             bail!("missing script.sh entrypoint: {}", script.display());
         }
 
+        let mut lines = read_json_inputs(&self.inputs)?;
+
+        // round-trip randomly generated payloads, in addition to the fixed inputs.
+        if let Some(ref samples) = self.samples {
+            lines.extend(self.reproto.sample(self.manifest(), &self.sample, samples)?);
+        }
+
         let mut child = Command::new(script)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
@@ -443,7 +499,7 @@ impl<'a> ProjectRunner<'a> {
 
         {
             let stdin = child.stdin.take().ok_or_else(|| format_err!("no stdin"))?;
-            expected.extend(write_json_inputs(&self.inputs, stdin)?);
+            expected.extend(write_json_inputs(&lines, stdin)?);
 
             let stdout = child.stdout.take().ok_or_else(|| format_err!("no stdout"))?;
 
@@ -494,12 +550,9 @@ impl<'a> ProjectRunner<'a> {
 
         return Ok(());
 
-        /// Write inputs to the stdin of the process and collect expected documents.
-        fn write_json_inputs<W>(inputs: &[PathBuf], mut stdin: W) -> Result<Vec<json::Value>>
-        where
-            W: io::Write,
-        {
-            let mut expected = Vec::new();
+        /// Read all input documents from the given files.
+        fn read_json_inputs(inputs: &[PathBuf]) -> Result<Vec<String>> {
+            let mut lines = Vec::new();
 
             for input in inputs {
                 let f = File::open(&input).map_err(|e| format_err!("{}: {}", input.display(), e))?;
@@ -517,11 +570,25 @@ impl<'a> ProjectRunner<'a> {
                         continue;
                     }
 
-                    expected.push(json::from_str(&line)?);
-                    writeln!(stdin, "{}", line)?;
+                    lines.push(line);
                 }
             }
 
+            Ok(lines)
+        }
+
+        /// Write inputs to the stdin of the process and collect expected documents.
+        fn write_json_inputs<W>(lines: &[String], mut stdin: W) -> Result<Vec<json::Value>>
+        where
+            W: io::Write,
+        {
+            let mut expected = Vec::new();
+
+            for line in lines {
+                expected.push(json::from_str(line)?);
+                writeln!(stdin, "{}", line)?;
+            }
+
             Ok(expected)
         }
 
@@ -764,6 +831,8 @@ pub struct Suite<'a> {
     proto: Vec<RelativePathBuf>,
     inputs: Vec<RelativePathBuf>,
     arguments: HashMap<Language, &'a [&'a str]>,
+    /// Declaration to generate sample payloads for.
+    sample: String,
     /// Build the given packages.
     packages: Vec<String>,
     /// Extract suite from the given directory.
@@ -779,6 +848,7 @@ impl<'a> Suite<'a> {
             proto: Vec::new(),
             inputs: Vec::new(),
             arguments: HashMap::new(),
+            sample: "test::Entry".to_string(),
             packages: vec!["test".to_string()],
             dir: None,
             include: HashSet::new(),
@@ -814,6 +884,11 @@ impl<'a> Suite<'a> {
     pub fn input<P: AsRef<RelativePath>>(&mut self, path: P) {
         self.inputs.push(path.as_ref().to_owned());
     }
+
+    /// Set the declaration to generate sample payloads for.
+    pub fn sample<S: AsRef<str>>(&mut self, name: S) {
+        self.sample = name.as_ref().to_string();
+    }
 }
 
 #[derive(Debug)]
@@ -854,6 +929,8 @@ pub struct Project<'a> {
     ///
     /// By default only a single instance named `default` is used.
     instances: HashMap<Language, Vec<Instance>>,
+    /// Sample payloads to round-trip through every project.
+    samples: Option<Samples>,
 }
 
 impl<'a> Project<'a> {
@@ -873,6 +950,7 @@ impl<'a> Project<'a> {
             suites: Vec::new(),
             arguments: HashMap::new(),
             instances: HashMap::new(),
+            samples: None,
         }
     }
 
@@ -889,6 +967,11 @@ impl<'a> Project<'a> {
         self.arguments.insert(lang, args);
     }
 
+    /// Round-trip randomly generated payloads through every project.
+    pub fn samples(&mut self, samples: Samples) {
+        self.samples = Some(samples);
+    }
+
     /// Hook up another suite.
     pub fn suite(&mut self, suite: Suite<'a>) {
         self.suites.push(suite);
@@ -956,6 +1039,8 @@ impl<'a> Project<'a> {
                             path: path.clone(),
                             packages: suite.packages.clone(),
                             inputs: inputs.clone(),
+                            sample: suite.sample.clone(),
+                            samples: self.samples.clone(),
                             source_workdir: source_workdir,
                             target_workdir: target_workdir,
                             current_dir: current_dir.clone(),
@@ -2,9 +2,11 @@
 extern crate failure;
 extern crate it;
 extern crate rayon;
+extern crate reproto_sample as sample;
 
-use it::{Action, Instance, Language, Project, Reproto, Result};
+use it::{Action, Instance, Language, Project, Reproto, Result, Samples};
 use rayon::prelude::*;
+use sample::Sampler;
 use std::collections::HashSet;
 use std::env;
 use std::path::PathBuf;
@@ -71,6 +73,8 @@ fn try_main() -> Result<()> {
     let mut do_project = false;
     let mut action = Action::Verify;
     let mut filters = HashSet::new();
+    let mut samples = None;
+    let mut seed = None;
 
     while let Some(opt) = args.next() {
         match opt.as_str() {
@@ -83,6 +87,18 @@ fn try_main() -> Result<()> {
             "--project" => {
                 do_project = true;
             }
+            "--samples" => {
+                let arg = args.next()
+                    .ok_or_else(|| format_err!("expected argument to `--samples`"))?;
+                samples = Some(arg.parse::<u64>()
+                    .map_err(|e| format_err!("bad argument to `--samples`: {}", e))?);
+            }
+            "--seed" => {
+                let arg = args.next()
+                    .ok_or_else(|| format_err!("expected argument to `--seed`"))?;
+                seed = Some(arg.parse::<u64>()
+                    .map_err(|e| format_err!("bad argument to `--seed`: {}", e))?);
+            }
             "--root" => {
                 let arg = args.next()
                     .ok_or_else(|| format_err!("expected argument to `--root`"))?;
@@ -119,6 +135,20 @@ fn try_main() -> Result<()> {
         i
     });
 
+    if let Some(count) = samples {
+        let seed = match seed {
+            Some(seed) => seed,
+            None => Sampler::seed_from_time().map_err(|e| format_err!("{}", e.message()))?,
+        };
+
+        // so that failures can be reproduced with `--seed`.
+        println!("Sampling {} payload(s) per project (seed: {})", count, seed);
+        project.samples(Samples {
+            count: count,
+            seed: seed,
+        });
+    }
+
     it::entry(&mut project);
 
     let before = Instant::now();